@@ -6,12 +6,157 @@
  * OpenAPI spec version: 0.1.0
  */
 import { apiClient } from '../mutator';
+export interface ApiTokenResponse {
+  created_at: string;
+  /**
+   * 为空表示长期有效。
+   * @nullable
+   */
+  expires_at?: string | null;
+  id: string;
+  /** @nullable */
+  last_used_at?: string | null;
+  /** @nullable */
+  last_used_ip?: string | null;
+  name: string;
+  /**
+   * 绑定的组织；绑定后该 token 始终在此组织上下文中生效。
+   * @nullable
+   */
+  organization_id?: string | null;
+  scopes: ApiTokenScope[];
+}
+
+/**
+ * PAT 授权范围：按资源区分读写，`GET`/`HEAD` 需要 `:read`，其余方法需要 `:write`。
+ */
+export type ApiTokenScope = typeof ApiTokenScope[keyof typeof ApiTokenScope];
+
+
+export const ApiTokenScope = {
+  'users:read': 'users:read',
+  'users:write': 'users:write',
+  'settings:read': 'settings:read',
+  'settings:write': 'settings:write',
+} as const;
+
 export interface AppSettings {
   /** @minimum 0 */
   check_interval_secs: number;
   welcome_message: string;
 }
 
+export interface CompleteMfaChallengeRequest {
+  /**
+   * 6 位 TOTP 动态码，或一次性恢复码。
+   * @minLength 1
+   * @maxLength 32
+   */
+  code: string;
+  /**
+   * @minLength 1
+   * @maxLength 256
+   */
+  mfa_token: string;
+}
+
+export interface ConfirmPasswordResetRequest {
+  /**
+   * @minLength 8
+   * @maxLength 256
+   */
+  new_password: string;
+  /**
+   * 邮件链接中的 `token` 参数。
+   * @minLength 1
+   * @maxLength 256
+   */
+  token: string;
+}
+
+export type CookieSameSite = typeof CookieSameSite[keyof typeof CookieSameSite];
+
+
+export const CookieSameSite = {
+  Strict: 'Strict',
+  Lax: 'Lax',
+  None: 'None',
+} as const;
+
+/**
+ * refresh cookie 的 `Secure` 标记：`auto` 时仅 release 构建开启。
+ */
+export type CookieSecure = typeof CookieSecure[keyof typeof CookieSecure];
+
+
+export const CookieSecure = {
+  auto: 'auto',
+  always: 'always',
+  never: 'never',
+} as const;
+
+export interface CreateApiTokenRequest {
+  /**
+   * 有效期（天），1 ~ 365；不传表示长期有效。
+   * @minimum 1
+   * @maximum 365
+   * @nullable
+   */
+  expires_in_days?: number | null;
+  /**
+   * 令牌名称，同一用户下未撤销的令牌不能重名。
+   * @minLength 1
+   * @maxLength 64
+   */
+  name: string;
+  /**
+   * 绑定到当前用户所属的组织；不传表示平台上下文（可用请求头选择组织）。
+   * @nullable
+   */
+  organization_id?: string | null;
+  /** @minItems 1 */
+  scopes: ApiTokenScope[];
+}
+
+export interface CreateGroupRequest {
+  /** @maxLength 256 */
+  description?: string;
+  /**
+   * @minLength 1
+   * @maxLength 64
+   */
+  name: string;
+  /** 权限名列表（见 `GET /api/v1/permissions`），不能超出操作者自身的权限。 */
+  permissions?: string[];
+}
+
+export interface CreateOrganizationRequest {
+  /**
+   * @minLength 1
+   * @maxLength 128
+   */
+  name: string;
+  /**
+   * 组织标识，小写字母或数字开头，只能包含小写字母、数字或连字符。
+   * @minLength 1
+   * @maxLength 64
+   */
+  slug: string;
+}
+
+export interface CreateRoleRequest {
+  /** @maxLength 256 */
+  description?: string;
+  /**
+   * 小写字母开头，只能包含小写字母、数字或下划线。
+   * @minLength 1
+   * @maxLength 32
+   */
+  name: string;
+  /** 权限名列表（见 `GET /api/v1/permissions`），不能超出操作者自身的权限。 */
+  permissions?: string[];
+}
+
 export interface CreateSessionRequest {
   /**
    * @minLength 1
@@ -55,6 +200,13 @@ export interface CreateUserRequest {
    * @nullable
    */
   phone?: string | null;
+  /**
+   * 角色名（内置 `admin` | `user` | `auditor` 或自定义角色），默认 `user`；不能超出操作者自身的权限。
+   * @minLength 1
+   * @maxLength 32
+   * @nullable
+   */
+  role?: string | null;
   /**
    * @minLength 1
    * @maxLength 64
@@ -63,6 +215,34 @@ export interface CreateUserRequest {
   username?: string | null;
 }
 
+export type CreatedApiTokenResponse = ApiTokenResponse & {
+  /** 令牌明文，仅在创建时返回一次；使用方式：`Authorization: Bearer <token>`。 */
+  token: string;
+};
+
+export interface DeleteCurrentUserRequest {
+  /**
+   * 当前密码，用于确认注销。
+   * @minLength 1
+   * @maxLength 256
+   */
+  password: string;
+}
+
+export interface DisableTotpRequest {
+  /**
+   * 6 位 TOTP 动态码，或一次性恢复码。
+   * @minLength 1
+   * @maxLength 32
+   */
+  code: string;
+  /**
+   * @minLength 1
+   * @maxLength 256
+   */
+  current_password: string;
+}
+
 /**
  * 失败时的统一错误体（与 `AppError` 的序列化保持一致）。
  */
@@ -74,11 +254,143 @@ export interface ErrorResponseBody {
   request_id: string;
 }
 
+export interface GroupMemberResponse {
+  created_at: string;
+  display_name: string;
+  email: string;
+  user_id: string;
+  /** @nullable */
+  username?: string | null;
+}
+
+export interface GroupResponse {
+  created_at: string;
+  description: string;
+  id: string;
+  member_count: number;
+  name: string;
+  /** 授予组内全部成员的权限，与成员自身角色的权限合并生效。 */
+  permissions: string[];
+  updated_at: string;
+}
+
+export interface ImportUserRowResult {
+  /** 字段级校验错误，结构同错误体的 `details`。 */
+  details?: unknown;
+  /** @nullable */
+  email?: string | null;
+  /**
+   * 失败原因；成功时为 `null`。
+   * @nullable
+   */
+  error?: string | null;
+  /**
+   * 该行在文件中的起始行号（从 1 开始）。
+   * @minimum 0
+   */
+  line: number;
+  /**
+   * 新建用户 ID；dry-run 或该行失败时为 `null`。
+   * @nullable
+   */
+  user_id?: string | null;
+}
+
+export interface ImportUsersResponse {
+  dry_run: boolean;
+  /** @minimum 0 */
+  failed: number;
+  rows: ImportUserRowResult[];
+  /**
+   * 校验通过的行数；非 dry-run 时即实际创建的用户数。
+   * @minimum 0
+   */
+  succeeded: number;
+  /**
+   * 数据行总数（不含 CSV 表头与空行）。
+   * @minimum 0
+   */
+  total: number;
+}
+
 export interface IntegrationsSettings {
   example_api_base: string;
   example_api_key_is_set: boolean;
 }
 
+/**
+ * JWK Set（RFC 7517），供其他服务校验本服务签发的 access token。
+ */
+export interface JwksResponse {
+  keys: PublicJwk[];
+}
+
+/**
+ * 可配置的 JWT 签名算法（`security.jwt_algorithm`）。
+ */
+export type JwtAlgorithm = typeof JwtAlgorithm[keyof typeof JwtAlgorithm];
+
+
+export const JwtAlgorithm = {
+  HS256: 'HS256',
+  EdDSA: 'EdDSA',
+  RS256: 'RS256',
+} as const;
+
+export interface JwtKeyResponse {
+  algorithm: string;
+  /** @nullable */
+  created_at?: string | null;
+  /**
+   * 退役密钥的校验截止时间；过期后使用该密钥签发的 token 全部失效。
+   * @nullable
+   */
+  expires_at?: string | null;
+  /** 是否为当前签名密钥。 */
+  is_active: boolean;
+  kid: string;
+}
+
+/**
+ * 账号已启用两步验证时，密码校验通过后返回的 MFA 挑战。
+ */
+export interface MfaChallengeResponse {
+  /** @minimum 0 */
+  expires_in: number;
+  /** 可用的第二因素：`totp`、`recovery_code`。 */
+  methods: string[];
+  /** 调用 `POST /api/v1/sessions/mfa` 时原样提交。 */
+  mfa_token: string;
+}
+
+export interface MyOrganizationResponse {
+  id: string;
+  name: string;
+  role: string;
+  slug: string;
+}
+
+export interface OrganizationMemberResponse {
+  created_at: string;
+  display_name: string;
+  email: string;
+  /** 组织内角色，只有组织级权限在该组织上下文中生效。 */
+  role: string;
+  updated_at: string;
+  user_id: string;
+  /** @nullable */
+  username?: string | null;
+}
+
+export interface OrganizationResponse {
+  created_at: string;
+  id: string;
+  member_count: number;
+  name: string;
+  slug: string;
+  updated_at: string;
+}
+
 export interface PatchAppSettings {
   /**
    * @minimum 10
@@ -108,6 +420,7 @@ export interface PatchCurrentUserPasswordRequest {
 
 export interface PatchCurrentUserRequest {
   /**
+   * 传 `null` 或空字符串清空头像。
    * @minLength 1
    * @maxLength 2048
    * @nullable
@@ -126,6 +439,7 @@ export interface PatchCurrentUserRequest {
    */
   email?: string | null;
   /**
+   * 传 `null` 或空字符串清空手机号。
    * @minLength 1
    * @maxLength 32
    * @nullable
@@ -133,6 +447,25 @@ export interface PatchCurrentUserRequest {
   phone?: string | null;
 }
 
+export interface PatchGroupRequest {
+  /**
+   * @maxLength 256
+   * @nullable
+   */
+  description?: string | null;
+  /**
+   * @minLength 1
+   * @maxLength 64
+   * @nullable
+   */
+  name?: string | null;
+  /**
+   * 整体替换用户组的权限列表。
+   * @nullable
+   */
+  permissions?: string[] | null;
+}
+
 export interface PatchIntegrationsSettings {
   /**
    * @minLength 1
@@ -149,13 +482,116 @@ export interface PatchIntegrationsSettings {
   example_api_key?: string | null;
 }
 
+export interface PatchOrganizationRequest {
+  /**
+   * @minLength 1
+   * @maxLength 128
+   * @nullable
+   */
+  name?: string | null;
+}
+
+export interface PatchRoleRequest {
+  /**
+   * @maxLength 256
+   * @nullable
+   */
+  description?: string | null;
+  /**
+   * 整体替换角色的权限列表。
+   * @nullable
+   */
+  permissions?: string[] | null;
+}
+
+export interface PatchSecuritySettings {
+  /**
+   * 60 ~ 86400 秒。
+   * @minimum 60
+   * @maximum 86400
+   * @nullable
+   */
+  access_token_ttl_secs?: number | null;
+  jwt_algorithm?: null | JwtAlgorithm;
+  /**
+   * @minimum 1
+   * @nullable
+   */
+  login_backoff_after_failures?: number | null;
+  /**
+   * 1 ~ 2592000 秒（30 天）。
+   * @minimum 1
+   * @maximum 2592000
+   * @nullable
+   */
+  login_backoff_base_secs?: number | null;
+  /**
+   * 1 ~ 2592000 秒（30 天）。
+   * @minimum 1
+   * @maximum 2592000
+   * @nullable
+   */
+  login_backoff_max_secs?: number | null;
+  /**
+   * 1 ~ 2592000 秒（30 天）。
+   * @minimum 1
+   * @maximum 2592000
+   * @nullable
+   */
+  login_failure_window_secs?: number | null;
+  /**
+   * @minimum 1
+   * @nullable
+   */
+  login_ip_failure_threshold?: number | null;
+  /**
+   * 1 ~ 2592000 秒（30 天）。
+   * @minimum 1
+   * @maximum 2592000
+   * @nullable
+   */
+  login_lockout_secs?: number | null;
+  /**
+   * @minimum 1
+   * @nullable
+   */
+  login_lockout_threshold?: number | null;
+  /** @nullable */
+  mfa_required_for_admin?: boolean | null;
+  /**
+   * 传空字符串表示清除 `Domain`（host-only cookie）。
+   * @maxLength 253
+   * @nullable
+   */
+  refresh_cookie_domain?: string | null;
+  /**
+   * 必须以 `/` 开头并覆盖 `/api/v1/sessions/refresh`。
+   * @minLength 1
+   * @maxLength 256
+   * @nullable
+   */
+  refresh_cookie_path?: string | null;
+  refresh_cookie_same_site?: null | CookieSameSite;
+  refresh_cookie_secure?: null | CookieSecure;
+  /**
+   * 必须大于 `access_token_ttl_secs`，最长 365 天。
+   * @minimum 61
+   * @maximum 31536000
+   * @nullable
+   */
+  refresh_token_ttl_secs?: number | null;
+}
+
 export interface PatchSettingsRequest {
   app?: null | PatchAppSettings;
   integrations?: null | PatchIntegrationsSettings;
+  security?: null | PatchSecuritySettings;
+  users?: null | PatchUsersSettings;
 }
 
 export interface PatchUserRequest {
   /**
+   * 传 `null` 或空字符串清空头像。
    * @minLength 1
    * @maxLength 2048
    * @nullable
@@ -175,14 +611,27 @@ export interface PatchUserRequest {
   email?: string | null;
   /** @nullable */
   is_active?: boolean | null;
-  metadata?: unknown;
   /**
+   * 按 RFC 7396 与现有 metadata 深度合并：值为 `null` 的键被删除，传 `null` 清空为 `{}`。
+   * @nullable
+   */
+  metadata?: unknown | null;
+  /**
+   * 传 `null` 或空字符串清空手机号。
    * @minLength 1
    * @maxLength 32
    * @nullable
    */
   phone?: string | null;
   /**
+   * 角色名；不能超出操作者自身的权限，变更后该用户已签发的 access token 立即失效。
+   * @minLength 1
+   * @maxLength 32
+   * @nullable
+   */
+  role?: string | null;
+  /**
+   * 传 `null` 或空字符串清空用户名。
    * @minLength 1
    * @maxLength 64
    * @nullable
@@ -190,89 +639,1339 @@ export interface PatchUserRequest {
   username?: string | null;
 }
 
-export interface SettingsResponse {
-  app: AppSettings;
-  integrations: IntegrationsSettings;
-}
+export interface PatchUsersSettings {
+  /**
+   * 0 ~ 3650 天；`0` 表示不自动清除。
+   * @minimum 0
+   * @maximum 3650
+   * @nullable
+   */
+  purge_after_days?: number | null;
+}
+
+export interface PermissionResponse {
+  description: string;
+  name: string;
+}
+
+export interface PersonalDataApiToken {
+  created_at: string;
+  /** @nullable */
+  expires_at?: string | null;
+  id: string;
+  /** @nullable */
+  last_used_at?: string | null;
+  /** @nullable */
+  last_used_ip?: string | null;
+  /** @nullable */
+  last_used_user_agent?: string | null;
+  name: string;
+  /** @nullable */
+  revoked_at?: string | null;
+  scopes: string[];
+}
+
+/**
+ * 个人数据归档：我们保存的与当前用户相关的全部数据。
+ */
+export interface PersonalDataExport {
+  /** API token 元数据，不含密钥。 */
+  api_tokens: PersonalDataApiToken[];
+  exported_at: string;
+  groups: PersonalDataGroup[];
+  mfa_enabled: boolean;
+  organizations: PersonalDataOrganization[];
+  profile: UserDetailResponse;
+  security_events: PersonalDataSecurityEvent[];
+  /** 全部会话（含已吊销、已过期及模拟登录会话）。 */
+  sessions: PersonalDataSession[];
+}
+
+export interface PersonalDataGroup {
+  group_id: string;
+  joined_at: string;
+  name: string;
+}
+
+export interface PersonalDataOrganization {
+  joined_at: string;
+  name: string;
+  organization_id: string;
+  role: string;
+  slug: string;
+}
+
+export interface PersonalDataSecurityEvent {
+  /** @nullable */
+  client_ip?: string | null;
+  created_at: string;
+  details: unknown;
+  event_type: string;
+  /** @nullable */
+  session_id?: string | null;
+  /** @nullable */
+  user_agent?: string | null;
+}
+
+export interface PersonalDataSession {
+  created_at: string;
+  /** @nullable */
+  created_ip?: string | null;
+  /** @nullable */
+  created_user_agent?: string | null;
+  expires_at: string;
+  id: string;
+  /**
+   * 模拟登录会话的操作者；用户本人登录时为 `null`。
+   * @nullable
+   */
+  impersonator_id?: string | null;
+  /** @nullable */
+  last_ip?: string | null;
+  /** @nullable */
+  last_refreshed_at?: string | null;
+  /** @nullable */
+  last_user_agent?: string | null;
+  /** @nullable */
+  revoked_at?: string | null;
+  /** @nullable */
+  revoked_reason?: string | null;
+}
+
+/**
+ * 非对称签名密钥的公钥（RFC 7517 JWK），通过 `/.well-known/jwks.json` 公开。
+ */
+export interface PublicJwk {
+  alg: string;
+  /**
+   * Ed25519 曲线名（`Ed25519`）。
+   * @nullable
+   */
+  crv?: string | null;
+  /**
+   * RSA 公钥指数（base64url）。
+   * @nullable
+   */
+  e?: string | null;
+  kid: string;
+  /** `OKP`（Ed25519）或 `RSA`。 */
+  kty: string;
+  /**
+   * RSA 模数（base64url）。
+   * @nullable
+   */
+  n?: string | null;
+  use: string;
+  /**
+   * Ed25519 公钥（base64url）。
+   * @nullable
+   */
+  x?: string | null;
+}
+
+export interface PutOrganizationMemberRequest {
+  /**
+   * 组织内角色，不能超出操作者自身的权限。
+   * @minLength 1
+   * @maxLength 32
+   */
+  role: string;
+}
+
+export interface RequestPasswordResetRequest {
+  /**
+   * 账号绑定的邮箱（不区分大小写）。
+   * @minLength 1
+   * @maxLength 320
+   */
+  email: string;
+}
+
+export interface RoleResponse {
+  created_at: string;
+  description: string;
+  /** 内置角色（`admin` / `user` / `auditor`）不可修改或删除。 */
+  is_builtin: boolean;
+  name: string;
+  permissions: string[];
+  updated_at: string;
+}
+
+export interface RotateJwtKeyRequest {
+  /**
+   * 旧签名密钥的校验宽限期（秒），默认 3600；传 0 表示立即作废旧密钥签发的全部 access token。
+   * @minimum 0
+   * @maximum 2592000
+   * @nullable
+   */
+  grace_period_secs?: number | null;
+}
+
+export interface SecuritySettings {
+  /** @minimum 0 */
+  access_token_ttl_secs: number;
+  /** JWT 签名算法；修改后会自动轮换签名密钥。 */
+  jwt_algorithm: JwtAlgorithm;
+  /** @minimum 0 */
+  login_backoff_after_failures: number;
+  /** @minimum 0 */
+  login_backoff_base_secs: number;
+  /** @minimum 0 */
+  login_backoff_max_secs: number;
+  /** @minimum 0 */
+  login_failure_window_secs: number;
+  /** @minimum 0 */
+  login_ip_failure_threshold: number;
+  /** @minimum 0 */
+  login_lockout_secs: number;
+  /** @minimum 0 */
+  login_lockout_threshold: number;
+  /** 是否要求 admin 角色必须启用两步验证（TOTP）。 */
+  mfa_required_for_admin: boolean;
+  /**
+   * 为空表示 host-only cookie。
+   * @nullable
+   */
+  refresh_cookie_domain?: string | null;
+  refresh_cookie_path: string;
+  refresh_cookie_same_site: CookieSameSite;
+  /** `auto` 时仅 release 构建设置 `Secure`。 */
+  refresh_cookie_secure: CookieSecure;
+  /** @minimum 0 */
+  refresh_token_ttl_secs: number;
+}
+
+export interface SessionResponse {
+  created_at: string;
+  /**
+   * 登录时的客户端 IP。
+   * @nullable
+   */
+  created_ip?: string | null;
+  /**
+   * 登录时的 User-Agent。
+   * @nullable
+   */
+  created_user_agent?: string | null;
+  expires_at: string;
+  id: string;
+  /**
+   * 模拟登录会话的操作者；用户本人登录时为 `null`。
+   * @nullable
+   */
+  impersonator_id?: string | null;
+  /** 是否为当前 access token 所属会话。 */
+  is_current: boolean;
+  /**
+   * 最近一次登录/刷新时的客户端 IP。
+   * @nullable
+   */
+  last_ip?: string | null;
+  /**
+   * 最近一次刷新时间；从未刷新过时为 `null`。
+   * @nullable
+   */
+  last_refreshed_at?: string | null;
+  /**
+   * 最近一次登录/刷新时的 User-Agent。
+   * @nullable
+   */
+  last_user_agent?: string | null;
+  updated_at: string;
+}
+
+export interface SetUserPasswordRequest {
+  /** 是否要求用户下次登录时修改密码，默认 `false`。 */
+  must_change?: boolean;
+  /**
+   * @minLength 8
+   * @maxLength 256
+   */
+  password: string;
+}
+
+export interface SettingsResponse {
+  app: AppSettings;
+  integrations: IntegrationsSettings;
+  security?: null | SecuritySettings;
+  users: UsersSettings;
+}
+
+export interface StartImpersonationRequest {
+  /**
+   * 模拟登录的原因（如工单号），写入审计事件。
+   * @minLength 1
+   * @maxLength 256
+   */
+  reason: string;
+  user_id: string;
+}
+
+export interface StartTotpEnrollmentRequest {
+  /**
+   * @minLength 1
+   * @maxLength 256
+   */
+  current_password: string;
+}
+
+export interface TemporaryPasswordResponse {
+  /** 临时密码明文，仅在本次响应中返回；用户使用后必须修改密码。 */
+  temporary_password: string;
+}
+
+export interface TotpCodeRequest {
+  /**
+   * 6 位 TOTP 动态码。
+   * @minLength 1
+   * @maxLength 32
+   */
+  code: string;
+}
+
+export interface TotpEnrollmentResponse {
+  /** `otpauth://` URI，前端可渲染为二维码。 */
+  otpauth_uri: string;
+  /** Base32 编码的 TOTP 密钥，供无法扫码时手动输入。 */
+  secret: string;
+}
+
+export interface TotpRecoveryCodesResponse {
+  /** 一次性恢复码，仅在本次响应中返回明文。 */
+  recovery_codes: string[];
+}
+
+export interface TotpStatusResponse {
+  /** 是否已启用（完成绑定确认）。 */
+  enabled: boolean;
+  /** 是否存在尚未确认的待绑定密钥。 */
+  pending: boolean;
+  /** 剩余可用的恢复码数量。 */
+  recovery_codes_remaining: number;
+  /** 当前策略是否要求该用户必须启用两步验证。 */
+  required: boolean;
+}
+
+/**
+ * 管理端用户详情：在 [`UserResponse`] 基础上附带删除状态与登录信息。
+ */
+export type UserDetailResponse = UserResponse & {
+  /** 未吊销且未过期的会话数。 */
+  active_session_count: number;
+  /**
+   * 逻辑删除时间；未删除时为 `null`。
+   * @nullable
+   */
+  deleted_at?: string | null;
+  /**
+   * 最近一次登录时间（模拟登录不计入）；从未登录时为 `null`。
+   * @nullable
+   */
+  last_login_at?: string | null;
+};
+
+export interface UserResponse {
+  /** @nullable */
+  avatar_url?: string | null;
+  created_at: string;
+  display_name: string;
+  email: string;
+  id: string;
+  is_active: boolean;
+  metadata: unknown;
+  /** @nullable */
+  phone?: string | null;
+  /** 角色名（内置 `admin` | `user` | `auditor` 或自定义角色）。 */
+  role: string;
+  updated_at: string;
+  /** @nullable */
+  username?: string | null;
+}
+
+export interface UsersSettings {
+  /**
+   * 逻辑删除的用户保留天数，到期后物理删除；`0` 表示不自动清除。
+   * @minimum 0
+   */
+  purge_after_days: number;
+}
+
+export type PatchSettingsHandlerHeaders = {
+/**
+ * 上次读取到的 `ETag`；不一致时返回 412
+ * @nullable
+ */
+'If-Match'?: string | null;
+};
+
+export type GetUsersHandlerParams = {
+/**
+ * 是否包含已逻辑删除用户，默认 `false`。
+ */
+include_deleted?: boolean;
+/**
+ * 页码，从 1 开始，默认 1。
+ * @minimum 1
+ */
+page?: number;
+/**
+ * 每页条数，默认 50，最大 200。
+ * @minimum 1
+ * @maximum 200
+ */
+per_page?: number;
+/**
+ * 模糊搜索用户名、显示名、邮箱和手机号（不区分大小写）。
+ * @maxLength 128
+ */
+q?: string;
+/**
+ * 按启用状态过滤。
+ */
+is_active?: boolean;
+/**
+ * 按角色过滤；组织上下文中匹配组织内角色。
+ */
+role?: string;
+/**
+ * 只返回该时间及之后创建的用户（RFC 3339）。
+ */
+created_from?: string;
+/**
+ * 只返回该时间之前创建的用户（RFC 3339）。
+ */
+created_to?: string;
+/**
+ * 排序字段，`-` 前缀表示倒序，默认 `-created_at`。
+ */
+sort?: GetUsersHandlerSort;
+};
+
+export type GetUsersHandlerSort = typeof GetUsersHandlerSort[keyof typeof GetUsersHandlerSort];
+
+
+export const GetUsersHandlerSort = {
+  created_at: 'created_at',
+  '-created_at': '-created_at',
+  username: 'username',
+  '-username': '-username',
+  display_name: 'display_name',
+  '-display_name': '-display_name',
+  email: 'email',
+  '-email': '-email',
+} as const;
+
+export type ExportUsersHandlerParams = {
+/**
+ * 导出格式，默认 `csv`。
+ */
+format?: ExportUsersHandlerFormat;
+/**
+ * 逗号分隔的导出列，默认全部：`id`、`username`、`display_name`、`email`、`phone`、`avatar_url`、
+ * `role`、`is_active`、`metadata`、`created_at`、`updated_at`、`deleted_at`、`last_login_at`。
+ */
+columns?: string;
+/**
+ * 是否包含已逻辑删除用户，默认 `false`。
+ */
+include_deleted?: boolean;
+/**
+ * 同用户列表的 `q`。
+ * @maxLength 128
+ */
+q?: string;
+is_active?: boolean;
+role?: string;
+created_from?: string;
+created_to?: string;
+sort?: ExportUsersHandlerSort;
+};
+
+export type ExportUsersHandlerFormat = typeof ExportUsersHandlerFormat[keyof typeof ExportUsersHandlerFormat];
+
+
+export const ExportUsersHandlerFormat = {
+  csv: 'csv',
+  ndjson: 'ndjson',
+} as const;
+
+export type ExportUsersHandlerSort = typeof ExportUsersHandlerSort[keyof typeof ExportUsersHandlerSort];
+
+
+export const ExportUsersHandlerSort = {
+  created_at: 'created_at',
+  '-created_at': '-created_at',
+  username: 'username',
+  '-username': '-username',
+  display_name: 'display_name',
+  '-display_name': '-display_name',
+  email: 'email',
+  '-email': '-email',
+} as const;
+
+export type ImportUsersHandlerParams = {
+/**
+ * 只校验不写入，默认 `false`。
+ */
+dry_run?: boolean;
+};
+
+export type PatchCurrentUserHandlerHeaders = {
+/**
+ * 上次读取到的 `ETag`；不一致时返回 412
+ * @nullable
+ */
+'If-Match'?: string | null;
+};
+
+export type PatchUserHandlerHeaders = {
+/**
+ * 上次读取到的 `ETag`；不一致时返回 412
+ * @nullable
+ */
+'If-Match'?: string | null;
+};
+
+export const getJwksHandlerUrl = () => {
+
+
+  
+
+  return `/.well-known/jwks.json`
+}
+
+export const jwksHandler = async ( options?: RequestInit): Promise<JwksResponse> => {
+  
+  return apiClient<JwksResponse>(getJwksHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getListGroupsHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/groups`
+}
+
+export const listGroupsHandler = async ( options?: RequestInit): Promise<GroupResponse[]> => {
+  
+  return apiClient<GroupResponse[]>(getListGroupsHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getCreateGroupHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/groups`
+}
+
+export const createGroupHandler = async (createGroupRequest: CreateGroupRequest, options?: RequestInit): Promise<GroupResponse> => {
+  
+  return apiClient<GroupResponse>(getCreateGroupHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      createGroupRequest,)
+  }
+);}
+
+
+
+export const getDeleteGroupHandlerUrl = (groupId: string,) => {
+
+
+  
+
+  return `/api/v1/groups/${groupId}`
+}
+
+export const deleteGroupHandler = async (groupId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteGroupHandlerUrl(groupId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getPatchGroupHandlerUrl = (groupId: string,) => {
+
+
+  
+
+  return `/api/v1/groups/${groupId}`
+}
+
+export const patchGroupHandler = async (groupId: string,
+    patchGroupRequest: PatchGroupRequest, options?: RequestInit): Promise<GroupResponse> => {
+  
+  return apiClient<GroupResponse>(getPatchGroupHandlerUrl(groupId),
+  {      
+    ...options,
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      patchGroupRequest,)
+  }
+);}
+
+
+
+export const getListGroupMembersHandlerUrl = (groupId: string,) => {
+
+
+  
+
+  return `/api/v1/groups/${groupId}/members`
+}
+
+export const listGroupMembersHandler = async (groupId: string, options?: RequestInit): Promise<GroupMemberResponse[]> => {
+  
+  return apiClient<GroupMemberResponse[]>(getListGroupMembersHandlerUrl(groupId),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getPutGroupMemberHandlerUrl = (groupId: string,
+    userId: string,) => {
+
+
+  
+
+  return `/api/v1/groups/${groupId}/members/${userId}`
+}
+
+export const putGroupMemberHandler = async (groupId: string,
+    userId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getPutGroupMemberHandlerUrl(groupId,userId),
+  {      
+    ...options,
+    method: 'PUT'
+    
+    
+  }
+);}
+
+
+
+export const getDeleteGroupMemberHandlerUrl = (groupId: string,
+    userId: string,) => {
+
+
+  
+
+  return `/api/v1/groups/${groupId}/members/${userId}`
+}
+
+export const deleteGroupMemberHandler = async (groupId: string,
+    userId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteGroupMemberHandlerUrl(groupId,userId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getListOrganizationsHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/organizations`
+}
+
+export const listOrganizationsHandler = async ( options?: RequestInit): Promise<OrganizationResponse[]> => {
+  
+  return apiClient<OrganizationResponse[]>(getListOrganizationsHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getCreateOrganizationHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/organizations`
+}
+
+export const createOrganizationHandler = async (createOrganizationRequest: CreateOrganizationRequest, options?: RequestInit): Promise<OrganizationResponse> => {
+  
+  return apiClient<OrganizationResponse>(getCreateOrganizationHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      createOrganizationRequest,)
+  }
+);}
+
+
+
+export const getDeleteOrganizationHandlerUrl = (organizationId: string,) => {
+
+
+  
+
+  return `/api/v1/organizations/${organizationId}`
+}
+
+export const deleteOrganizationHandler = async (organizationId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteOrganizationHandlerUrl(organizationId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getPatchOrganizationHandlerUrl = (organizationId: string,) => {
+
+
+  
+
+  return `/api/v1/organizations/${organizationId}`
+}
+
+export const patchOrganizationHandler = async (organizationId: string,
+    patchOrganizationRequest: PatchOrganizationRequest, options?: RequestInit): Promise<OrganizationResponse> => {
+  
+  return apiClient<OrganizationResponse>(getPatchOrganizationHandlerUrl(organizationId),
+  {      
+    ...options,
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      patchOrganizationRequest,)
+  }
+);}
+
+
+
+export const getListOrganizationMembersHandlerUrl = (organizationId: string,) => {
+
+
+  
+
+  return `/api/v1/organizations/${organizationId}/members`
+}
+
+export const listOrganizationMembersHandler = async (organizationId: string, options?: RequestInit): Promise<OrganizationMemberResponse[]> => {
+  
+  return apiClient<OrganizationMemberResponse[]>(getListOrganizationMembersHandlerUrl(organizationId),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getPutOrganizationMemberHandlerUrl = (organizationId: string,
+    userId: string,) => {
+
+
+  
+
+  return `/api/v1/organizations/${organizationId}/members/${userId}`
+}
+
+export const putOrganizationMemberHandler = async (organizationId: string,
+    userId: string,
+    putOrganizationMemberRequest: PutOrganizationMemberRequest, options?: RequestInit): Promise<OrganizationMemberResponse> => {
+  
+  return apiClient<OrganizationMemberResponse>(getPutOrganizationMemberHandlerUrl(organizationId,userId),
+  {      
+    ...options,
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      putOrganizationMemberRequest,)
+  }
+);}
+
+
+
+export const getDeleteOrganizationMemberHandlerUrl = (organizationId: string,
+    userId: string,) => {
+
+
+  
+
+  return `/api/v1/organizations/${organizationId}/members/${userId}`
+}
+
+export const deleteOrganizationMemberHandler = async (organizationId: string,
+    userId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteOrganizationMemberHandlerUrl(organizationId,userId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getListPermissionsHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/permissions`
+}
+
+export const listPermissionsHandler = async ( options?: RequestInit): Promise<PermissionResponse[]> => {
+  
+  return apiClient<PermissionResponse[]>(getListPermissionsHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getListRolesHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/roles`
+}
+
+export const listRolesHandler = async ( options?: RequestInit): Promise<RoleResponse[]> => {
+  
+  return apiClient<RoleResponse[]>(getListRolesHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getCreateRoleHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/roles`
+}
+
+export const createRoleHandler = async (createRoleRequest: CreateRoleRequest, options?: RequestInit): Promise<RoleResponse> => {
+  
+  return apiClient<RoleResponse>(getCreateRoleHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      createRoleRequest,)
+  }
+);}
+
+
+
+export const getDeleteRoleHandlerUrl = (role: string,) => {
+
+
+  
+
+  return `/api/v1/roles/${role}`
+}
+
+export const deleteRoleHandler = async (role: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteRoleHandlerUrl(role),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getPatchRoleHandlerUrl = (role: string,) => {
+
+
+  
+
+  return `/api/v1/roles/${role}`
+}
+
+export const patchRoleHandler = async (role: string,
+    patchRoleRequest: PatchRoleRequest, options?: RequestInit): Promise<RoleResponse> => {
+  
+  return apiClient<RoleResponse>(getPatchRoleHandlerUrl(role),
+  {      
+    ...options,
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      patchRoleRequest,)
+  }
+);}
+
+
+
+export const getListJwtKeysHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/jwt-keys`
+}
+
+export const listJwtKeysHandler = async ( options?: RequestInit): Promise<JwtKeyResponse[]> => {
+  
+  return apiClient<JwtKeyResponse[]>(getListJwtKeysHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getRotateJwtKeyHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/jwt-keys/rotate`
+}
+
+export const rotateJwtKeyHandler = async (rotateJwtKeyRequest: RotateJwtKeyRequest, options?: RequestInit): Promise<JwtKeyResponse[]> => {
+  
+  return apiClient<JwtKeyResponse[]>(getRotateJwtKeyHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      rotateJwtKeyRequest,)
+  }
+);}
+
+
+
+export const getPatchCurrentUserPasswordHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/password`
+}
+
+export const patchCurrentUserPasswordHandler = async (patchCurrentUserPasswordRequest: PatchCurrentUserPasswordRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getPatchCurrentUserPasswordHandlerUrl(),
+  {      
+    ...options,
+    method: 'PATCH',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      patchCurrentUserPasswordRequest,)
+  }
+);}
+
+
+
+export const getRequestPasswordResetHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/password-reset`
+}
+
+export const requestPasswordResetHandler = async (requestPasswordResetRequest: RequestPasswordResetRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getRequestPasswordResetHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      requestPasswordResetRequest,)
+  }
+);}
+
+
+
+export const getConfirmPasswordResetHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/password-reset/confirm`
+}
+
+export const confirmPasswordResetHandler = async (confirmPasswordResetRequest: ConfirmPasswordResetRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getConfirmPasswordResetHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      confirmPasswordResetRequest,)
+  }
+);}
+
+
+
+export const getListApiTokensHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/tokens`
+}
+
+export const listApiTokensHandler = async ( options?: RequestInit): Promise<ApiTokenResponse[]> => {
+  
+  return apiClient<ApiTokenResponse[]>(getListApiTokensHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getCreateApiTokenHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/tokens`
+}
+
+export const createApiTokenHandler = async (createApiTokenRequest: CreateApiTokenRequest, options?: RequestInit): Promise<CreatedApiTokenResponse> => {
+  
+  return apiClient<CreatedApiTokenResponse>(getCreateApiTokenHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      createApiTokenRequest,)
+  }
+);}
+
+
+
+export const getRevokeApiTokenHandlerUrl = (tokenId: string,) => {
+
+
+  
+
+  return `/api/v1/security/tokens/${tokenId}`
+}
+
+export const revokeApiTokenHandler = async (tokenId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getRevokeApiTokenHandlerUrl(tokenId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
+export const getGetTotpStatusHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/totp`
+}
+
+export const getTotpStatusHandler = async ( options?: RequestInit): Promise<TotpStatusResponse> => {
+  
+  return apiClient<TotpStatusResponse>(getGetTotpStatusHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getStartTotpEnrollmentHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/totp`
+}
+
+export const startTotpEnrollmentHandler = async (startTotpEnrollmentRequest: StartTotpEnrollmentRequest, options?: RequestInit): Promise<TotpEnrollmentResponse> => {
+  
+  return apiClient<TotpEnrollmentResponse>(getStartTotpEnrollmentHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      startTotpEnrollmentRequest,)
+  }
+);}
+
+
+
+export const getDisableTotpHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/totp`
+}
+
+export const disableTotpHandler = async (disableTotpRequest: DisableTotpRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDisableTotpHandlerUrl(),
+  {      
+    ...options,
+    method: 'DELETE',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      disableTotpRequest,)
+  }
+);}
+
+
+
+export const getConfirmTotpEnrollmentHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/totp/confirm`
+}
+
+export const confirmTotpEnrollmentHandler = async (totpCodeRequest: TotpCodeRequest, options?: RequestInit): Promise<TotpRecoveryCodesResponse> => {
+  
+  return apiClient<TotpRecoveryCodesResponse>(getConfirmTotpEnrollmentHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      totpCodeRequest,)
+  }
+);}
+
+
+
+export const getRegenerateTotpRecoveryCodesHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/security/totp/recovery-codes`
+}
+
+export const regenerateTotpRecoveryCodesHandler = async (totpCodeRequest: TotpCodeRequest, options?: RequestInit): Promise<TotpRecoveryCodesResponse> => {
+  
+  return apiClient<TotpRecoveryCodesResponse>(getRegenerateTotpRecoveryCodesHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      totpCodeRequest,)
+  }
+);}
+
+
+
+export const getListSessionsHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/sessions`
+}
+
+export const listSessionsHandler = async ( options?: RequestInit): Promise<SessionResponse[]> => {
+  
+  return apiClient<SessionResponse[]>(getListSessionsHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getCreateSessionHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/sessions`
+}
+
+export const createSessionHandler = async (createSessionRequest: CreateSessionRequest, options?: RequestInit): Promise<CreateSessionResponse | MfaChallengeResponse> => {
+  
+  return apiClient<CreateSessionResponse | MfaChallengeResponse>(getCreateSessionHandlerUrl(),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      createSessionRequest,)
+  }
+);}
+
+
+
+export const getDeleteCurrentSessionHandlerUrl = () => {
+
 
-export interface UserResponse {
-  /** @nullable */
-  avatar_url?: string | null;
-  created_at: string;
-  display_name: string;
-  email: string;
-  id: string;
-  is_active: boolean;
-  metadata: unknown;
-  /** @nullable */
-  phone?: string | null;
-  updated_at: string;
-  /** @nullable */
-  username?: string | null;
+  
+
+  return `/api/v1/sessions/current`
 }
 
-export type GetUsersHandlerParams = {
-/**
- * 是否包含已逻辑删除用户
- */
-include_deleted?: boolean;
-};
+export const deleteCurrentSessionHandler = async ( options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteCurrentSessionHandlerUrl(),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
 
-export const getPatchCurrentUserPasswordHandlerUrl = () => {
+
+
+export const getStartImpersonationHandlerUrl = () => {
 
 
   
 
-  return `/api/v1/security/password`
+  return `/api/v1/sessions/impersonation`
 }
 
-export const patchCurrentUserPasswordHandler = async (patchCurrentUserPasswordRequest: PatchCurrentUserPasswordRequest, options?: RequestInit): Promise<void> => {
+export const startImpersonationHandler = async (startImpersonationRequest: StartImpersonationRequest, options?: RequestInit): Promise<CreateSessionResponse> => {
   
-  return apiClient<void>(getPatchCurrentUserPasswordHandlerUrl(),
+  return apiClient<CreateSessionResponse>(getStartImpersonationHandlerUrl(),
   {      
     ...options,
-    method: 'PATCH',
+    method: 'POST',
     headers: { 'Content-Type': 'application/json', ...options?.headers },
     body: JSON.stringify(
-      patchCurrentUserPasswordRequest,)
+      startImpersonationRequest,)
   }
 );}
 
 
 
-export const getCreateSessionHandlerUrl = () => {
+export const getCompleteMfaChallengeHandlerUrl = () => {
 
 
   
 
-  return `/api/v1/sessions`
+  return `/api/v1/sessions/mfa`
 }
 
-export const createSessionHandler = async (createSessionRequest: CreateSessionRequest, options?: RequestInit): Promise<CreateSessionResponse> => {
+export const completeMfaChallengeHandler = async (completeMfaChallengeRequest: CompleteMfaChallengeRequest, options?: RequestInit): Promise<CreateSessionResponse> => {
   
-  return apiClient<CreateSessionResponse>(getCreateSessionHandlerUrl(),
+  return apiClient<CreateSessionResponse>(getCompleteMfaChallengeHandlerUrl(),
   {      
     ...options,
     method: 'POST',
     headers: { 'Content-Type': 'application/json', ...options?.headers },
     body: JSON.stringify(
-      createSessionRequest,)
+      completeMfaChallengeRequest,)
   }
 );}
 
 
 
-export const getDeleteCurrentSessionHandlerUrl = () => {
+export const getDeleteOtherSessionsHandlerUrl = () => {
 
 
   
 
-  return `/api/v1/sessions/current`
+  return `/api/v1/sessions/others`
 }
 
-export const deleteCurrentSessionHandler = async ( options?: RequestInit): Promise<void> => {
+export const deleteOtherSessionsHandler = async ( options?: RequestInit): Promise<void> => {
   
-  return apiClient<void>(getDeleteCurrentSessionHandlerUrl(),
+  return apiClient<void>(getDeleteOtherSessionsHandlerUrl(),
   {      
     ...options,
     method: 'DELETE'
@@ -304,6 +2003,27 @@ export const refreshSessionHandler = async ( options?: RequestInit): Promise<Cre
 
 
 
+export const getDeleteSessionHandlerUrl = (sessionId: string,) => {
+
+
+  
+
+  return `/api/v1/sessions/${sessionId}`
+}
+
+export const deleteSessionHandler = async (sessionId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteSessionHandlerUrl(sessionId),
+  {      
+    ...options,
+    method: 'DELETE'
+    
+    
+  }
+);}
+
+
+
 export const getGetSettingsHandlerUrl = () => {
 
 
@@ -333,13 +2053,14 @@ export const getPatchSettingsHandlerUrl = () => {
   return `/api/v1/settings`
 }
 
-export const patchSettingsHandler = async (patchSettingsRequest: PatchSettingsRequest, options?: RequestInit): Promise<SettingsResponse> => {
+export const patchSettingsHandler = async (patchSettingsRequest: PatchSettingsRequest,
+    headers?: PatchSettingsHandlerHeaders, options?: RequestInit): Promise<SettingsResponse> => {
   
   return apiClient<SettingsResponse>(getPatchSettingsHandlerUrl(),
   {      
     ...options,
     method: 'PATCH',
-    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    headers: { 'Content-Type': 'application/json',...headers, ...options?.headers },
     body: JSON.stringify(
       patchSettingsRequest,)
   }
@@ -397,6 +2118,64 @@ export const createUserHandler = async (createUserRequest: CreateUserRequest, op
 
 
 
+export const getExportUsersHandlerUrl = (params?: ExportUsersHandlerParams,) => {
+  const normalizedParams = new URLSearchParams();
+
+  Object.entries(params || {}).forEach(([key, value]) => {
+    
+    if (value !== undefined) {
+      normalizedParams.append(key, value === null ? 'null' : value.toString())
+    }
+  });
+
+  const stringifiedParams = normalizedParams.toString();
+
+  return stringifiedParams.length > 0 ? `/api/v1/users/export?${stringifiedParams}` : `/api/v1/users/export`
+}
+
+export const exportUsersHandler = async (params?: ExportUsersHandlerParams, options?: RequestInit): Promise<string> => {
+  
+  return apiClient<string>(getExportUsersHandlerUrl(params),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getImportUsersHandlerUrl = (params?: ImportUsersHandlerParams,) => {
+  const normalizedParams = new URLSearchParams();
+
+  Object.entries(params || {}).forEach(([key, value]) => {
+    
+    if (value !== undefined) {
+      normalizedParams.append(key, value === null ? 'null' : value.toString())
+    }
+  });
+
+  const stringifiedParams = normalizedParams.toString();
+
+  return stringifiedParams.length > 0 ? `/api/v1/users/import?${stringifiedParams}` : `/api/v1/users/import`
+}
+
+export const importUsersHandler = async (importUsersHandlerBody: string,
+    params?: ImportUsersHandlerParams, options?: RequestInit): Promise<ImportUsersResponse> => {
+  
+  return apiClient<ImportUsersResponse>(getImportUsersHandlerUrl(params),
+  {      
+    ...options,
+    method: 'POST',
+    headers: { 'Content-Type': 'application/x-ndjson', ...options?.headers },
+    body: JSON.stringify(
+      importUsersHandlerBody,)
+  }
+);}
+
+
+
 export const getGetCurrentUserHandlerUrl = () => {
 
 
@@ -418,6 +2197,28 @@ export const getCurrentUserHandler = async ( options?: RequestInit): Promise<Use
 
 
 
+export const getDeleteCurrentUserHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/users/me`
+}
+
+export const deleteCurrentUserHandler = async (deleteCurrentUserRequest: DeleteCurrentUserRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getDeleteCurrentUserHandlerUrl(),
+  {      
+    ...options,
+    method: 'DELETE',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      deleteCurrentUserRequest,)
+  }
+);}
+
+
+
 export const getPatchCurrentUserHandlerUrl = () => {
 
 
@@ -426,13 +2227,14 @@ export const getPatchCurrentUserHandlerUrl = () => {
   return `/api/v1/users/me`
 }
 
-export const patchCurrentUserHandler = async (patchCurrentUserRequest: PatchCurrentUserRequest, options?: RequestInit): Promise<UserResponse> => {
+export const patchCurrentUserHandler = async (patchCurrentUserRequest: PatchCurrentUserRequest,
+    headers?: PatchCurrentUserHandlerHeaders, options?: RequestInit): Promise<UserResponse> => {
   
   return apiClient<UserResponse>(getPatchCurrentUserHandlerUrl(),
   {      
     ...options,
     method: 'PATCH',
-    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    headers: { 'Content-Type': 'application/json',...headers, ...options?.headers },
     body: JSON.stringify(
       patchCurrentUserRequest,)
   }
@@ -440,6 +2242,69 @@ export const patchCurrentUserHandler = async (patchCurrentUserRequest: PatchCurr
 
 
 
+export const getExportCurrentUserHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/users/me/export`
+}
+
+export const exportCurrentUserHandler = async ( options?: RequestInit): Promise<PersonalDataExport> => {
+  
+  return apiClient<PersonalDataExport>(getExportCurrentUserHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getListMyOrganizationsHandlerUrl = () => {
+
+
+  
+
+  return `/api/v1/users/me/organizations`
+}
+
+export const listMyOrganizationsHandler = async ( options?: RequestInit): Promise<MyOrganizationResponse[]> => {
+  
+  return apiClient<MyOrganizationResponse[]>(getListMyOrganizationsHandlerUrl(),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
+export const getGetUserHandlerUrl = (userId: string,) => {
+
+
+  
+
+  return `/api/v1/users/${userId}`
+}
+
+export const getUserHandler = async (userId: string, options?: RequestInit): Promise<UserDetailResponse> => {
+  
+  return apiClient<UserDetailResponse>(getGetUserHandlerUrl(userId),
+  {      
+    ...options,
+    method: 'GET'
+    
+    
+  }
+);}
+
+
+
 export const getDeleteUserHandlerUrl = (userId: string,) => {
 
 
@@ -470,13 +2335,14 @@ export const getPatchUserHandlerUrl = (userId: string,) => {
 }
 
 export const patchUserHandler = async (userId: string,
-    patchUserRequest: PatchUserRequest, options?: RequestInit): Promise<UserResponse> => {
+    patchUserRequest: PatchUserRequest,
+    headers?: PatchUserHandlerHeaders, options?: RequestInit): Promise<UserResponse> => {
   
   return apiClient<UserResponse>(getPatchUserHandlerUrl(userId),
   {      
     ...options,
     method: 'PATCH',
-    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    headers: { 'Content-Type': 'application/json',...headers, ...options?.headers },
     body: JSON.stringify(
       patchUserRequest,)
   }
@@ -484,6 +2350,50 @@ export const patchUserHandler = async (userId: string,
 
 
 
+export const getSetUserPasswordHandlerUrl = (userId: string,) => {
+
+
+  
+
+  return `/api/v1/users/${userId}/password`
+}
+
+export const setUserPasswordHandler = async (userId: string,
+    setUserPasswordRequest: SetUserPasswordRequest, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getSetUserPasswordHandlerUrl(userId),
+  {      
+    ...options,
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json', ...options?.headers },
+    body: JSON.stringify(
+      setUserPasswordRequest,)
+  }
+);}
+
+
+
+export const getPurgeUserHandlerUrl = (userId: string,) => {
+
+
+  
+
+  return `/api/v1/users/${userId}/purge`
+}
+
+export const purgeUserHandler = async (userId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getPurgeUserHandlerUrl(userId),
+  {      
+    ...options,
+    method: 'POST'
+    
+    
+  }
+);}
+
+
+
 export const getRestoreUserHandlerUrl = (userId: string,) => {
 
 
@@ -502,3 +2412,45 @@ export const restoreUserHandler = async (userId: string, options?: RequestInit):
     
   }
 );}
+
+
+
+export const getIssueTemporaryPasswordHandlerUrl = (userId: string,) => {
+
+
+  
+
+  return `/api/v1/users/${userId}/temporary-password`
+}
+
+export const issueTemporaryPasswordHandler = async (userId: string, options?: RequestInit): Promise<TemporaryPasswordResponse> => {
+  
+  return apiClient<TemporaryPasswordResponse>(getIssueTemporaryPasswordHandlerUrl(userId),
+  {      
+    ...options,
+    method: 'POST'
+    
+    
+  }
+);}
+
+
+
+export const getUnlockUserHandlerUrl = (userId: string,) => {
+
+
+  
+
+  return `/api/v1/users/${userId}/unlock`
+}
+
+export const unlockUserHandler = async (userId: string, options?: RequestInit): Promise<void> => {
+  
+  return apiClient<void>(getUnlockUserHandlerUrl(userId),
+  {      
+    ...options,
+    method: 'POST'
+    
+    
+  }
+);}
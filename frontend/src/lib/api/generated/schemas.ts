@@ -3,12 +3,19 @@
 
 import { z } from "zod";
 
-export const PatchCurrentUserPasswordRequest = z
+export const PublicJwk = z
   .object({
-    current_password: z.string().min(1).max(256),
-    new_password: z.string().min(8).max(256),
+    alg: z.string(),
+    crv: z.union([z.string(), z.null()]).optional(),
+    e: z.union([z.string(), z.null()]).optional(),
+    kid: z.string(),
+    kty: z.string(),
+    n: z.union([z.string(), z.null()]).optional(),
+    use: z.string(),
+    x: z.union([z.string(), z.null()]).optional(),
   })
   .passthrough();
+export const JwksResponse = z.object({ keys: z.array(PublicJwk) }).passthrough();
 export const ErrorResponseBody = z
   .object({
     code: z.number().int().gte(0),
@@ -17,20 +24,241 @@ export const ErrorResponseBody = z
     request_id: z.string(),
   })
   .passthrough();
+export const GroupResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    description: z.string(),
+    id: z.string().uuid(),
+    member_count: z.number().int(),
+    name: z.string(),
+    permissions: z.array(z.string()),
+    updated_at: z.string().datetime({ offset: true }),
+  })
+  .passthrough();
+export const CreateGroupRequest = z
+  .object({
+    description: z.string().max(256).optional(),
+    name: z.string().min(1).max(64),
+    permissions: z.array(z.string()).optional(),
+  })
+  .passthrough();
+export const PatchGroupRequest = z
+  .object({
+    description: z.union([z.string(), z.null()]),
+    name: z.union([z.string(), z.null()]),
+    permissions: z.union([z.array(z.string()), z.null()]),
+  })
+  .partial();
+export const GroupMemberResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    display_name: z.string(),
+    email: z.string(),
+    user_id: z.string().uuid(),
+    username: z.union([z.string(), z.null()]).optional(),
+  })
+  .passthrough();
+export const OrganizationResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    id: z.string().uuid(),
+    member_count: z.number().int(),
+    name: z.string(),
+    slug: z.string(),
+    updated_at: z.string().datetime({ offset: true }),
+  })
+  .passthrough();
+export const CreateOrganizationRequest = z
+  .object({ name: z.string().min(1).max(128), slug: z.string().min(1).max(64) })
+  .passthrough();
+export const PatchOrganizationRequest = z
+  .object({ name: z.union([z.string(), z.null()]) })
+  .partial();
+export const OrganizationMemberResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    display_name: z.string(),
+    email: z.string(),
+    role: z.string(),
+    updated_at: z.string().datetime({ offset: true }),
+    user_id: z.string().uuid(),
+    username: z.union([z.string(), z.null()]).optional(),
+  })
+  .passthrough();
+export const PutOrganizationMemberRequest = z
+  .object({ role: z.string().min(1).max(32) })
+  .passthrough();
+export const PermissionResponse = z
+  .object({ description: z.string(), name: z.string() })
+  .passthrough();
+export const RoleResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    description: z.string(),
+    is_builtin: z.boolean(),
+    name: z.string(),
+    permissions: z.array(z.string()),
+    updated_at: z.string().datetime({ offset: true }),
+  })
+  .passthrough();
+export const CreateRoleRequest = z
+  .object({
+    description: z.string().max(256).optional(),
+    name: z.string().min(1).max(32),
+    permissions: z.array(z.string()).optional(),
+  })
+  .passthrough();
+export const PatchRoleRequest = z
+  .object({
+    description: z.union([z.string(), z.null()]),
+    permissions: z.union([z.array(z.string()), z.null()]),
+  })
+  .partial();
+export const JwtKeyResponse = z
+  .object({
+    algorithm: z.string(),
+    created_at: z.union([z.string(), z.null()]).optional(),
+    expires_at: z.union([z.string(), z.null()]).optional(),
+    is_active: z.boolean(),
+    kid: z.string(),
+  })
+  .passthrough();
+export const RotateJwtKeyRequest = z
+  .object({ grace_period_secs: z.union([z.number(), z.null()]) })
+  .partial()
+  .passthrough();
+export const PatchCurrentUserPasswordRequest = z
+  .object({
+    current_password: z.string().min(1).max(256),
+    new_password: z.string().min(8).max(256),
+  })
+  .passthrough();
+export const RequestPasswordResetRequest = z
+  .object({ email: z.string().min(1).max(320).email() })
+  .passthrough();
+export const ConfirmPasswordResetRequest = z
+  .object({ new_password: z.string().min(8).max(256), token: z.string().min(1).max(256) })
+  .passthrough();
+export const ApiTokenScope = z.enum([
+  "users:read",
+  "users:write",
+  "settings:read",
+  "settings:write",
+]);
+export const ApiTokenResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    expires_at: z.union([z.string(), z.null()]).optional(),
+    id: z.string().uuid(),
+    last_used_at: z.union([z.string(), z.null()]).optional(),
+    last_used_ip: z.union([z.string(), z.null()]).optional(),
+    name: z.string(),
+    organization_id: z.union([z.string(), z.null()]).optional(),
+    scopes: z.array(ApiTokenScope),
+  })
+  .passthrough();
+export const CreateApiTokenRequest = z
+  .object({
+    expires_in_days: z.union([z.number(), z.null()]).optional(),
+    name: z.string().min(1).max(64),
+    organization_id: z.union([z.string(), z.null()]).optional(),
+    scopes: z.array(ApiTokenScope).min(1),
+  })
+  .passthrough();
+export const CreatedApiTokenResponse = ApiTokenResponse.and(
+  z.object({ token: z.string() }).passthrough(),
+);
+export const TotpStatusResponse = z
+  .object({
+    enabled: z.boolean(),
+    pending: z.boolean(),
+    recovery_codes_remaining: z.number().int(),
+    required: z.boolean(),
+  })
+  .passthrough();
+export const StartTotpEnrollmentRequest = z
+  .object({ current_password: z.string().min(1).max(256) })
+  .passthrough();
+export const TotpEnrollmentResponse = z
+  .object({ otpauth_uri: z.string(), secret: z.string() })
+  .passthrough();
+export const DisableTotpRequest = z
+  .object({ code: z.string().min(1).max(32), current_password: z.string().min(1).max(256) })
+  .passthrough();
+export const TotpCodeRequest = z.object({ code: z.string().min(1).max(32) }).passthrough();
+export const TotpRecoveryCodesResponse = z
+  .object({ recovery_codes: z.array(z.string()) })
+  .passthrough();
+export const SessionResponse = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    created_ip: z.union([z.string(), z.null()]).optional(),
+    created_user_agent: z.union([z.string(), z.null()]).optional(),
+    expires_at: z.string().datetime({ offset: true }),
+    id: z.string().uuid(),
+    impersonator_id: z.union([z.string(), z.null()]).optional(),
+    is_current: z.boolean(),
+    last_ip: z.union([z.string(), z.null()]).optional(),
+    last_refreshed_at: z.union([z.string(), z.null()]).optional(),
+    last_user_agent: z.union([z.string(), z.null()]).optional(),
+    updated_at: z.string().datetime({ offset: true }),
+  })
+  .passthrough();
 export const CreateSessionRequest = z
   .object({ identifier: z.string().min(1).max(320), password: z.string().min(1).max(256) })
   .passthrough();
 export const CreateSessionResponse = z
   .object({ expires_in: z.number().int().gte(0), token: z.string() })
   .passthrough();
+export const MfaChallengeResponse = z
+  .object({
+    expires_in: z.number().int().gte(0),
+    methods: z.array(z.string()),
+    mfa_token: z.string(),
+  })
+  .passthrough();
+export const StartImpersonationRequest = z
+  .object({ reason: z.string().min(1).max(256), user_id: z.string().uuid() })
+  .passthrough();
+export const CompleteMfaChallengeRequest = z
+  .object({ code: z.string().min(1).max(32), mfa_token: z.string().min(1).max(256) })
+  .passthrough();
 export const AppSettings = z
   .object({ check_interval_secs: z.number().int().gte(0), welcome_message: z.string() })
   .passthrough();
 export const IntegrationsSettings = z
   .object({ example_api_base: z.string(), example_api_key_is_set: z.boolean() })
   .passthrough();
+export const JwtAlgorithm = z.enum(["HS256", "EdDSA", "RS256"]);
+export const CookieSameSite = z.enum(["Strict", "Lax", "None"]);
+export const CookieSecure = z.enum(["auto", "always", "never"]);
+export const SecuritySettings = z
+  .object({
+    access_token_ttl_secs: z.number().int().gte(0),
+    jwt_algorithm: JwtAlgorithm,
+    login_backoff_after_failures: z.number().int().gte(0),
+    login_backoff_base_secs: z.number().int().gte(0),
+    login_backoff_max_secs: z.number().int().gte(0),
+    login_failure_window_secs: z.number().int().gte(0),
+    login_ip_failure_threshold: z.number().int().gte(0),
+    login_lockout_secs: z.number().int().gte(0),
+    login_lockout_threshold: z.number().int().gte(0),
+    mfa_required_for_admin: z.boolean(),
+    refresh_cookie_domain: z.union([z.string(), z.null()]).optional(),
+    refresh_cookie_path: z.string(),
+    refresh_cookie_same_site: CookieSameSite,
+    refresh_cookie_secure: CookieSecure,
+    refresh_token_ttl_secs: z.number().int().gte(0),
+  })
+  .passthrough();
+export const UsersSettings = z.object({ purge_after_days: z.number().int().gte(0) }).passthrough();
 export const SettingsResponse = z
-  .object({ app: AppSettings, integrations: IntegrationsSettings })
+  .object({
+    app: AppSettings,
+    integrations: IntegrationsSettings,
+    security: z.union([z.null(), SecuritySettings]).optional(),
+    users: UsersSettings,
+  })
   .passthrough();
 export const PatchAppSettings = z
   .object({
@@ -46,10 +274,36 @@ export const PatchIntegrationsSettings = z
   })
   .partial()
   .passthrough();
+export const PatchSecuritySettings = z
+  .object({
+    access_token_ttl_secs: z.union([z.number(), z.null()]),
+    jwt_algorithm: z.union([z.null(), JwtAlgorithm]),
+    login_backoff_after_failures: z.union([z.number(), z.null()]),
+    login_backoff_base_secs: z.union([z.number(), z.null()]),
+    login_backoff_max_secs: z.union([z.number(), z.null()]),
+    login_failure_window_secs: z.union([z.number(), z.null()]),
+    login_ip_failure_threshold: z.union([z.number(), z.null()]),
+    login_lockout_secs: z.union([z.number(), z.null()]),
+    login_lockout_threshold: z.union([z.number(), z.null()]),
+    mfa_required_for_admin: z.union([z.boolean(), z.null()]),
+    refresh_cookie_domain: z.union([z.string(), z.null()]),
+    refresh_cookie_path: z.union([z.string(), z.null()]),
+    refresh_cookie_same_site: z.union([z.null(), CookieSameSite]),
+    refresh_cookie_secure: z.union([z.null(), CookieSecure]),
+    refresh_token_ttl_secs: z.union([z.number(), z.null()]),
+  })
+  .partial()
+  .passthrough();
+export const PatchUsersSettings = z
+  .object({ purge_after_days: z.union([z.number(), z.null()]) })
+  .partial()
+  .passthrough();
 export const PatchSettingsRequest = z
   .object({
     app: z.union([z.null(), PatchAppSettings]),
     integrations: z.union([z.null(), PatchIntegrationsSettings]),
+    security: z.union([z.null(), PatchSecuritySettings]),
+    users: z.union([z.null(), PatchUsersSettings]),
   })
   .partial()
   .passthrough();
@@ -63,6 +317,7 @@ export const UserResponse = z
     is_active: z.boolean(),
     metadata: z.unknown(),
     phone: z.union([z.string(), z.null()]).optional(),
+    role: z.string(),
     updated_at: z.string().datetime({ offset: true }),
     username: z.union([z.string(), z.null()]).optional(),
   })
@@ -74,9 +329,31 @@ export const CreateUserRequest = z
     email: z.string().min(3).max(320),
     metadata: z.unknown().optional(),
     phone: z.union([z.string(), z.null()]).optional(),
+    role: z.union([z.string(), z.null()]).optional(),
     username: z.union([z.string(), z.null()]).optional(),
   })
   .passthrough();
+export const ImportUserRowResult = z
+  .object({
+    details: z.unknown().optional(),
+    email: z.union([z.string(), z.null()]).optional(),
+    error: z.union([z.string(), z.null()]).optional(),
+    line: z.number().int().gte(0),
+    user_id: z.union([z.string(), z.null()]).optional(),
+  })
+  .passthrough();
+export const ImportUsersResponse = z
+  .object({
+    dry_run: z.boolean(),
+    failed: z.number().int().gte(0),
+    rows: z.array(ImportUserRowResult),
+    succeeded: z.number().int().gte(0),
+    total: z.number().int().gte(0),
+  })
+  .passthrough();
+export const DeleteCurrentUserRequest = z
+  .object({ password: z.string().min(1).max(256) })
+  .passthrough();
 export const PatchCurrentUserRequest = z
   .object({
     avatar_url: z.union([z.string(), z.null()]),
@@ -85,15 +362,98 @@ export const PatchCurrentUserRequest = z
     phone: z.union([z.string(), z.null()]),
   })
   .partial();
+export const PersonalDataApiToken = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    expires_at: z.union([z.string(), z.null()]).optional(),
+    id: z.string().uuid(),
+    last_used_at: z.union([z.string(), z.null()]).optional(),
+    last_used_ip: z.union([z.string(), z.null()]).optional(),
+    last_used_user_agent: z.union([z.string(), z.null()]).optional(),
+    name: z.string(),
+    revoked_at: z.union([z.string(), z.null()]).optional(),
+    scopes: z.array(z.string()),
+  })
+  .passthrough();
+export const PersonalDataGroup = z
+  .object({
+    group_id: z.string().uuid(),
+    joined_at: z.string().datetime({ offset: true }),
+    name: z.string(),
+  })
+  .passthrough();
+export const PersonalDataOrganization = z
+  .object({
+    joined_at: z.string().datetime({ offset: true }),
+    name: z.string(),
+    organization_id: z.string().uuid(),
+    role: z.string(),
+    slug: z.string(),
+  })
+  .passthrough();
+export const UserDetailResponse = UserResponse.and(
+  z
+    .object({
+      active_session_count: z.number().int(),
+      deleted_at: z.union([z.string(), z.null()]).optional(),
+      last_login_at: z.union([z.string(), z.null()]).optional(),
+    })
+    .passthrough(),
+);
+export const PersonalDataSecurityEvent = z
+  .object({
+    client_ip: z.union([z.string(), z.null()]).optional(),
+    created_at: z.string().datetime({ offset: true }),
+    details: z.unknown(),
+    event_type: z.string(),
+    session_id: z.union([z.string(), z.null()]).optional(),
+    user_agent: z.union([z.string(), z.null()]).optional(),
+  })
+  .passthrough();
+export const PersonalDataSession = z
+  .object({
+    created_at: z.string().datetime({ offset: true }),
+    created_ip: z.union([z.string(), z.null()]).optional(),
+    created_user_agent: z.union([z.string(), z.null()]).optional(),
+    expires_at: z.string().datetime({ offset: true }),
+    id: z.string().uuid(),
+    impersonator_id: z.union([z.string(), z.null()]).optional(),
+    last_ip: z.union([z.string(), z.null()]).optional(),
+    last_refreshed_at: z.union([z.string(), z.null()]).optional(),
+    last_user_agent: z.union([z.string(), z.null()]).optional(),
+    revoked_at: z.union([z.string(), z.null()]).optional(),
+    revoked_reason: z.union([z.string(), z.null()]).optional(),
+  })
+  .passthrough();
+export const PersonalDataExport = z
+  .object({
+    api_tokens: z.array(PersonalDataApiToken),
+    exported_at: z.string().datetime({ offset: true }),
+    groups: z.array(PersonalDataGroup),
+    mfa_enabled: z.boolean(),
+    organizations: z.array(PersonalDataOrganization),
+    profile: UserDetailResponse,
+    security_events: z.array(PersonalDataSecurityEvent),
+    sessions: z.array(PersonalDataSession),
+  })
+  .passthrough();
+export const MyOrganizationResponse = z
+  .object({ id: z.string().uuid(), name: z.string(), role: z.string(), slug: z.string() })
+  .passthrough();
 export const PatchUserRequest = z
   .object({
     avatar_url: z.union([z.string(), z.null()]),
     display_name: z.union([z.string(), z.null()]),
     email: z.union([z.string(), z.null()]),
     is_active: z.union([z.boolean(), z.null()]),
-    metadata: z.unknown(),
+    metadata: z.union([z.object({}).partial().passthrough(), z.null()]),
     phone: z.union([z.string(), z.null()]),
+    role: z.union([z.string(), z.null()]),
     username: z.union([z.string(), z.null()]),
   })
   .partial()
   .passthrough();
+export const SetUserPasswordRequest = z
+  .object({ must_change: z.boolean().optional(), password: z.string().min(8).max(256) })
+  .passthrough();
+export const TemporaryPasswordResponse = z.object({ temporary_password: z.string() }).passthrough();
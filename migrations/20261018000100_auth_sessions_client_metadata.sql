@@ -0,0 +1,12 @@
+ALTER TABLE auth_sessions
+ADD COLUMN created_ip TEXT,
+ADD COLUMN created_user_agent TEXT,
+ADD COLUMN last_ip TEXT,
+ADD COLUMN last_user_agent TEXT,
+ADD COLUMN last_refreshed_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN auth_sessions.created_ip IS '创建会话（登录）时的客户端 IP';
+COMMENT ON COLUMN auth_sessions.created_user_agent IS '创建会话（登录）时的 User-Agent';
+COMMENT ON COLUMN auth_sessions.last_ip IS '最近一次登录/刷新时的客户端 IP';
+COMMENT ON COLUMN auth_sessions.last_user_agent IS '最近一次登录/刷新时的 User-Agent';
+COMMENT ON COLUMN auth_sessions.last_refreshed_at IS '最近一次 refresh token 轮换时间';
@@ -0,0 +1,21 @@
+ALTER TABLE auth_sessions
+ADD COLUMN previous_refresh_secret_hash TEXT;
+
+COMMENT ON COLUMN auth_sessions.previous_refresh_secret_hash IS '上一代（已轮换）refresh secret 的哈希，用于识别 refresh token 重放';
+
+CREATE TABLE security_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type TEXT NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    session_id UUID,
+    client_ip TEXT,
+    user_agent TEXT,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT security_events_event_type_non_empty CHECK (char_length(btrim(event_type)) > 0)
+);
+
+COMMENT ON TABLE security_events IS '安全事件审计表（如 refresh token 重放、账号锁定等）';
+
+CREATE INDEX idx_security_events_user_id_created_at ON security_events (user_id, created_at DESC);
+CREATE INDEX idx_security_events_event_type_created_at ON security_events (event_type, created_at DESC);
@@ -0,0 +1,43 @@
+CREATE TABLE user_totp (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    secret_ciphertext TEXT NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    last_used_step BIGINT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE user_totp IS '用户 TOTP 两步验证密钥（AES-256-GCM 加密存储）';
+COMMENT ON COLUMN user_totp.confirmed_at IS '绑定确认时间；为空表示尚未确认（待绑定）';
+COMMENT ON COLUMN user_totp.last_used_step IS '最近一次成功使用的 TOTP 步长，用于防止动态码重放';
+
+CREATE TABLE user_totp_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_user_totp_recovery_codes_user_id ON user_totp_recovery_codes (user_id);
+
+CREATE TABLE auth_mfa_challenges (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    secret_hash TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    failed_attempts INTEGER NOT NULL DEFAULT 0,
+    created_ip TEXT,
+    created_user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE auth_mfa_challenges IS '两步登录的 MFA 挑战：密码校验通过后签发，完成第二因素后才签发会话';
+
+CREATE INDEX idx_auth_mfa_challenges_user_id ON auth_mfa_challenges (user_id);
+CREATE INDEX idx_auth_mfa_challenges_expires_at ON auth_mfa_challenges (expires_at);
+
+INSERT INTO system_config (key, value, description)
+VALUES ('security.mfa_required_for_admin', 'false'::jsonb, '是否要求 admin 角色必须启用两步验证（TOTP）')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,34 @@
+CREATE TABLE auth_login_throttles (
+    scope TEXT NOT NULL CHECK (scope IN ('identifier', 'ip')),
+    key TEXT NOT NULL,
+    failure_count INTEGER NOT NULL DEFAULT 0,
+    first_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_failure_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    blocked_until TIMESTAMPTZ,
+    PRIMARY KEY (scope, key)
+);
+
+COMMENT ON TABLE auth_login_throttles IS '登录失败计数（按登录标识 / 客户端 IP），用于指数退避与账号临时锁定';
+
+CREATE INDEX idx_auth_login_throttles_last_failure_at ON auth_login_throttles (last_failure_at);
+
+ALTER TABLE users
+    ADD COLUMN locked_until TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.locked_until IS '账号临时锁定截止时间；为空或已过期表示未锁定';
+
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('security.login_failure_window_secs', '900'::jsonb, '登录失败计数窗口（秒），超过该时长无失败则重新计数'),
+    ('security.login_backoff_after_failures', '3'::jsonb, '同一登录标识失败达到该次数后开始指数退避'),
+    ('security.login_backoff_base_secs', '1'::jsonb, '登录退避基础时长（秒）'),
+    ('security.login_backoff_max_secs', '300'::jsonb, '登录退避最大时长（秒）'),
+    ('security.login_lockout_threshold', '10'::jsonb, '同一登录标识失败达到该次数后临时锁定账号'),
+    ('security.login_lockout_secs', '900'::jsonb, '账号临时锁定时长（秒）'),
+    ('security.login_ip_failure_threshold', '50'::jsonb, '同一客户端 IP 失败达到该次数后开始指数退避')
+ON CONFLICT (key) DO NOTHING;
+
+ALTER TABLE auth_mfa_challenges
+    ADD COLUMN login_identifier TEXT;
+
+COMMENT ON COLUMN auth_mfa_challenges.login_identifier IS '发起登录时使用的登录标识（已规范化），MFA 失败同样计入该标识的失败次数';
@@ -0,0 +1,15 @@
+CREATE TABLE jwt_signing_keys (
+    kid TEXT PRIMARY KEY,
+    algorithm TEXT NOT NULL DEFAULT 'HS256',
+    secret_ciphertext TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE jwt_signing_keys IS 'JWT 签名密钥环：一个签名密钥 + 宽限期内仍可校验的旧密钥；为空时回退到 security.jwt_secret';
+COMMENT ON COLUMN jwt_signing_keys.secret_ciphertext IS 'AES-256-GCM 加密后的密钥材料（security.data_encryption_key）';
+COMMENT ON COLUMN jwt_signing_keys.is_active IS '是否为当前签名密钥（最多一个）';
+COMMENT ON COLUMN jwt_signing_keys.expires_at IS '退役密钥的校验截止时间';
+
+CREATE UNIQUE INDEX uq_jwt_signing_keys_active ON jwt_signing_keys (is_active) WHERE is_active;
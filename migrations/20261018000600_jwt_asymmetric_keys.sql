@@ -0,0 +1,12 @@
+ALTER TABLE jwt_signing_keys
+    ADD COLUMN public_jwk JSONB;
+
+COMMENT ON COLUMN jwt_signing_keys.public_jwk IS '非对称密钥的公钥（JWK），通过 /.well-known/jwks.json 公开；HS256 为空';
+COMMENT ON COLUMN jwt_signing_keys.secret_ciphertext IS 'AES-256-GCM 加密后的密钥材料：HS256 为对称密钥，EdDSA 为 PKCS#8 DER，RS256 为 PKCS#1 DER';
+
+ALTER TABLE jwt_signing_keys
+    ADD CONSTRAINT ck_jwt_signing_keys_algorithm CHECK (algorithm IN ('HS256', 'EdDSA', 'RS256'));
+
+INSERT INTO system_config (key, value, description)
+VALUES ('security.jwt_algorithm', '"HS256"'::jsonb, 'JWT 签名算法：HS256 | EdDSA | RS256；修改后自动轮换签名密钥')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,9 @@
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('security.access_token_ttl_secs', '900'::jsonb, 'access token 有效期（秒）'),
+    ('security.refresh_token_ttl_secs', '2592000'::jsonb, 'refresh token 有效期（秒），每次刷新后重新计算'),
+    ('security.refresh_cookie_path', '"/api/v1/sessions"'::jsonb, 'refresh cookie 的 Path，必须覆盖 /api/v1/sessions/refresh'),
+    ('security.refresh_cookie_domain', '""'::jsonb, 'refresh cookie 的 Domain；为空表示仅当前主机'),
+    ('security.refresh_cookie_same_site', '"Lax"'::jsonb, 'refresh cookie 的 SameSite：Strict | Lax | None'),
+    ('security.refresh_cookie_secure', '"auto"'::jsonb, 'refresh cookie 的 Secure：auto（release 构建开启）| always | never')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,25 @@
+CREATE TABLE api_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    secret_hash TEXT NOT NULL,
+    scopes TEXT[] NOT NULL,
+    expires_at TIMESTAMPTZ,
+    last_used_at TIMESTAMPTZ,
+    last_used_ip TEXT,
+    last_used_user_agent TEXT,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE api_tokens IS '个人访问令牌（PAT）：供脚本与 CI 使用，明文只在创建时返回一次';
+COMMENT ON COLUMN api_tokens.secret_hash IS '令牌密钥的 Argon2id 哈希（与 auth_sessions.refresh_secret_hash 一致）';
+COMMENT ON COLUMN api_tokens.scopes IS '授权范围，如 users:read、settings:write';
+COMMENT ON COLUMN api_tokens.expires_at IS '过期时间；为空表示长期有效';
+COMMENT ON COLUMN api_tokens.last_used_at IS '最近一次使用时间（至多每分钟更新一次）';
+
+CREATE INDEX idx_api_tokens_user_id ON api_tokens (user_id);
+CREATE UNIQUE INDEX api_tokens_user_id_name_active_unique
+    ON api_tokens (user_id, name)
+    WHERE revoked_at IS NULL;
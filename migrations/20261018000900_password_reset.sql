@@ -0,0 +1,26 @@
+CREATE TABLE password_reset_tokens (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    secret_hash TEXT NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    consumed_at TIMESTAMPTZ,
+    created_ip TEXT,
+    created_user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE password_reset_tokens IS '找回密码的一次性令牌：通过邮件发送，确认重置或签发新令牌后即失效';
+COMMENT ON COLUMN password_reset_tokens.secret_hash IS '令牌密钥的 Argon2id 哈希';
+
+CREATE INDEX idx_password_reset_tokens_user_id ON password_reset_tokens (user_id);
+CREATE INDEX idx_password_reset_tokens_expires_at ON password_reset_tokens (expires_at);
+
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('app.public_base_url', '"http://localhost:8080"'::jsonb, '对外访问地址，用于拼接邮件中的链接'),
+    ('mail.transport', '"log"'::jsonb, '邮件发送方式：log（仅写日志）| file（写入 mail.file_dir）| smtp（投递到 SMTP 中继）'),
+    ('mail.from', '"PROJECT_NAME <no-reply@localhost>"'::jsonb, '发件人'),
+    ('mail.file_dir', '"data/mail"'::jsonb, 'file 方式的邮件输出目录'),
+    ('mail.smtp_host', '"127.0.0.1"'::jsonb, 'SMTP 中继主机'),
+    ('mail.smtp_port', '25'::jsonb, 'SMTP 中继端口')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,4 @@
+ALTER TABLE users
+    ADD COLUMN password_must_change BOOLEAN NOT NULL DEFAULT FALSE;
+
+COMMENT ON COLUMN users.password_must_change IS '下次登录时必须修改密码（管理员设置或签发临时密码后置位，用户自行改密后清除）';
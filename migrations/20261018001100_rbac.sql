@@ -0,0 +1,52 @@
+CREATE TABLE roles (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE permissions (
+    name TEXT PRIMARY KEY,
+    description TEXT NOT NULL
+);
+
+CREATE TABLE role_permissions (
+    role TEXT NOT NULL REFERENCES roles(name) ON DELETE CASCADE,
+    permission TEXT NOT NULL REFERENCES permissions(name) ON DELETE CASCADE,
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO permissions (name, description)
+VALUES
+    ('users:read', '查看用户'),
+    ('users:write', '创建、修改、删除用户，重置用户密码'),
+    ('settings:read', '查看运行期配置'),
+    ('settings:write', '修改运行期配置'),
+    ('roles:read', '查看角色与权限'),
+    ('roles:write', '创建、修改、删除自定义角色'),
+    ('jwt_keys:read', '查看 JWT 签名密钥'),
+    ('jwt_keys:write', '轮换 JWT 签名密钥');
+
+INSERT INTO roles (name, description, is_builtin)
+VALUES
+    ('admin', '管理员，拥有全部权限', TRUE),
+    ('user', '普通用户', TRUE),
+    ('auditor', '审计员，只读查看用户、配置与角色', TRUE);
+
+INSERT INTO role_permissions (role, permission)
+SELECT 'admin', name
+FROM permissions;
+
+INSERT INTO role_permissions (role, permission)
+VALUES
+    ('user', 'settings:read'),
+    ('auditor', 'users:read'),
+    ('auditor', 'settings:read'),
+    ('auditor', 'roles:read');
+
+ALTER TABLE users
+DROP CONSTRAINT users_role_check;
+
+ALTER TABLE users
+ADD CONSTRAINT users_role_fkey FOREIGN KEY (role) REFERENCES roles(name);
@@ -0,0 +1,33 @@
+CREATE TABLE user_groups (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE user_group_permissions (
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    permission TEXT NOT NULL REFERENCES permissions(name) ON DELETE CASCADE,
+    PRIMARY KEY (group_id, permission)
+);
+
+CREATE TABLE user_group_members (
+    group_id UUID NOT NULL REFERENCES user_groups(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (group_id, user_id)
+);
+
+CREATE INDEX user_group_members_user_id_idx ON user_group_members (user_id);
+
+INSERT INTO permissions (name, description)
+VALUES
+    ('groups:read', '查看用户组与成员'),
+    ('groups:write', '创建、修改、删除用户组，管理组成员');
+
+INSERT INTO role_permissions (role, permission)
+VALUES
+    ('admin', 'groups:read'),
+    ('admin', 'groups:write'),
+    ('auditor', 'groups:read');
@@ -0,0 +1,8 @@
+ALTER TABLE auth_sessions
+ADD COLUMN impersonator_id UUID REFERENCES users(id) ON DELETE CASCADE;
+
+INSERT INTO permissions (name, description)
+VALUES ('users:impersonate', '以其他用户身份登录（模拟登录）');
+
+INSERT INTO role_permissions (role, permission)
+VALUES ('admin', 'users:impersonate');
@@ -0,0 +1,12 @@
+ALTER TABLE users
+ADD COLUMN last_login_at TIMESTAMPTZ;
+
+UPDATE users u
+SET last_login_at = s.last_login_at
+FROM (
+    SELECT user_id, MAX(created_at) AS last_login_at
+    FROM auth_sessions
+    WHERE impersonator_id IS NULL
+    GROUP BY user_id
+) s
+WHERE s.user_id = u.id;
@@ -0,0 +1,4 @@
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('users.purge_after_days', '0'::jsonb, '逻辑删除的用户保留天数，到期后物理删除；0 表示不自动清除')
+ON CONFLICT (key) DO NOTHING;
@@ -0,0 +1,23 @@
+ALTER TABLE auth_sessions
+ADD COLUMN refresh_generation INTEGER NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN auth_sessions.refresh_generation IS '当前 refresh token 的代次，每次轮换加一并写入 refresh token';
+
+CREATE TABLE auth_session_rotated_secrets (
+    session_id UUID NOT NULL REFERENCES auth_sessions(id) ON DELETE CASCADE,
+    generation INTEGER NOT NULL,
+    secret_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (session_id, generation)
+);
+
+COMMENT ON TABLE auth_session_rotated_secrets IS '会话各代已轮换 refresh secret 的哈希，用于识别任意旧代 refresh token 的重放';
+
+-- 升级前签发的 refresh token 不带代次；其上一代 secret 记为第 -1 代，仍参与重放检测
+INSERT INTO auth_session_rotated_secrets (session_id, generation, secret_hash)
+SELECT id, -1, previous_refresh_secret_hash
+FROM auth_sessions
+WHERE previous_refresh_secret_hash IS NOT NULL;
+
+ALTER TABLE auth_sessions
+DROP COLUMN previous_refresh_secret_hash;
@@ -0,0 +1,4 @@
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('security.login_trusted_proxies', '""'::jsonb, '受信反向代理 IP（逗号分隔）；仅来自这些地址的请求才采信 x-forwarded-for 计算限流 IP')
+ON CONFLICT (key) DO NOTHING;
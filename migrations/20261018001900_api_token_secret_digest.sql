@@ -0,0 +1 @@
+COMMENT ON COLUMN api_tokens.secret_hash IS '令牌密钥的 SHA-256（hex）；早期令牌为 Argon2id PHC，校验时按前缀区分';
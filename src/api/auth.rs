@@ -1,15 +1,21 @@
 use axum::{
     extract::{Request, State},
+    http::Method,
     middleware::Next,
     response::Response,
 };
 use chrono::Utc;
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use jsonwebtoken::{decode, decode_header, Validation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::api::client_info::ClientInfo;
 use crate::error::AppError;
 use crate::http::router::AppState;
+use crate::services::api_tokens::{self, ApiTokenScope};
+use crate::services::organizations;
+
+const UNKNOWN_KID_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
@@ -25,13 +31,96 @@ pub struct Claims {
     #[serde(default)]
     pub email: Option<String>,
     pub role: String,
+    /// 会话限制；存在时该 token 只能访问限制对应的少量接口。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rst: Option<SessionRestriction>,
+    /// 模拟登录时的真实操作者（RFC 8693 `act`）；存在时前端应提示当前处于模拟登录状态。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaim>,
+}
+
+/// 模拟登录的真实操作者。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaim {
+    pub sub: String,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+/// 受限会话的限制类型。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionRestriction {
+    /// 策略要求启用两步验证，但用户尚未完成 TOTP 绑定。
+    MfaEnrollmentRequired,
+    /// 须先修改密码（初始化生成的管理员密码、管理员签发的临时密码等）。
+    PasswordChangeRequired,
+}
+
+impl SessionRestriction {
+    pub fn allows(self, method: &Method, path: &str) -> bool {
+        match self {
+            SessionRestriction::MfaEnrollmentRequired => {
+                path == "/api/v1/security/totp"
+                    || path.starts_with("/api/v1/security/totp/")
+                    || (method == Method::GET && path == "/api/v1/users/me")
+                    || (method == Method::DELETE && path == "/api/v1/sessions/current")
+            }
+            SessionRestriction::PasswordChangeRequired => {
+                method == Method::PATCH && path == "/api/v1/security/password"
+            }
+        }
+    }
+
+    /// 受限会话访问未放行接口时返回的错误；须改密使用独立错误码，便于前端直接跳转改密页面。
+    pub fn error(self) -> AppError {
+        match self {
+            SessionRestriction::MfaEnrollmentRequired => {
+                AppError::session_restricted("请先启用两步验证（TOTP）后再访问该接口")
+            }
+            SessionRestriction::PasswordChangeRequired => {
+                AppError::password_change_required("请先修改密码后再访问该接口")
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct CurrentUser {
     pub user_id: Uuid,
-    pub session_id: Uuid,
+    /// 密码登录签发的会话；通过 API token 鉴权时为空。
+    pub session_id: Option<Uuid>,
+    /// 通过 API token（PAT）鉴权时的令牌 id。
+    pub api_token_id: Option<Uuid>,
     pub role: String,
+    /// 角色拥有的权限（`role_permissions`），每次请求从数据库加载，修改角色权限即时生效；
+    /// 组织上下文中为成员角色在该组织内的权限。
+    pub permissions: Vec<String>,
+    /// 组织上下文（`X-Organization-Id` 或 API token 绑定的组织）；为空表示平台上下文。
+    pub organization_id: Option<Uuid>,
+    pub restriction: Option<SessionRestriction>,
+    /// 模拟登录会话的真实操作者；为空表示用户本人登录。
+    pub impersonator_id: Option<Uuid>,
+}
+
+impl CurrentUser {
+    /// 实际发起请求的用户：模拟登录时为操作者，否则为当前用户本人。
+    pub fn actor_id(&self) -> Uuid {
+        self.impersonator_id.unwrap_or(self.user_id)
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|p| p == permission)
+    }
+
+    pub fn ensure_permission(&self, permission: &str) -> Result<(), AppError> {
+        if !self.has_permission(permission) {
+            return Err(AppError::PermissionDenied(format!(
+                "权限不足，缺少权限: {permission}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 pub async fn auth_middleware(
@@ -52,15 +141,59 @@ pub async fn auth_middleware(
         .filter(|s| !s.is_empty())
         .ok_or_else(|| AppError::auth_token("缺少 Authorization: Bearer token"))?;
 
-    let cfg = state.config.load_full();
-    let secret = cfg.security.jwt_secret.as_bytes();
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let requested_organization = organizations::requested_organization(req.headers())?;
+    let mut current_user = if api_tokens::is_api_token(token) {
+        let client = ClientInfo::from_parts(req.headers(), req.extensions());
+        authenticate_api_token(&state, token, &method, &path, &client).await?
+    } else {
+        authenticate_jwt(&state, token, &method, &path).await?
+    };
 
-    let token_data = decode::<Claims>(
-        token,
-        &DecodingKey::from_secret(secret),
-        &Validation::default(),
-    )
-    .map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
+    let organization_id = match (current_user.organization_id, requested_organization) {
+        (Some(bound), Some(requested)) if bound != requested => {
+            return Err(AppError::PermissionDenied(
+                "API token 已绑定其他组织".to_string(),
+            ));
+        }
+        (bound, requested) => bound.or(requested),
+    };
+    if let Some(organization_id) = organization_id {
+        organizations::enter(&state.db, &mut current_user, organization_id).await?;
+    }
+    req.extensions_mut().insert(current_user);
+
+    Ok(next.run(req).await)
+}
+
+async fn authenticate_jwt(
+    state: &AppState,
+    token: &str,
+    method: &Method,
+    path: &str,
+) -> Result<CurrentUser, AppError> {
+    let header = decode_header(token).map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
+    let mut cfg = state.config.load_full();
+    if cfg
+        .security
+        .jwt_keys
+        .verification_key(header.kid.as_deref())
+        .is_none()
+    {
+        // 未知 kid 可能来自其他实例刚轮换的新密钥；限制重新加载频率，避免被伪造 kid 放大数据库压力。
+        cfg = state
+            .runtime_with_fresh_jwt_keys(UNKNOWN_KID_RELOAD_INTERVAL)
+            .await?;
+    }
+    let key = cfg
+        .security
+        .jwt_keys
+        .verification_key(header.kid.as_deref())
+        .ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
+
+    let token_data = decode::<Claims>(token, key.decoding_key(), &Validation::new(key.algorithm))
+        .map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
 
     let user_id = Uuid::parse_str(&token_data.claims.sub)
         .map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
@@ -72,8 +205,21 @@ pub async fn auth_middleware(
         r#"
 SELECT
     u.auth_version,
+    u.role,
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = u.role
+        UNION
+        SELECT gp.permission
+        FROM user_group_members gm
+        INNER JOIN user_group_permissions gp ON gp.group_id = gm.group_id
+        WHERE gm.user_id = u.id
+        ORDER BY 1
+    ) AS "permissions!",
     s.expires_at AS session_expires_at,
-    s.revoked_at AS session_revoked_at
+    s.revoked_at AS session_revoked_at,
+    s.impersonator_id AS session_impersonator_id
 FROM users u
 LEFT JOIN auth_sessions s
        ON s.id = $2
@@ -90,10 +236,7 @@ LIMIT 1
     .map_err(|e| AppError::InternalError(format!("查询鉴权状态失败: {e}")))?
     .ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
 
-    if token_data.claims.role != "admin" && token_data.claims.role != "user" {
-        return Err(AppError::auth_token("Token 无效或已过期"));
-    }
-
+    // 角色变更会递增 auth_version，版本一致时 token 中的角色与数据库一致。
     if auth_row.auth_version != token_data.claims.ver {
         return Err(AppError::auth_token("Token 无效或已过期"));
     }
@@ -108,18 +251,103 @@ LIMIT 1
         return Err(AppError::auth_token("Token 无效或已过期"));
     }
 
-    req.extensions_mut().insert(CurrentUser {
+    if let Some(restriction) = token_data.claims.rst {
+        if !restriction.allows(method, path) {
+            return Err(restriction.error());
+        }
+    }
+
+    // 模拟登录以会话记录为准，token 中的 act 与会话不一致时视为无效 token。
+    let claimed_actor = token_data
+        .claims
+        .act
+        .as_ref()
+        .map(|act| Uuid::parse_str(&act.sub))
+        .transpose()
+        .map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
+    if claimed_actor != auth_row.session_impersonator_id {
+        return Err(AppError::auth_token("Token 无效或已过期"));
+    }
+    if auth_row.session_impersonator_id.is_some() && !impersonation_allows(method, path) {
+        return Err(AppError::PermissionDenied(
+            "模拟登录会话不能访问安全设置、会话管理、账号注销与个人数据导出接口".to_string(),
+        ));
+    }
+
+    Ok(CurrentUser {
         user_id,
-        session_id,
-        role: token_data.claims.role,
-    });
+        session_id: Some(session_id),
+        api_token_id: None,
+        role: auth_row.role,
+        permissions: auth_row.permissions,
+        organization_id: None,
+        restriction: token_data.claims.rst,
+        impersonator_id: auth_row.session_impersonator_id,
+    })
+}
 
-    Ok(next.run(req).await)
+/// PAT 鉴权：只能访问 scope 覆盖的资源接口，安全设置与会话管理接口一律拒绝。
+async fn authenticate_api_token(
+    state: &AppState,
+    token: &str,
+    method: &Method,
+    path: &str,
+    client: &ClientInfo,
+) -> Result<CurrentUser, AppError> {
+    let principal = api_tokens::authenticate(&state.db, token, client).await?;
+
+    let Some(scope) = ApiTokenScope::required_for(method, path) else {
+        return Err(AppError::PermissionDenied(
+            "API token 不能访问该接口，请使用登录会话".to_string(),
+        ));
+    };
+    if !principal.scopes.contains(&scope) {
+        return Err(AppError::PermissionDenied(format!(
+            "API token 缺少授权范围: {}",
+            scope.as_str()
+        )));
+    }
+
+    // 与登录会话保持一致：须修改密码或策略要求 admin 启用两步验证但尚未绑定时，PAT 同样不可用。
+    if principal.password_must_change {
+        return Err(SessionRestriction::PasswordChangeRequired.error());
+    }
+    let cfg = state.config.load_full();
+    if cfg.security.mfa_required_for_admin && principal.role == "admin" && !principal.mfa_enabled {
+        return Err(SessionRestriction::MfaEnrollmentRequired.error());
+    }
+
+    Ok(CurrentUser {
+        user_id: principal.user_id,
+        session_id: None,
+        api_token_id: Some(principal.token_id),
+        role: principal.role,
+        permissions: principal.permissions,
+        organization_id: principal.organization_id,
+        restriction: None,
+        impersonator_id: None,
+    })
 }
 
 #[derive(Debug, sqlx::FromRow)]
 struct AuthCheckRow {
     auth_version: i32,
+    role: String,
+    permissions: Vec<String>,
     session_expires_at: Option<chrono::DateTime<Utc>>,
     session_revoked_at: Option<chrono::DateTime<Utc>>,
+    session_impersonator_id: Option<Uuid>,
+}
+
+/// 模拟登录会话不能修改被模拟用户的凭证（密码、两步验证、API token）或管理其会话，
+/// 也不能注销账号或导出个人数据；只能退出自身（`DELETE /api/v1/sessions/current`）。
+fn impersonation_allows(method: &Method, path: &str) -> bool {
+    if method == Method::DELETE && path == "/api/v1/sessions/current" {
+        return true;
+    }
+    if (method == Method::DELETE && path == "/api/v1/users/me") || path == "/api/v1/users/me/export"
+    {
+        return false;
+    }
+    !(path.starts_with("/api/v1/security/") || path.starts_with("/api/v1/sessions"))
 }
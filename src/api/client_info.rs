@@ -0,0 +1,148 @@
+use std::convert::Infallible;
+use std::net::{IpAddr, SocketAddr};
+
+use axum::extract::{ConnectInfo, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{header, Extensions, HeaderMap};
+
+const USER_AGENT_MAX_CHARS: usize = 512;
+
+/// 请求方的客户端信息（IP + User-Agent）。
+///
+/// `ip` 的解析规则与访问日志一致：优先取 `x-forwarded-for` 的第一个地址，
+/// 其次取 `ConnectInfo<SocketAddr>` 中的对端地址。该值可被客户端伪造，只用于展示与审计；
+/// 限流等安全判断使用 [`ClientInfo::trusted_ip`]。
+#[derive(Debug, Clone, Default)]
+pub struct ClientInfo {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// TCP 对端地址。
+    pub peer_ip: Option<IpAddr>,
+    /// `x-forwarded-for` 中的全部地址，按出现顺序。
+    pub forwarded_for: Vec<String>,
+}
+
+impl ClientInfo {
+    pub fn from_parts(headers: &HeaderMap, extensions: &Extensions) -> Self {
+        Self {
+            ip: client_ip(headers, extensions),
+            user_agent: user_agent(headers),
+            peer_ip: extensions
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|ConnectInfo(addr)| addr.ip()),
+            forwarded_for: headers
+                .get_all("x-forwarded-for")
+                .iter()
+                .filter_map(|v| v.to_str().ok())
+                .flat_map(|v| v.split(','))
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+        }
+    }
+
+    /// 不可伪造的客户端 IP：从 TCP 对端地址开始，仅当其属于 `trusted_proxies` 时
+    /// 才继续采信 `x-forwarded-for` 中由该代理追加的上一跳（从右向左）。
+    pub fn trusted_ip(&self, trusted_proxies: &[IpAddr]) -> Option<String> {
+        let mut ip = self.peer_ip?;
+        for hop in self.forwarded_for.iter().rev() {
+            if !trusted_proxies.contains(&ip) {
+                break;
+            }
+            let Ok(hop) = hop.parse::<IpAddr>() else {
+                break;
+            };
+            ip = hop;
+        }
+        Some(ip.to_string())
+    }
+}
+
+impl<S> FromRequestParts<S> for ClientInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_parts(&parts.headers, &parts.extensions))
+    }
+}
+
+pub fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    let forwarded_for = headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.split(',').next())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty());
+
+    let remote_ip = extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+
+    forwarded_for.map(str::to_string).or(remote_ip)
+}
+
+pub fn user_agent(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.chars().take(USER_AGENT_MAX_CHARS).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn should_prefer_first_forwarded_for_address() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static(" 203.0.113.7 , 10.0.0.1"),
+        );
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 8080))));
+
+        assert_eq!(
+            client_ip(&headers, &extensions).as_deref(),
+            Some("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn trusted_ip_should_only_follow_forwarded_for_through_trusted_proxies() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("198.51.100.1, 203.0.113.7"),
+        );
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 8080))));
+        let client = ClientInfo::from_parts(&headers, &extensions);
+
+        assert_eq!(client.trusted_ip(&[]).as_deref(), Some("10.0.0.1"));
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        assert_eq!(
+            client.trusted_ip(&[proxy]).as_deref(),
+            Some("203.0.113.7"),
+            "只采信受信代理追加的最后一跳，客户端自带的地址被忽略"
+        );
+    }
+
+    #[test]
+    fn should_fallback_to_connect_info_address() {
+        let headers = HeaderMap::new();
+        let mut extensions = Extensions::new();
+        extensions.insert(ConnectInfo(SocketAddr::from(([192, 0, 2, 1], 8080))));
+
+        assert_eq!(
+            client_ip(&headers, &extensions).as_deref(),
+            Some("192.0.2.1")
+        );
+    }
+}
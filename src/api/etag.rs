@@ -0,0 +1,96 @@
+use axum::extract::FromRequestParts;
+use axum::http::header::{self, HeaderName};
+use axum::http::request::Parts;
+use axum::http::HeaderValue;
+use chrono::{DateTime, Utc};
+
+use crate::error::AppError;
+
+/// 由资源版本（`updated_at`）生成强 ETag（微秒时间戳），资源每次写入都会变化。
+pub fn entity_tag(version: DateTime<Utc>) -> String {
+    format!("\"{:x}\"", version.timestamp_micros())
+}
+
+/// 响应头 `ETag`，可直接作为 handler 返回元组的一部分。
+pub fn header(version: DateTime<Utc>) -> [(HeaderName, HeaderValue); 1] {
+    let value =
+        HeaderValue::from_str(&entity_tag(version)).expect("ETag 只包含 ASCII 十六进制字符");
+    [(header::ETAG, value)]
+}
+
+/// 请求头 `If-Match`；未携带时不做并发校验，保持对旧客户端的兼容。
+#[derive(Debug, Default)]
+pub struct IfMatch(Option<Vec<String>>);
+
+impl IfMatch {
+    /// 当前版本与 `If-Match` 不一致时返回 412；`*` 匹配任意版本。
+    ///
+    /// 按 RFC 9110 使用强比较，弱 ETag（`W/"..."`）永不匹配。
+    pub fn ensure(&self, version: DateTime<Utc>, resource: &str) -> Result<(), AppError> {
+        let Some(tags) = &self.0 else {
+            return Ok(());
+        };
+        let current = entity_tag(version);
+        if tags.iter().any(|tag| tag == "*" || *tag == current) {
+            return Ok(());
+        }
+        Err(AppError::precondition_failed(format!(
+            "{resource}已被其他人修改，请刷新后重试"
+        )))
+    }
+}
+
+impl<S> FromRequestParts<S> for IfMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let mut tags = Vec::new();
+        for value in parts.headers.get_all(header::IF_MATCH) {
+            let value = value
+                .to_str()
+                .map_err(|_| AppError::validation("If-Match 请求头不合法"))?;
+            tags.extend(
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        if tags.is_empty() {
+            return Ok(Self(None));
+        }
+        Ok(Self(Some(tags)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn if_match_should_use_strong_comparison() {
+        let version = Utc::now();
+        let tag = entity_tag(version);
+
+        assert!(IfMatch::default().ensure(version, "用户").is_ok());
+        assert!(IfMatch(Some(vec![tag.clone()]))
+            .ensure(version, "用户")
+            .is_ok());
+        assert!(IfMatch(Some(vec!["*".to_string()]))
+            .ensure(version, "用户")
+            .is_ok());
+        assert!(IfMatch(Some(vec![format!("W/{tag}")]))
+            .ensure(version, "用户")
+            .is_err());
+
+        let stale = IfMatch(Some(vec![tag]));
+        let err = stale
+            .ensure(version + chrono::Duration::microseconds(1), "用户")
+            .unwrap_err();
+        assert_eq!(err.error_code(), 2003);
+    }
+}
@@ -14,6 +14,15 @@ pub fn string_trim_min_len_8(v: &str, _ctx: &()) -> garde::Result {
     Ok(())
 }
 
+pub fn opt_u64_min_1(v: &Option<u64>, _ctx: &()) -> garde::Result {
+    if let Some(n) = v {
+        if *n < 1 {
+            return Err(garde::Error::new("不能小于 1"));
+        }
+    }
+    Ok(())
+}
+
 pub fn opt_u64_min_10(v: &Option<u64>, _ctx: &()) -> garde::Result {
     if let Some(n) = v {
         if *n < 10 {
@@ -23,11 +32,26 @@ pub fn opt_u64_min_10(v: &Option<u64>, _ctx: &()) -> garde::Result {
     Ok(())
 }
 
+pub fn opt_u64_max_3650(v: &Option<u64>, _ctx: &()) -> garde::Result {
+    if let Some(n) = v {
+        if *n > 3650 {
+            return Err(garde::Error::new("不能大于 3650"));
+        }
+    }
+    Ok(())
+}
+
 pub fn string_basic_email(v: &str, _ctx: &()) -> garde::Result {
     let v = v.trim();
     if v.is_empty() {
         return Err(garde::Error::new("不能为空"));
     }
+    // 地址会写入邮件头与 SMTP 命令，不允许空白、控制字符与尖括号
+    if v.chars()
+        .any(|c| c.is_whitespace() || c.is_control() || matches!(c, '<' | '>'))
+    {
+        return Err(garde::Error::new("邮箱格式不合法"));
+    }
     let at_index = v.find('@');
     let Some(at_index) = at_index else {
         return Err(garde::Error::new("邮箱格式不合法"));
@@ -48,6 +72,32 @@ pub fn opt_string_basic_email(v: &Option<String>, _ctx: &()) -> garde::Result {
     Ok(())
 }
 
+pub fn role_name_format(v: &str, _ctx: &()) -> garde::Result {
+    if !v.starts_with(|c: char| c.is_ascii_lowercase()) {
+        return Err(garde::Error::new("角色名必须以小写字母开头"));
+    }
+    if !v
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(garde::Error::new("角色名只能包含小写字母、数字或下划线"));
+    }
+    Ok(())
+}
+
+pub fn organization_slug_format(v: &str, _ctx: &()) -> garde::Result {
+    if !v.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit()) {
+        return Err(garde::Error::new("组织标识必须以小写字母或数字开头"));
+    }
+    if !v
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(garde::Error::new("组织标识只能包含小写字母、数字或连字符"));
+    }
+    Ok(())
+}
+
 pub fn opt_username_format(v: &Option<String>, _ctx: &()) -> garde::Result {
     let Some(username) = v else {
         return Ok(());
@@ -71,3 +121,10 @@ pub fn opt_username_format(v: &Option<String>, _ctx: &()) -> garde::Result {
 
     Ok(())
 }
+
+pub fn nullable_username_format(v: &Option<Option<String>>, ctx: &()) -> garde::Result {
+    match v {
+        Some(username) => opt_username_format(username, ctx),
+        None => Ok(()),
+    }
+}
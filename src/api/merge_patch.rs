@@ -0,0 +1,69 @@
+use serde_json::Value;
+
+/// `PATCH` 请求体的媒体类型（RFC 7396 JSON Merge Patch）；`application/json` 同样按该语义处理。
+pub const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+/// 按 RFC 7396 把 `patch` 合并进 `target`。
+///
+/// - `patch` 不是对象时整体替换 `target`
+/// - 对象内值为 `null` 的键被删除，其余键递归合并
+pub fn apply(target: &mut Value, patch: Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    let Value::Object(target) = target else {
+        unreachable!("target 已被替换为对象");
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(&key);
+        } else {
+            apply(target.entry(key).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn should_follow_rfc7396_examples() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": {"b": "c", "d": "e"}}),
+                json!({"a": {"d": null, "f": "g"}}),
+                json!({"a": {"b": "c", "f": "g"}}),
+            ),
+            (json!({"a": ["b"]}), json!({"a": "c"}), json!({"a": "c"})),
+            (json!({"a": "foo"}), json!(null), json!(null)),
+            (json!(["a", "b"]), json!({"a": "b"}), json!({"a": "b"})),
+            (
+                json!({"e": null}),
+                json!({"a": 1}),
+                json!({"e": null, "a": 1}),
+            ),
+            (
+                json!({}),
+                json!({"a": {"bb": {"ccc": null}}}),
+                json!({"a": {"bb": {}}}),
+            ),
+        ];
+        for (mut target, patch, expected) in cases {
+            apply(&mut target, patch.clone());
+            assert_eq!(target, expected, "patch: {patch}");
+        }
+    }
+}
@@ -1,6 +1,10 @@
 pub mod auth;
+pub mod client_info;
+pub mod etag;
 pub mod garde_helpers;
+pub mod merge_patch;
 pub mod openapi;
+pub mod permissions;
 pub mod request_id;
 pub mod serde_helpers;
 pub mod validation;
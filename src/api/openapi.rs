@@ -2,6 +2,9 @@ use serde::Serialize;
 use utoipa::openapi::security::{Http, HttpAuthScheme, SecurityScheme};
 use utoipa::{Modify, OpenApi, ToSchema};
 
+use crate::modules::groups::handlers as groups;
+use crate::modules::organizations::handlers as organizations;
+use crate::modules::roles::handlers as roles;
 use crate::modules::security::handlers as security_handlers;
 use crate::modules::sessions::handlers as sessions;
 use crate::modules::settings::handlers as settings;
@@ -41,39 +44,142 @@ impl Modify for SecurityAddon {
         (name = "sessions", description = "认证与会话"),
         (name = "settings", description = "运行期配置"),
         (name = "security", description = "安全与凭证管理"),
-        (name = "users", description = "用户管理")
+        (name = "users", description = "用户管理"),
+        (name = "roles", description = "角色与权限"),
+        (name = "groups", description = "用户组"),
+        (name = "organizations", description = "组织与成员")
     ),
     modifiers(&SecurityAddon),
     paths(
         sessions::create_session_handler,
         sessions::refresh_session_handler,
+        sessions::complete_mfa_challenge_handler,
+        sessions::jwks_handler,
         sessions::delete_current_session_handler,
+        sessions::list_sessions_handler,
+        sessions::delete_session_handler,
+        sessions::delete_other_sessions_handler,
+        sessions::start_impersonation_handler,
         settings::get_settings_handler,
         settings::patch_settings_handler,
         security_handlers::patch_current_user_password_handler,
+        security_handlers::get_totp_status_handler,
+        security_handlers::start_totp_enrollment_handler,
+        security_handlers::confirm_totp_enrollment_handler,
+        security_handlers::disable_totp_handler,
+        security_handlers::regenerate_totp_recovery_codes_handler,
+        security_handlers::list_jwt_keys_handler,
+        security_handlers::rotate_jwt_key_handler,
+        security_handlers::list_api_tokens_handler,
+        security_handlers::create_api_token_handler,
+        security_handlers::revoke_api_token_handler,
+        security_handlers::request_password_reset_handler,
+        security_handlers::confirm_password_reset_handler,
         users::get_current_user_handler,
+        users::delete_current_user_handler,
+        users::export_current_user_handler,
         users::patch_current_user_handler,
         users::get_users_handler,
+        users::get_user_handler,
         users::create_user_handler,
+        users::import_users_handler,
+        users::export_users_handler,
         users::patch_user_handler,
         users::delete_user_handler,
-        users::restore_user_handler
+        users::restore_user_handler,
+        users::purge_user_handler,
+        users::unlock_user_handler,
+        users::set_user_password_handler,
+        users::issue_temporary_password_handler,
+        roles::list_roles_handler,
+        roles::list_permissions_handler,
+        roles::create_role_handler,
+        roles::patch_role_handler,
+        roles::delete_role_handler,
+        groups::list_groups_handler,
+        groups::create_group_handler,
+        groups::patch_group_handler,
+        groups::delete_group_handler,
+        groups::list_group_members_handler,
+        groups::put_group_member_handler,
+        groups::delete_group_member_handler,
+        organizations::list_organizations_handler,
+        organizations::create_organization_handler,
+        organizations::patch_organization_handler,
+        organizations::delete_organization_handler,
+        organizations::list_organization_members_handler,
+        organizations::put_organization_member_handler,
+        organizations::delete_organization_member_handler,
+        organizations::list_my_organizations_handler
     ),
     components(schemas(
         ErrorResponseBody,
         sessions::CreateSessionRequest,
         sessions::CreateSessionResponse,
+        sessions::SessionResponse,
+        sessions::MfaChallengeResponse,
+        sessions::CompleteMfaChallengeRequest,
+        sessions::StartImpersonationRequest,
+        sessions::JwksResponse,
+        crate::services::jwt_keys::PublicJwk,
+        crate::services::jwt_keys::JwtAlgorithm,
         settings::SettingsResponse,
         settings::AppSettings,
         settings::IntegrationsSettings,
         settings::PatchSettingsRequest,
         settings::PatchAppSettings,
         settings::PatchIntegrationsSettings,
+        settings::SecuritySettings,
+        settings::PatchSecuritySettings,
+        settings::UsersSettings,
+        settings::PatchUsersSettings,
+        crate::config::runtime::CookieSameSite,
+        crate::config::runtime::CookieSecure,
         security_handlers::PatchCurrentUserPasswordRequest,
+        security_handlers::TotpStatusResponse,
+        security_handlers::StartTotpEnrollmentRequest,
+        security_handlers::TotpEnrollmentResponse,
+        security_handlers::TotpCodeRequest,
+        security_handlers::DisableTotpRequest,
+        security_handlers::TotpRecoveryCodesResponse,
+        security_handlers::JwtKeyResponse,
+        security_handlers::RotateJwtKeyRequest,
+        security_handlers::ApiTokenResponse,
+        security_handlers::CreatedApiTokenResponse,
+        security_handlers::CreateApiTokenRequest,
+        crate::services::api_tokens::ApiTokenScope,
+        security_handlers::RequestPasswordResetRequest,
+        security_handlers::ConfirmPasswordResetRequest,
         users::UserResponse,
+        users::UserDetailResponse,
         users::CreateUserRequest,
+        users::ImportUsersResponse,
+        users::ImportUserRowResult,
         users::PatchCurrentUserRequest,
-        users::PatchUserRequest
+        users::PatchUserRequest,
+        users::SetUserPasswordRequest,
+        users::TemporaryPasswordResponse,
+        users::DeleteCurrentUserRequest,
+        users::PersonalDataExport,
+        users::PersonalDataOrganization,
+        users::PersonalDataGroup,
+        users::PersonalDataSession,
+        users::PersonalDataApiToken,
+        users::PersonalDataSecurityEvent,
+        roles::RoleResponse,
+        roles::PermissionResponse,
+        roles::CreateRoleRequest,
+        roles::PatchRoleRequest,
+        groups::GroupResponse,
+        groups::GroupMemberResponse,
+        groups::CreateGroupRequest,
+        groups::PatchGroupRequest,
+        organizations::OrganizationResponse,
+        organizations::OrganizationMemberResponse,
+        organizations::MyOrganizationResponse,
+        organizations::CreateOrganizationRequest,
+        organizations::PatchOrganizationRequest,
+        organizations::PutOrganizationMemberRequest
     ))
 )]
 pub struct ApiDoc;
@@ -90,11 +196,31 @@ mod tests {
 
         assert!(doc.paths.paths.contains_key("/api/v1/users"));
         assert!(doc.paths.paths.contains_key("/api/v1/users/me"));
-        assert!(doc.paths.paths.contains_key("/api/v1/users/{user_id}"));
+        assert!(doc.paths.paths.contains_key("/api/v1/users/import"));
+        assert!(doc.paths.paths.contains_key("/api/v1/users/export"));
+        assert!(doc.paths.paths["/api/v1/users/me"].delete.is_some());
+        assert!(doc.paths.paths.contains_key("/api/v1/users/me/export"));
+        assert!(doc.paths.paths["/api/v1/users/{user_id}"].get.is_some());
         assert!(doc
             .paths
             .paths
             .contains_key("/api/v1/users/{user_id}/restore"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/users/{user_id}/purge"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/users/{user_id}/unlock"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/users/{user_id}/password"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/users/{user_id}/temporary-password"));
         assert!(!doc
             .paths
             .paths
@@ -129,12 +255,114 @@ mod tests {
             .contains_key("/api/v1/security/admin-password"));
     }
 
+    #[test]
+    fn should_expose_totp_and_mfa_challenge_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/security/totp"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/totp/confirm"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/totp/recovery-codes"));
+        assert!(doc.paths.paths.contains_key("/api/v1/sessions/mfa"));
+    }
+
+    #[test]
+    fn should_expose_jwt_key_rotation_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/security/jwt-keys"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/jwt-keys/rotate"));
+        assert!(doc.paths.paths.contains_key("/.well-known/jwks.json"));
+    }
+
+    #[test]
+    fn should_expose_api_token_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/security/tokens"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/tokens/{token_id}"));
+    }
+
+    #[test]
+    fn should_expose_password_reset_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/password-reset"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/security/password-reset/confirm"));
+    }
+
+    #[test]
+    fn should_expose_role_management_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/roles"));
+        assert!(doc.paths.paths.contains_key("/api/v1/roles/{role}"));
+        assert!(doc.paths.paths.contains_key("/api/v1/permissions"));
+    }
+
+    #[test]
+    fn should_expose_group_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/groups"));
+        assert!(doc.paths.paths.contains_key("/api/v1/groups/{group_id}"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/groups/{group_id}/members/{user_id}"));
+    }
+
+    #[test]
+    fn should_expose_organization_paths() {
+        let doc = ApiDoc::openapi();
+
+        assert!(doc.paths.paths.contains_key("/api/v1/organizations"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/organizations/{organization_id}"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/organizations/{organization_id}/members/{user_id}"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/users/me/organizations"));
+    }
+
     #[test]
     fn should_expose_session_refresh_and_current_paths() {
         let doc = ApiDoc::openapi();
 
         assert!(doc.paths.paths.contains_key("/api/v1/sessions/refresh"));
         assert!(doc.paths.paths.contains_key("/api/v1/sessions/current"));
+        assert!(doc.paths.paths.contains_key("/api/v1/sessions/others"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/sessions/impersonation"));
+        assert!(doc
+            .paths
+            .paths
+            .contains_key("/api/v1/sessions/{session_id}"));
     }
 
     #[test]
@@ -0,0 +1,127 @@
+use std::marker::PhantomData;
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::api::auth::CurrentUser;
+use crate::error::AppError;
+
+/// 接口权限；`NAME` 与 `permissions` 表中的名称一致（由迁移初始化）。
+pub trait Permission {
+    const NAME: &'static str;
+}
+
+pub struct UsersRead;
+pub struct UsersWrite;
+pub struct UsersImpersonate;
+pub struct SettingsRead;
+pub struct SettingsWrite;
+pub struct RolesRead;
+pub struct RolesWrite;
+pub struct JwtKeysRead;
+pub struct JwtKeysWrite;
+pub struct OrganizationsRead;
+pub struct OrganizationsWrite;
+pub struct GroupsRead;
+pub struct GroupsWrite;
+
+impl Permission for UsersRead {
+    const NAME: &'static str = "users:read";
+}
+
+impl Permission for UsersWrite {
+    const NAME: &'static str = "users:write";
+}
+
+impl Permission for UsersImpersonate {
+    const NAME: &'static str = "users:impersonate";
+}
+
+impl Permission for SettingsRead {
+    const NAME: &'static str = "settings:read";
+}
+
+impl Permission for SettingsWrite {
+    const NAME: &'static str = "settings:write";
+}
+
+impl Permission for RolesRead {
+    const NAME: &'static str = "roles:read";
+}
+
+impl Permission for RolesWrite {
+    const NAME: &'static str = "roles:write";
+}
+
+impl Permission for JwtKeysRead {
+    const NAME: &'static str = "jwt_keys:read";
+}
+
+impl Permission for JwtKeysWrite {
+    const NAME: &'static str = "jwt_keys:write";
+}
+
+impl Permission for OrganizationsRead {
+    const NAME: &'static str = "organizations:read";
+}
+
+impl Permission for OrganizationsWrite {
+    const NAME: &'static str = "organizations:write";
+}
+
+impl Permission for GroupsRead {
+    const NAME: &'static str = "groups:read";
+}
+
+impl Permission for GroupsWrite {
+    const NAME: &'static str = "groups:write";
+}
+
+/// 代码中使用的全部权限，须与 `permissions` 表保持一致。
+pub const ALL: &[&str] = &[
+    UsersRead::NAME,
+    UsersWrite::NAME,
+    UsersImpersonate::NAME,
+    SettingsRead::NAME,
+    SettingsWrite::NAME,
+    RolesRead::NAME,
+    RolesWrite::NAME,
+    JwtKeysRead::NAME,
+    JwtKeysWrite::NAME,
+    OrganizationsRead::NAME,
+    OrganizationsWrite::NAME,
+    GroupsRead::NAME,
+    GroupsWrite::NAME,
+];
+
+/// 组织上下文中可用的权限；其余权限（签名密钥、角色与组织管理等）只在平台上下文生效。
+pub const ORGANIZATION_SCOPED: &[&str] = &[
+    UsersRead::NAME,
+    UsersWrite::NAME,
+    SettingsRead::NAME,
+    SettingsWrite::NAME,
+    RolesRead::NAME,
+];
+
+/// 要求当前用户具备权限 `P` 的提取器，缺少权限时返回 `403`（错误码 `2002`）。
+///
+/// 用法：`RequirePermission(current_user, _): RequirePermission<UsersWrite>`。
+pub struct RequirePermission<P>(pub CurrentUser, pub PhantomData<P>);
+
+impl<S, P> FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    P: Permission,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let current_user = parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or_else(|| AppError::auth_token("缺少 Authorization: Bearer token"))?;
+        current_user.ensure_permission(P::NAME)?;
+        Ok(Self(current_user, PhantomData))
+    }
+}
@@ -23,3 +23,35 @@ where
     }
     Ok(s)
 }
+
+/// 可置空字段的 merge-patch 反序列化（配合 `#[serde(default)]` 使用）：
+/// 字段缺省为 `None`（不修改）；`null` 或空白字符串为 `Some(None)`（清空）；其余为 `Some(Some(v))`。
+pub fn deserialize_nullable_trimmed_string<'de, D>(
+    deserializer: D,
+) -> Result<Option<Option<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_opt_trimmed_string(deserializer).map(Some)
+}
+
+/// 不可置空字段的 merge-patch 反序列化：显式 `null` 视为非法输入；空白字符串仍按未提供处理。
+pub fn deserialize_non_null_trimmed_string<'de, D>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    let s = s.trim().to_string();
+    Ok(Some(s).filter(|s| !s.is_empty()))
+}
+
+/// 字段出现即为 `Some`：非空类型遇到 `null` 报错，`serde_json::Value` 则保留 `Value::Null`。
+pub fn deserialize_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
@@ -1,4 +1,5 @@
-use axum::extract::{FromRequest, Request};
+use axum::extract::{FromRequest, FromRequestParts, Query, Request};
+use axum::http::request::Parts;
 use axum::Json;
 use garde::Validate;
 use serde::de::DeserializeOwned;
@@ -35,3 +36,31 @@ where
         Ok(Self(value))
     }
 }
+
+/// Query string + garde 校验的 extractor，错误体与 [`ValidatedJson`] 保持一致。
+pub struct ValidatedQuery<T>(pub T);
+
+impl<S, T> FromRequestParts<S> for ValidatedQuery<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate<Context = ()> + Send,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(value) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(|e| {
+                AppError::validation_with_details(
+                    "查询参数不合法".to_string(),
+                    Some(serde_json::json!({ "query": [e.body_text()] })),
+                )
+            })?;
+
+        value
+            .validate()
+            .map_err(|report| AppError::from_garde_report("字段校验失败", report))?;
+
+        Ok(Self(value))
+    }
+}
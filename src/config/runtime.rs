@@ -1,24 +1,370 @@
+use std::net::IpAddr;
+
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::db::DbPool;
+use crate::services::jwt_keys::{self, JwtAlgorithm, JwtKeyring};
+use crate::services::mail::MailTransportKind;
 
 /// 运行期（Runtime）配置：全部从数据库 `system_config` 读取。
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
     pub security: SecurityRuntimeConfig,
     pub app: AppRuntimeConfig,
+    pub mail: MailRuntimeConfig,
     pub integrations: IntegrationsRuntimeConfig,
+    pub users: UsersRuntimeConfig,
 }
 
 #[derive(Debug, Clone)]
 pub struct SecurityRuntimeConfig {
+    /// legacy HS256 密钥；`jwt_signing_keys` 为空时作为唯一签名密钥。
     pub jwt_secret: String,
+    pub jwt_keys: JwtKeyring,
+    /// 新签名密钥使用的算法；仅在轮换时生效，已有密钥保持原算法。
+    pub jwt_algorithm: JwtAlgorithm,
+    /// AES-256-GCM 密钥（hex），用于加密落库的 TOTP 密钥等敏感数据。
+    pub data_encryption_key: String,
+    /// 是否要求 `admin` 角色必须启用两步验证。
+    pub mfa_required_for_admin: bool,
+    pub login_throttle: LoginThrottleConfig,
+    pub session: SessionTokenConfig,
+}
+
+/// refresh cookie 必须能随该请求发送，`refresh_cookie_path` 需覆盖此路径。
+const REFRESH_ENDPOINT_PATH: &str = "/api/v1/sessions/refresh";
+const MIN_ACCESS_TOKEN_TTL_SECS: u64 = 60;
+const MAX_ACCESS_TOKEN_TTL_SECS: u64 = 24 * 60 * 60;
+const MAX_REFRESH_TOKEN_TTL_SECS: u64 = 365 * 24 * 60 * 60;
+/// 登录防爆破各时长的上限（30 天），避免计算截止时间时溢出。
+const MAX_LOGIN_THROTTLE_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// access/refresh token 有效期与 refresh cookie 属性。
+#[derive(Debug, Clone)]
+pub struct SessionTokenConfig {
+    pub access_token_ttl_secs: u64,
+    pub refresh_token_ttl_secs: u64,
+    pub refresh_cookie_path: String,
+    /// 为空表示 host-only cookie（不写 `Domain`）。
+    pub refresh_cookie_domain: Option<String>,
+    pub refresh_cookie_same_site: CookieSameSite,
+    pub refresh_cookie_secure: CookieSecure,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum CookieSameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl CookieSameSite {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+}
+
+impl std::str::FromStr for CookieSameSite {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "Strict" => Ok(Self::Strict),
+            "Lax" => Ok(Self::Lax),
+            "None" => Ok(Self::None),
+            other => Err(anyhow!("不支持的 SameSite 取值: {other}")),
+        }
+    }
+}
+
+/// refresh cookie 的 `Secure` 标记：`auto` 时仅 release 构建开启。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CookieSecure {
+    Auto,
+    Always,
+    Never,
+}
+
+impl CookieSecure {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Never => "never",
+        }
+    }
+
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Auto => !cfg!(debug_assertions),
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+impl std::str::FromStr for CookieSecure {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            other => Err(anyhow!("不支持的 Secure 取值: {other}")),
+        }
+    }
+}
+
+impl SessionTokenConfig {
+    async fn load_from_db(pool: &DbPool) -> Result<Self> {
+        let access_token_ttl_secs =
+            get_u64_with_default(pool, "security.access_token_ttl_secs", 15 * 60)
+                .await
+                .context("加载 security.access_token_ttl_secs 失败")?;
+        let refresh_token_ttl_secs =
+            get_u64_with_default(pool, "security.refresh_token_ttl_secs", 30 * 24 * 60 * 60)
+                .await
+                .context("加载 security.refresh_token_ttl_secs 失败")?;
+        let refresh_cookie_path = get_string_with_default(
+            pool,
+            "security.refresh_cookie_path",
+            "/api/v1/sessions".to_string(),
+        )
+        .await
+        .context("加载 security.refresh_cookie_path 失败")?;
+        let refresh_cookie_domain =
+            get_string_with_default(pool, "security.refresh_cookie_domain", String::new())
+                .await
+                .context("加载 security.refresh_cookie_domain 失败")?;
+        let refresh_cookie_same_site =
+            get_string_with_default(pool, "security.refresh_cookie_same_site", "Lax".into())
+                .await
+                .and_then(|v| v.parse())
+                .context("加载 security.refresh_cookie_same_site 失败")?;
+        let refresh_cookie_secure =
+            get_string_with_default(pool, "security.refresh_cookie_secure", "auto".into())
+                .await
+                .and_then(|v| v.parse())
+                .context("加载 security.refresh_cookie_secure 失败")?;
+
+        let config = Self {
+            access_token_ttl_secs,
+            refresh_token_ttl_secs,
+            refresh_cookie_path,
+            refresh_cookie_domain: Some(refresh_cookie_domain).filter(|v| !v.is_empty()),
+            refresh_cookie_same_site,
+            refresh_cookie_secure,
+        };
+        config
+            .validate()
+            .map_err(|e| anyhow!("会话 token 配置不合法: {e}"))?;
+        Ok(config)
+    }
+
+    /// 跨字段校验；设置接口在写库前、启动加载时都会调用。
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if !(MIN_ACCESS_TOKEN_TTL_SECS..=MAX_ACCESS_TOKEN_TTL_SECS)
+            .contains(&self.access_token_ttl_secs)
+        {
+            return Err(format!(
+                "access_token_ttl_secs 必须在 {MIN_ACCESS_TOKEN_TTL_SECS} 到 {MAX_ACCESS_TOKEN_TTL_SECS} 之间"
+            ));
+        }
+        if self.refresh_token_ttl_secs <= self.access_token_ttl_secs
+            || self.refresh_token_ttl_secs > MAX_REFRESH_TOKEN_TTL_SECS
+        {
+            return Err(format!(
+                "refresh_token_ttl_secs 必须大于 access_token_ttl_secs 且不超过 {MAX_REFRESH_TOKEN_TTL_SECS}"
+            ));
+        }
+
+        let path = self.refresh_cookie_path.as_str();
+        if !path.starts_with('/') || !is_cookie_attribute_value(path) {
+            return Err("refresh_cookie_path 必须以 / 开头且不能包含空白、分号或控制字符".into());
+        }
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        if path != REFRESH_ENDPOINT_PATH && !REFRESH_ENDPOINT_PATH.starts_with(&prefix) {
+            return Err(format!(
+                "refresh_cookie_path 必须覆盖 {REFRESH_ENDPOINT_PATH}，否则刷新时浏览器不会携带 cookie"
+            ));
+        }
+
+        if let Some(domain) = &self.refresh_cookie_domain {
+            let labels = domain.strip_prefix('.').unwrap_or(domain);
+            let valid = labels.split('.').all(|label| {
+                !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+            });
+            if !valid {
+                return Err("refresh_cookie_domain 不是合法的域名".into());
+            }
+        }
+
+        if self.refresh_cookie_same_site == CookieSameSite::None
+            && self.refresh_cookie_secure != CookieSecure::Always
+        {
+            return Err(
+                "refresh_cookie_same_site 为 None 时 refresh_cookie_secure 必须为 always".into(),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 逗号分隔的 IP 列表，忽略空项。
+fn parse_ip_list(v: &str) -> Result<Vec<IpAddr>> {
+    v.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().map_err(|_| anyhow!("不是合法的 IP 地址: {s}")))
+        .collect()
+}
+
+fn is_cookie_attribute_value(v: &str) -> bool {
+    v.chars()
+        .all(|c| c.is_ascii_graphic() && c != ';' && c != ',')
+}
+
+/// 登录防爆破配置（按登录标识与客户端 IP 分别计数）。
+#[derive(Debug, Clone)]
+pub struct LoginThrottleConfig {
+    /// 失败计数窗口：距上次失败超过该时长后计数重新开始。
+    pub failure_window_secs: u64,
+    /// 同一登录标识连续失败达到该次数后开始指数退避。
+    pub backoff_after_failures: u64,
+    pub backoff_base_secs: u64,
+    pub backoff_max_secs: u64,
+    /// 同一登录标识连续失败达到该次数后临时锁定账号。
+    pub lockout_threshold: u64,
+    pub lockout_secs: u64,
+    /// 同一客户端 IP 失败达到该次数后开始指数退避。
+    pub ip_failure_threshold: u64,
+    /// 受信反向代理地址；只有来自这些地址的请求才采信 `x-forwarded-for` 计算客户端 IP。
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl LoginThrottleConfig {
+    async fn load_from_db(pool: &DbPool) -> Result<Self> {
+        let config = Self {
+            failure_window_secs: get_u64_with_default(
+                pool,
+                "security.login_failure_window_secs",
+                900,
+            )
+            .await
+            .context("加载 security.login_failure_window_secs 失败")?,
+            backoff_after_failures: get_u64_with_default(
+                pool,
+                "security.login_backoff_after_failures",
+                3,
+            )
+            .await
+            .context("加载 security.login_backoff_after_failures 失败")?,
+            backoff_base_secs: get_u64_with_default(pool, "security.login_backoff_base_secs", 1)
+                .await
+                .context("加载 security.login_backoff_base_secs 失败")?,
+            backoff_max_secs: get_u64_with_default(pool, "security.login_backoff_max_secs", 300)
+                .await
+                .context("加载 security.login_backoff_max_secs 失败")?,
+            lockout_threshold: get_u64_with_default(pool, "security.login_lockout_threshold", 10)
+                .await
+                .context("加载 security.login_lockout_threshold 失败")?,
+            lockout_secs: get_u64_with_default(pool, "security.login_lockout_secs", 900)
+                .await
+                .context("加载 security.login_lockout_secs 失败")?,
+            ip_failure_threshold: get_u64_with_default(
+                pool,
+                "security.login_ip_failure_threshold",
+                50,
+            )
+            .await
+            .context("加载 security.login_ip_failure_threshold 失败")?,
+            trusted_proxies: get_string_with_default(
+                pool,
+                "security.login_trusted_proxies",
+                String::new(),
+            )
+            .await
+            .and_then(|v| parse_ip_list(&v))
+            .context("加载 security.login_trusted_proxies 失败")?,
+        };
+        config
+            .validate()
+            .map_err(|e| anyhow!("登录防爆破配置不合法: {e}"))?;
+        Ok(config)
+    }
+
+    /// 时长取值范围校验；设置接口在写库前、启动加载时都会调用。
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        let durations = [
+            ("login_failure_window_secs", self.failure_window_secs),
+            ("login_backoff_base_secs", self.backoff_base_secs),
+            ("login_backoff_max_secs", self.backoff_max_secs),
+            ("login_lockout_secs", self.lockout_secs),
+        ];
+        for (name, secs) in durations {
+            if !(1..=MAX_LOGIN_THROTTLE_SECS).contains(&secs) {
+                return Err(format!("{name} 必须在 1 到 {MAX_LOGIN_THROTTLE_SECS} 之间"));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct AppRuntimeConfig {
     pub check_interval_secs: u64,
     pub welcome_message: String,
+    /// 对外访问地址（不含末尾 `/`），用于拼接邮件中的链接。
+    pub public_base_url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MailRuntimeConfig {
+    pub transport: MailTransportKind,
+    pub from: String,
+    pub file_dir: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+}
+
+impl MailRuntimeConfig {
+    async fn load_from_db(pool: &DbPool) -> Result<Self> {
+        let transport: MailTransportKind =
+            get_string_with_default(pool, "mail.transport", "log".to_string())
+                .await
+                .and_then(|v| v.parse())
+                .context("加载 mail.transport 失败")?;
+        let smtp_port = get_u64_with_default(pool, "mail.smtp_port", 25)
+            .await
+            .and_then(|v| u16::try_from(v).map_err(|_| anyhow!("端口超出范围: {v}")))
+            .context("加载 mail.smtp_port 失败")?;
+
+        Ok(Self {
+            transport,
+            from: get_string_with_default(
+                pool,
+                "mail.from",
+                "PROJECT_NAME <no-reply@localhost>".to_string(),
+            )
+            .await
+            .context("加载 mail.from 失败")?,
+            file_dir: get_string_with_default(pool, "mail.file_dir", "data/mail".to_string())
+                .await
+                .context("加载 mail.file_dir 失败")?,
+            smtp_host: get_string_with_default(pool, "mail.smtp_host", "127.0.0.1".to_string())
+                .await
+                .context("加载 mail.smtp_host 失败")?,
+            smtp_port,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -27,11 +373,34 @@ pub struct IntegrationsRuntimeConfig {
     pub example_api_key: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct UsersRuntimeConfig {
+    /// 逻辑删除的用户保留天数，到期后由后台任务物理删除；`0` 表示不自动清除。
+    pub purge_after_days: u64,
+}
+
 impl RuntimeConfig {
     pub async fn load_from_db(pool: &DbPool) -> Result<Self> {
         let jwt_secret = get_required_string(pool, "security.jwt_secret")
             .await
             .context("加载 security.jwt_secret 失败")?;
+        let data_encryption_key = get_required_string(pool, "security.data_encryption_key")
+            .await
+            .context("加载 security.data_encryption_key 失败")?;
+        let mfa_required_for_admin =
+            get_bool_with_default(pool, "security.mfa_required_for_admin", false)
+                .await
+                .context("加载 security.mfa_required_for_admin 失败")?;
+        let jwt_algorithm: JwtAlgorithm =
+            get_string_with_default(pool, "security.jwt_algorithm", "HS256".to_string())
+                .await
+                .and_then(|v| v.parse())
+                .context("加载 security.jwt_algorithm 失败")?;
+        let login_throttle = LoginThrottleConfig::load_from_db(pool).await?;
+        let session = SessionTokenConfig::load_from_db(pool).await?;
+        let jwt_keys = jwt_keys::load_keyring(pool, &jwt_secret, &data_encryption_key)
+            .await
+            .context("加载 JWT 签名密钥失败")?;
 
         let check_interval_secs = get_u64_with_default(pool, "app.check_interval_secs", 3600)
             .await
@@ -43,6 +412,16 @@ impl RuntimeConfig {
         )
         .await
         .context("加载 app.welcome_message 失败")?;
+        let public_base_url = get_string_with_default(
+            pool,
+            "app.public_base_url",
+            "http://localhost:8080".to_string(),
+        )
+        .await
+        .context("加载 app.public_base_url 失败")?
+        .trim_end_matches('/')
+        .to_string();
+        let mail = MailRuntimeConfig::load_from_db(pool).await?;
 
         let example_api_base = get_string_with_default(
             pool,
@@ -55,21 +434,79 @@ impl RuntimeConfig {
             get_string_with_default(pool, "integrations.example_api_key", "".into())
                 .await
                 .context("加载 integrations.example_api_key 失败")?;
+        let purge_after_days = get_u64_with_default(pool, "users.purge_after_days", 0)
+            .await
+            .context("加载 users.purge_after_days 失败")?;
 
         Ok(Self {
-            security: SecurityRuntimeConfig { jwt_secret },
+            security: SecurityRuntimeConfig {
+                jwt_secret,
+                jwt_keys,
+                jwt_algorithm,
+                data_encryption_key,
+                mfa_required_for_admin,
+                login_throttle,
+                session,
+            },
             app: AppRuntimeConfig {
                 check_interval_secs,
                 welcome_message,
+                public_base_url,
             },
+            mail,
             integrations: IntegrationsRuntimeConfig {
                 example_api_base,
                 example_api_key,
             },
+            users: UsersRuntimeConfig { purge_after_days },
         })
     }
 }
 
+/// 可按组织覆盖的配置项（`organization_config`），其余配置始终取全局值。
+pub const ORGANIZATION_OVERRIDABLE_KEYS: &[&str] = &[
+    "app.welcome_message",
+    "integrations.example_api_base",
+    "integrations.example_api_key",
+];
+
+impl RuntimeConfig {
+    /// 在全局配置之上叠加组织级覆盖。
+    pub async fn with_organization_overrides(
+        &self,
+        pool: &DbPool,
+        organization_id: uuid::Uuid,
+    ) -> Result<Self> {
+        let overrides = sqlx::query!(
+            r#"
+SELECT key, value
+FROM organization_config
+WHERE organization_id = $1
+            "#,
+            organization_id,
+        )
+        .fetch_all(pool)
+        .await
+        .context("查询 organization_config 失败")?;
+
+        let mut cfg = self.clone();
+        for row in overrides {
+            let value = row
+                .value
+                .as_str()
+                .map(|s| s.to_string())
+                .ok_or_else(|| anyhow!("组织配置项 {} 类型错误：期望 string", row.key))?;
+            match row.key.as_str() {
+                "app.welcome_message" => cfg.app.welcome_message = value,
+                "integrations.example_api_base" => cfg.integrations.example_api_base = value,
+                "integrations.example_api_key" => cfg.integrations.example_api_key = value,
+                _ => {}
+            }
+        }
+        Ok(cfg)
+    }
+}
+
 async fn get_value(pool: &DbPool, key: &str) -> Result<Option<serde_json::Value>> {
     let value = sqlx::query_scalar!("SELECT value FROM system_config WHERE key = $1", key)
         .fetch_optional(pool)
@@ -115,3 +552,13 @@ async fn get_u64_with_default(pool: &DbPool, key: &str, default: u64) -> Result<
     }
     Err(anyhow!("配置项 {key} 类型错误：期望 non-negative integer"))
 }
+
+async fn get_bool_with_default(pool: &DbPool, key: &str, default: bool) -> Result<bool> {
+    let value = get_value(pool, key).await?;
+    let Some(value) = value else {
+        return Ok(default);
+    };
+    value
+        .as_bool()
+        .ok_or_else(|| anyhow!("配置项 {key} 类型错误：期望 boolean"))
+}
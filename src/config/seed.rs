@@ -26,20 +26,46 @@ impl SeedOptions {
 
 /// 迁移后/启动时的幂等初始化：
 /// - 确保 `security.jwt_secret` 存在
+/// - 确保 `security.data_encryption_key` 存在
 /// - 确保管理员用户（默认 `admin`）存在可用密码哈希
 pub async fn seed_if_needed(pool: &DbPool, opts: &SeedOptions) -> Result<()> {
     ensure_jwt_secret_exists(pool).await?;
+    ensure_data_encryption_key_exists(pool).await?;
     ensure_admin_user_password_hash_exists(pool, opts).await?;
     Ok(())
 }
 
 async fn ensure_jwt_secret_exists(pool: &DbPool) -> Result<()> {
+    ensure_random_hex_config_exists(
+        pool,
+        "security.jwt_secret",
+        "JWT 签名密钥（hex），用于 HS256",
+    )
+    .await
+}
+
+async fn ensure_data_encryption_key_exists(pool: &DbPool) -> Result<()> {
+    ensure_random_hex_config_exists(
+        pool,
+        "security.data_encryption_key",
+        "敏感数据加密密钥（hex，32 字节），用于 AES-256-GCM 加密 TOTP 密钥等",
+    )
+    .await
+}
+
+/// 若 `system_config` 中缺少 `key`，则生成 32 字节随机值（hex）写入。
+async fn ensure_random_hex_config_exists(
+    pool: &DbPool,
+    key: &str,
+    description: &str,
+) -> Result<()> {
     let exists_row = sqlx::query!(
-        "SELECT EXISTS (SELECT 1 FROM system_config WHERE key = 'security.jwt_secret') AS \"exists!\""
+        "SELECT EXISTS (SELECT 1 FROM system_config WHERE key = $1) AS \"exists!\"",
+        key,
     )
     .fetch_one(pool)
     .await
-    .context("检查 security.jwt_secret 是否存在失败")?;
+    .with_context(|| format!("检查 {key} 是否存在失败"))?;
     let exists = exists_row.exists;
 
     if exists {
@@ -57,16 +83,16 @@ VALUES ($1, $2, $3)
 ON CONFLICT (key) DO NOTHING
 RETURNING key
 "#,
-        "security.jwt_secret",
+        key,
         serde_json::Value::String(secret_hex),
-        "JWT 签名密钥（hex），用于 HS256",
+        description,
     )
     .fetch_optional(pool)
     .await
-    .context("写入 security.jwt_secret 失败")?;
+    .with_context(|| format!("写入 {key} 失败"))?;
 
     if inserted.is_some() {
-        tracing::info!("已生成并写入 security.jwt_secret");
+        tracing::info!("已生成并写入 {key}");
     }
 
     Ok(())
@@ -92,7 +118,7 @@ async fn ensure_admin_user_password_hash_exists(pool: &DbPool, opts: &SeedOption
     }
 
     if let Some(legacy_hash) = load_legacy_admin_password_hash(pool).await? {
-        upsert_admin_user_password_hash(pool, &username, &legacy_hash)
+        upsert_admin_user_password_hash(pool, &username, &legacy_hash, false)
             .await
             .context("迁移 legacy admin 密码到 users 失败")?;
         tracing::info!("已将 security.admin_password_hash 迁移到用户 {username}");
@@ -101,17 +127,18 @@ async fn ensure_admin_user_password_hash_exists(pool: &DbPool, opts: &SeedOption
 
     let (password, should_print) = match opts.seed_admin_password.as_ref() {
         Some(p) if !p.trim().is_empty() => (p.clone(), false),
-        _ => (generate_random_password()?, true),
+        _ => (crate::password::generate_random_password(), true),
     };
     let password_hash = crate::password::hash_password_argon2id(&password)?;
 
-    upsert_admin_user_password_hash(pool, &username, &password_hash)
+    // 随机生成的初始密码会出现在日志中，首次登录后必须修改。
+    upsert_admin_user_password_hash(pool, &username, &password_hash, should_print)
         .await
         .context("初始化管理员用户密码失败")?;
 
     if should_print {
         tracing::warn!(
-            "已生成管理员初始密码（用户: {}，仅打印一次，请立即修改/妥善保存）：{}",
+            "已生成管理员初始密码（用户: {}，仅打印一次，首次登录后必须修改）：{}",
             username,
             password
         );
@@ -178,9 +205,10 @@ async fn upsert_admin_user_password_hash(
     pool: &DbPool,
     username: &str,
     password_hash: &str,
+    must_change: bool,
 ) -> Result<()> {
     let default_email = format!("{username}@local.invalid");
-    if try_upsert_admin_user(pool, username, &default_email, password_hash)
+    if try_upsert_admin_user(pool, username, &default_email, password_hash, must_change)
         .await
         .is_ok()
     {
@@ -190,7 +218,7 @@ async fn upsert_admin_user_password_hash(
     let mut suffix = [0u8; 4];
     OsRng.fill_bytes(&mut suffix);
     let fallback_email = format!("{username}+{}@local.invalid", hex_encode(&suffix));
-    try_upsert_admin_user(pool, username, &fallback_email, password_hash)
+    try_upsert_admin_user(pool, username, &fallback_email, password_hash, must_change)
         .await
         .context("创建管理员用户失败（默认与回退邮箱均不可用）")?;
     Ok(())
@@ -201,6 +229,7 @@ async fn try_upsert_admin_user(
     username: &str,
     email: &str,
     password_hash: &str,
+    must_change: bool,
 ) -> Result<()> {
     sqlx::query!(
         r#"
@@ -211,11 +240,16 @@ INSERT INTO users (
     role,
     is_active,
     metadata,
-    password_hash
+    password_hash,
+    password_must_change
 )
-VALUES ($1, $2, $3, 'admin', TRUE, '{}'::jsonb, $4)
+VALUES ($1, $2, $3, 'admin', TRUE, '{}'::jsonb, $4, $5)
 ON CONFLICT (username) WHERE deleted_at IS NULL AND username IS NOT NULL DO UPDATE
 SET password_hash = COALESCE(NULLIF(users.password_hash, ''), EXCLUDED.password_hash),
+    password_must_change = CASE
+        WHEN NULLIF(users.password_hash, '') IS NULL THEN EXCLUDED.password_must_change
+        ELSE users.password_must_change
+    END,
     role = 'admin',
     is_active = TRUE,
     updated_at = NOW()
@@ -224,6 +258,7 @@ SET password_hash = COALESCE(NULLIF(users.password_hash, ''), EXCLUDED.password_
         "Administrator",
         email,
         password_hash,
+        must_change,
     )
     .execute(pool)
     .await
@@ -231,12 +266,6 @@ SET password_hash = COALESCE(NULLIF(users.password_hash, ''), EXCLUDED.password_
     Ok(())
 }
 
-fn generate_random_password() -> Result<String> {
-    let mut bytes = [0u8; 16];
-    OsRng.fill_bytes(&mut bytes);
-    Ok(hex_encode(&bytes))
-}
-
 pub fn hex_encode(bytes: &[u8]) -> String {
     const TABLE: &[u8; 16] = b"0123456789abcdef";
     let mut out = Vec::with_capacity(bytes.len() * 2);
@@ -279,28 +308,55 @@ mod tests {
             "seed 应能成功创建管理员用户，实际错误: {result:?}"
         );
 
-        let hash = sqlx::query_scalar!(
+        let row = sqlx::query!(
             r#"
-SELECT password_hash
+SELECT password_hash, password_must_change
 FROM users
 WHERE username = $1
 LIMIT 1
             "#,
             username,
         )
-        .fetch_optional(&pool)
+        .fetch_one(&pool)
         .await
-        .expect("查询测试管理员失败")
-        .flatten();
+        .expect("查询测试管理员失败");
 
         assert!(
-            hash.as_deref().is_some_and(|v| !v.trim().is_empty()),
+            row.password_hash
+                .as_deref()
+                .is_some_and(|v| !v.trim().is_empty()),
             "seed 创建后管理员密码哈希不应为空"
         );
+        assert!(
+            !row.password_must_change,
+            "使用 SEED_ADMIN_PASSWORD 时不应强制修改密码"
+        );
 
         sqlx::query!("DELETE FROM users WHERE username = $1", username)
             .execute(&pool)
             .await
             .expect("清理测试管理员失败");
     }
+
+    #[sqlx::test(migrations = "./migrations")]
+    async fn seed_should_require_password_change_for_generated_admin_password(pool: sqlx::PgPool) {
+        let username = format!("seed-admin-{}", Uuid::new_v4().simple());
+        let opts = SeedOptions {
+            seed_admin_username: Some(username.clone()),
+            seed_admin_password: None,
+        };
+
+        seed_if_needed(&pool, &opts)
+            .await
+            .expect("seed 应能成功创建管理员用户");
+
+        let must_change = sqlx::query_scalar!(
+            "SELECT password_must_change FROM users WHERE username = $1",
+            username,
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("查询测试管理员失败");
+        assert!(must_change, "随机生成的初始密码必须在首次登录后修改");
+    }
 }
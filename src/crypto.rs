@@ -0,0 +1,85 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, Result};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+use crate::config::seed::hex_encode;
+
+const NONCE_LEN: usize = 12;
+
+/// 使用 AES-256-GCM 加密，输出 `hex(nonce || ciphertext)`。
+///
+/// `key_hex` 为 32 字节密钥的 hex 表示（即 `security.data_encryption_key`）。
+pub fn encrypt_to_hex(key_hex: &str, plaintext: &[u8]) -> Result<String> {
+    let cipher = build_cipher(key_hex)?;
+    let mut nonce = [0u8; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = cipher
+        .encrypt(&Nonce::from(nonce), plaintext)
+        .map_err(|e| anyhow!("AES-GCM 加密失败: {e}"))?;
+
+    let mut out = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(hex_encode(&out))
+}
+
+pub fn decrypt_from_hex(key_hex: &str, sealed_hex: &str) -> Result<Vec<u8>> {
+    let cipher = build_cipher(key_hex)?;
+    let sealed = hex_decode(sealed_hex)?;
+    if sealed.len() <= NONCE_LEN {
+        return Err(anyhow!("密文长度不合法"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    let nonce: [u8; NONCE_LEN] = nonce.try_into().map_err(|_| anyhow!("nonce 长度不合法"))?;
+    cipher
+        .decrypt(&Nonce::from(nonce), ciphertext)
+        .map_err(|e| anyhow!("AES-GCM 解密失败: {e}"))
+}
+
+pub fn hex_decode(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    if !input.len().is_multiple_of(2) {
+        return Err(anyhow!("hex 长度不合法"));
+    }
+    (0..input.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&input[i..i + 2], 16).map_err(|e| anyhow!("hex 解析失败: {e}")))
+        .collect()
+}
+
+/// 常量时间比较，避免通过比较耗时泄露匹配前缀。
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn build_cipher(key_hex: &str) -> Result<Aes256Gcm> {
+    let key = hex_decode(key_hex)?;
+    Aes256Gcm::new_from_slice(&key).map_err(|_| anyhow!("加密密钥长度不合法：期望 32 字节"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_HEX: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    #[test]
+    fn should_roundtrip_encrypted_payload() {
+        let sealed = encrypt_to_hex(KEY_HEX, b"totp-secret").unwrap();
+        assert_ne!(sealed, hex_encode(b"totp-secret"));
+        assert_eq!(decrypt_from_hex(KEY_HEX, &sealed).unwrap(), b"totp-secret");
+    }
+
+    #[test]
+    fn should_reject_tampered_ciphertext() {
+        let mut sealed = encrypt_to_hex(KEY_HEX, b"totp-secret").unwrap();
+        let last = sealed.pop().unwrap();
+        sealed.push(if last == '0' { '1' } else { '0' });
+        assert!(decrypt_from_hex(KEY_HEX, &sealed).is_err());
+    }
+}
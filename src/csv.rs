@@ -0,0 +1,156 @@
+use anyhow::{anyhow, Result};
+
+/// 一条 CSV 记录及其起始行号（从 1 开始），用于逐行报告错误。
+#[derive(Debug, PartialEq, Eq)]
+pub struct Record {
+    pub line: usize,
+    pub fields: Vec<String>,
+}
+
+/// 按 RFC 4180 解析 CSV：逗号分隔，双引号包裹的字段可包含逗号、换行与转义的 `""`。
+///
+/// 兼容 `\r\n` / `\n` 换行与 UTF-8 BOM，跳过空行。
+pub fn parse(input: &str) -> Result<Vec<Record>> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut line = 1;
+    let mut record_line = 1;
+    let mut chars = input.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(ch);
+                }
+                _ => field.push(ch),
+            }
+            continue;
+        }
+        match ch {
+            '"' if field.is_empty() && !quoted => {
+                in_quotes = true;
+                quoted = true;
+            }
+            '"' => return Err(anyhow!("第 {line} 行：引号只能出现在字段开头")),
+            ',' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                fields.push(std::mem::take(&mut field));
+                quoted = false;
+                push_record(&mut records, record_line, std::mem::take(&mut fields));
+                line += 1;
+                record_line = line;
+            }
+            _ if quoted => return Err(anyhow!("第 {line} 行：引号字段结束后只能是分隔符")),
+            _ => field.push(ch),
+        }
+    }
+    if in_quotes {
+        return Err(anyhow!("第 {record_line} 行：引号未闭合"));
+    }
+    fields.push(field);
+    push_record(&mut records, record_line, fields);
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<Record>, line: usize, fields: Vec<String>) {
+    if fields.len() == 1 && fields[0].is_empty() {
+        return;
+    }
+    records.push(Record { line, fields });
+}
+
+/// 生成一行 CSV（含结尾 `\r\n`）；包含逗号、引号或换行的字段加引号转义。
+///
+/// 以 `=`、`+`、`-`、`@`、制表符或回车开头的字段前加 `'`，
+/// 防止在电子表格中打开时被当作公式执行（CSV 注入）。
+pub fn write_record<I, S>(fields: I) -> String
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut out = String::new();
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        let field = field.as_ref();
+        let escaped;
+        let field = if field.starts_with(FORMULA_PREFIXES) {
+            escaped = format!("'{field}");
+            escaped.as_str()
+        } else {
+            field
+        };
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+    out
+}
+
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_quoted_fields_and_track_lines() {
+        let input = "\u{feff}a,b\r\n\"x, y\",\"say \"\"hi\"\"\"\n\n\"multi\nline\",\nlast,";
+        let records = parse(input).unwrap();
+        let fields: Vec<_> = records.iter().map(|r| (r.line, r.fields.clone())).collect();
+        assert_eq!(
+            fields,
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (2, vec!["x, y".to_string(), "say \"hi\"".to_string()]),
+                (4, vec!["multi\nline".to_string(), String::new()]),
+                (6, vec!["last".to_string(), String::new()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_reject_malformed_quotes() {
+        assert!(parse("a,\"b").is_err());
+        assert!(parse("a,b\"c\"").is_err());
+        assert!(parse("\"a\"b,c").is_err());
+    }
+
+    #[test]
+    fn write_record_should_round_trip() {
+        let fields = ["plain", "with,comma", "with \"quote\"", "multi\nline", ""];
+        let line = write_record(fields);
+        assert_eq!(
+            line,
+            "plain,\"with,comma\",\"with \"\"quote\"\"\",\"multi\nline\",\r\n"
+        );
+        assert_eq!(parse(&line).unwrap()[0].fields, fields);
+    }
+
+    #[test]
+    fn write_record_should_neutralize_formula_prefixes() {
+        let line = write_record(["=1+1", "+1", "-1", "@SUM(A1)", "\tx", "\rx", "a=b"]);
+        assert_eq!(line, "'=1+1,'+1,'-1,'@SUM(A1),'\tx,\"'\rx\",a=b\r\n");
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -17,6 +17,15 @@ pub enum AppError {
     AuthCredentialError(String),
     #[error("权限不足: {0}")]
     PermissionDenied(String),
+    #[error("会话受限: {0}")]
+    SessionRestricted(String),
+    #[error("需要修改密码: {0}")]
+    PasswordChangeRequired(String),
+    #[error("请求过于频繁: {message}")]
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u64,
+    },
     #[error("验证失败: {message}")]
     ValidationError {
         message: String,
@@ -24,6 +33,8 @@ pub enum AppError {
     },
     #[error("未找到资源: {0}")]
     NotFound(String),
+    #[error("资源已被修改: {0}")]
+    PreconditionFailed(String),
     #[error("服务器内部错误: {0}")]
     InternalError(String),
     #[error(transparent)]
@@ -39,6 +50,25 @@ impl AppError {
         Self::AuthCredentialError(message.into())
     }
 
+    pub fn session_restricted(message: impl Into<String>) -> Self {
+        Self::SessionRestricted(message.into())
+    }
+
+    pub fn password_change_required(message: impl Into<String>) -> Self {
+        Self::PasswordChangeRequired(message.into())
+    }
+
+    pub fn too_many_requests(message: impl Into<String>, retry_after_secs: u64) -> Self {
+        Self::TooManyRequests {
+            message: message.into(),
+            retry_after_secs,
+        }
+    }
+
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::PreconditionFailed(message.into())
+    }
+
     pub fn validation(message: impl Into<String>) -> Self {
         Self::ValidationError {
             message: message.into(),
@@ -73,8 +103,12 @@ impl AppError {
             AppError::ValidationError { .. } => 1000,
             AppError::AuthTokenError(_) => 1001,
             AppError::AuthCredentialError(_) => 1002,
+            AppError::SessionRestricted(_) => 1003,
+            AppError::TooManyRequests { .. } => 1004,
+            AppError::PasswordChangeRequired(_) => 1005,
             AppError::PermissionDenied(_) => 2002,
             AppError::NotFound(_) => 2000,
+            AppError::PreconditionFailed(_) => 2003,
             AppError::InternalError(_) => 5000,
             AppError::Unknown(_) => 5000,
         }
@@ -85,8 +119,12 @@ impl AppError {
             AppError::AuthTokenError(_) => StatusCode::UNAUTHORIZED,
             AppError::AuthCredentialError(_) => StatusCode::UNAUTHORIZED,
             AppError::PermissionDenied(_) => StatusCode::FORBIDDEN,
+            AppError::SessionRestricted(_) => StatusCode::FORBIDDEN,
+            AppError::PasswordChangeRequired(_) => StatusCode::FORBIDDEN,
+            AppError::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
             AppError::ValidationError { .. } => StatusCode::BAD_REQUEST,
             AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
             AppError::InternalError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
@@ -115,6 +153,12 @@ impl IntoResponse for AppError {
             AppError::ValidationError { details, .. } => details.clone(),
             _ => None,
         };
+        let retry_after = match &self {
+            AppError::TooManyRequests {
+                retry_after_secs, ..
+            } => Some(*retry_after_secs),
+            _ => None,
+        };
         let request_id = current_request_id().unwrap_or_else(|| "req_unknown".to_string());
 
         if matches!(self, AppError::InternalError(_) | AppError::Unknown(_)) {
@@ -128,7 +172,13 @@ impl IntoResponse for AppError {
             request_id,
         });
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
     }
 }
 
@@ -145,4 +195,46 @@ mod tests {
     fn credential_auth_error_should_use_1002() {
         assert_eq!(AppError::auth_credential("密码错误").error_code(), 1002);
     }
+
+    #[test]
+    fn session_restricted_error_should_use_1003() {
+        let err = AppError::session_restricted("请先启用两步验证");
+        assert_eq!(err.error_code(), 1003);
+        assert_eq!(err.status_code(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn password_change_required_error_should_use_1005() {
+        let err = AppError::password_change_required("请先修改密码");
+        assert_eq!(err.error_code(), 1005);
+        assert_eq!(err.status_code(), axum::http::StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn precondition_failed_error_should_use_2003() {
+        let err = AppError::precondition_failed("用户已被其他人修改");
+        assert_eq!(err.error_code(), 2003);
+        assert_eq!(
+            err.status_code(),
+            axum::http::StatusCode::PRECONDITION_FAILED
+        );
+    }
+
+    #[test]
+    fn too_many_requests_error_should_use_1004_and_set_retry_after() {
+        use axum::response::IntoResponse;
+
+        let err = AppError::too_many_requests("登录失败次数过多", 30);
+        assert_eq!(err.error_code(), 1004);
+
+        let response = err.into_response();
+        assert_eq!(response.status(), axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response
+                .headers()
+                .get(axum::http::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok()),
+            Some("30")
+        );
+    }
 }
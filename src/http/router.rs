@@ -1,7 +1,7 @@
 use arc_swap::ArcSwap;
 use axum::{
     middleware,
-    routing::{any, delete, get, patch, post},
+    routing::{any, delete, get, patch, post, put},
     Router,
 };
 use std::sync::Arc;
@@ -13,14 +13,38 @@ use crate::api::openapi::ApiDoc;
 use crate::config::runtime::RuntimeConfig;
 use crate::db::DbPool;
 use crate::error::AppError;
-use crate::modules::security::handlers::patch_current_user_password_handler;
+use crate::modules::groups::handlers::{
+    create_group_handler, delete_group_handler, delete_group_member_handler,
+    list_group_members_handler, list_groups_handler, patch_group_handler, put_group_member_handler,
+};
+use crate::modules::organizations::handlers::{
+    create_organization_handler, delete_organization_handler, delete_organization_member_handler,
+    list_my_organizations_handler, list_organization_members_handler, list_organizations_handler,
+    patch_organization_handler, put_organization_member_handler,
+};
+use crate::modules::roles::handlers::{
+    create_role_handler, delete_role_handler, list_permissions_handler, list_roles_handler,
+    patch_role_handler,
+};
+use crate::modules::security::handlers::{
+    confirm_password_reset_handler, confirm_totp_enrollment_handler, create_api_token_handler,
+    disable_totp_handler, get_totp_status_handler, list_api_tokens_handler, list_jwt_keys_handler,
+    patch_current_user_password_handler, regenerate_totp_recovery_codes_handler,
+    request_password_reset_handler, revoke_api_token_handler, rotate_jwt_key_handler,
+    start_totp_enrollment_handler,
+};
 use crate::modules::sessions::handlers::{
-    create_session_handler, delete_current_session_handler, refresh_session_handler,
+    complete_mfa_challenge_handler, create_session_handler, delete_current_session_handler,
+    delete_other_sessions_handler, delete_session_handler, jwks_handler, list_sessions_handler,
+    refresh_session_handler, start_impersonation_handler,
 };
 use crate::modules::settings::handlers::{get_settings_handler, patch_settings_handler};
 use crate::modules::users::handlers::{
-    create_user_handler, delete_user_handler, get_current_user_handler, get_users_handler,
-    patch_current_user_handler, patch_user_handler, restore_user_handler,
+    create_user_handler, delete_current_user_handler, delete_user_handler,
+    export_current_user_handler, export_users_handler, get_current_user_handler, get_user_handler,
+    get_users_handler, import_users_handler, issue_temporary_password_handler,
+    patch_current_user_handler, patch_user_handler, purge_user_handler, restore_user_handler,
+    set_user_password_handler, unlock_user_handler,
 };
 use crate::web_assets::{serve_frontend_index, serve_frontend_path};
 
@@ -38,13 +62,36 @@ impl AppState {
         self.config.store(Arc::new(runtime));
         Ok(())
     }
+
+    /// JWT 密钥可能已被其他实例轮换：本地密钥环超过 `max_age` 未刷新时重新加载运行期配置。
+    pub async fn runtime_with_fresh_jwt_keys(
+        &self,
+        max_age: std::time::Duration,
+    ) -> Result<Arc<RuntimeConfig>, AppError> {
+        let cfg = self.config.load_full();
+        if !cfg.security.jwt_keys.is_stale(max_age) {
+            return Ok(cfg);
+        }
+        self.reload_runtime().await?;
+        Ok(self.config.load_full())
+    }
 }
 
 pub fn app_router(state: AppState) -> Router {
     let public_routes = Router::new()
         .route("/api/v1/health", get(health_check))
         .route("/api/v1/sessions", post(create_session_handler))
-        .route("/api/v1/sessions/refresh", post(refresh_session_handler));
+        .route("/api/v1/sessions/refresh", post(refresh_session_handler))
+        .route("/api/v1/sessions/mfa", post(complete_mfa_challenge_handler))
+        .route(
+            "/api/v1/security/password-reset",
+            post(request_password_reset_handler),
+        )
+        .route(
+            "/api/v1/security/password-reset/confirm",
+            post(confirm_password_reset_handler),
+        )
+        .route("/.well-known/jwks.json", get(jwks_handler));
 
     let protected_routes = Router::new()
         .route(
@@ -55,26 +102,128 @@ pub fn app_router(state: AppState) -> Router {
             "/api/v1/security/password",
             patch(patch_current_user_password_handler),
         )
+        .route(
+            "/api/v1/security/totp",
+            get(get_totp_status_handler)
+                .post(start_totp_enrollment_handler)
+                .delete(disable_totp_handler),
+        )
+        .route(
+            "/api/v1/security/totp/confirm",
+            post(confirm_totp_enrollment_handler),
+        )
+        .route(
+            "/api/v1/security/totp/recovery-codes",
+            post(regenerate_totp_recovery_codes_handler),
+        )
+        .route("/api/v1/security/jwt-keys", get(list_jwt_keys_handler))
+        .route(
+            "/api/v1/security/jwt-keys/rotate",
+            post(rotate_jwt_key_handler),
+        )
+        .route(
+            "/api/v1/security/tokens",
+            get(list_api_tokens_handler).post(create_api_token_handler),
+        )
+        .route(
+            "/api/v1/security/tokens/{token_id}",
+            delete(revoke_api_token_handler),
+        )
+        .route("/api/v1/sessions", get(list_sessions_handler))
         .route(
             "/api/v1/sessions/current",
             delete(delete_current_session_handler),
         )
+        .route(
+            "/api/v1/sessions/impersonation",
+            post(start_impersonation_handler),
+        )
+        .route(
+            "/api/v1/sessions/others",
+            delete(delete_other_sessions_handler),
+        )
+        .route(
+            "/api/v1/sessions/{session_id}",
+            delete(delete_session_handler),
+        )
+        .route(
+            "/api/v1/roles",
+            get(list_roles_handler).post(create_role_handler),
+        )
+        .route(
+            "/api/v1/roles/{role}",
+            patch(patch_role_handler).delete(delete_role_handler),
+        )
+        .route("/api/v1/permissions", get(list_permissions_handler))
+        .route(
+            "/api/v1/groups",
+            get(list_groups_handler).post(create_group_handler),
+        )
+        .route(
+            "/api/v1/groups/{group_id}",
+            patch(patch_group_handler).delete(delete_group_handler),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/members",
+            get(list_group_members_handler),
+        )
+        .route(
+            "/api/v1/groups/{group_id}/members/{user_id}",
+            put(put_group_member_handler).delete(delete_group_member_handler),
+        )
+        .route(
+            "/api/v1/organizations",
+            get(list_organizations_handler).post(create_organization_handler),
+        )
+        .route(
+            "/api/v1/organizations/{organization_id}",
+            patch(patch_organization_handler).delete(delete_organization_handler),
+        )
+        .route(
+            "/api/v1/organizations/{organization_id}/members",
+            get(list_organization_members_handler),
+        )
+        .route(
+            "/api/v1/organizations/{organization_id}/members/{user_id}",
+            put(put_organization_member_handler).delete(delete_organization_member_handler),
+        )
         .route(
             "/api/v1/users",
             get(get_users_handler).post(create_user_handler),
         )
+        .route("/api/v1/users/export", get(export_users_handler))
+        .route("/api/v1/users/import", post(import_users_handler))
         .route(
             "/api/v1/users/me",
-            get(get_current_user_handler).patch(patch_current_user_handler),
+            get(get_current_user_handler)
+                .patch(patch_current_user_handler)
+                .delete(delete_current_user_handler),
+        )
+        .route("/api/v1/users/me/export", get(export_current_user_handler))
+        .route(
+            "/api/v1/users/me/organizations",
+            get(list_my_organizations_handler),
         )
         .route(
             "/api/v1/users/{user_id}",
-            patch(patch_user_handler).delete(delete_user_handler),
+            get(get_user_handler)
+                .patch(patch_user_handler)
+                .delete(delete_user_handler),
         )
         .route(
             "/api/v1/users/{user_id}/restore",
             post(restore_user_handler),
         )
+        .route("/api/v1/users/{user_id}/purge", post(purge_user_handler))
+        .route("/api/v1/users/{user_id}/unlock", post(unlock_user_handler))
+        .route(
+            "/api/v1/users/{user_id}/password",
+            put(set_user_password_handler),
+        )
+        .route(
+            "/api/v1/users/{user_id}/temporary-password",
+            post(issue_temporary_password_handler),
+        )
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
@@ -134,6 +283,8 @@ mod tests {
     use uuid::Uuid;
 
     const E2E_JWT_SECRET: &str = "router-tests-shared-jwt-secret";
+    const E2E_DATA_ENCRYPTION_KEY: &str =
+        "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
 
     async fn request_json(
         server: &TestServer,
@@ -291,16 +442,19 @@ RETURNING id
         sqlx::query!(
             r#"
 INSERT INTO system_config (key, value, description)
-VALUES ('security.jwt_secret', $1, 'e2e test secret')
+VALUES
+    ('security.jwt_secret', $1, 'e2e test secret'),
+    ('security.data_encryption_key', $2, 'e2e test encryption key')
 ON CONFLICT (key) DO UPDATE
 SET value = EXCLUDED.value,
     updated_at = NOW()
             "#,
             Value::String(jwt_secret),
+            Value::String(E2E_DATA_ENCRYPTION_KEY.to_string()),
         )
         .execute(&pool)
         .await
-        .expect("写入测试 jwt secret 失败");
+        .expect("写入测试密钥失败");
 
         let runtime = crate::config::runtime::RuntimeConfig::load_from_db(&pool)
             .await
@@ -347,6 +501,10 @@ SET value = EXCLUDED.value,
         (token, cookie_pair)
     }
 
+    mod groups;
+    mod mfa;
+    mod organizations;
+    mod roles;
     mod security;
     mod sessions;
     mod settings;
@@ -0,0 +1,241 @@
+use super::*;
+
+use serde_json::{json, Value};
+
+async fn create_group(server: &TestServer, token: &str, permissions: Value) -> TestResponse {
+    request_json(
+        server,
+        Method::POST,
+        "/api/v1/groups",
+        Some(token),
+        None,
+        Some(json!({
+            "name": format!("group {}", Uuid::new_v4().simple()),
+            "permissions": permissions,
+        })),
+    )
+    .await
+}
+
+fn group_id(response: TestResponse) -> String {
+    response
+        .json::<Value>()
+        .get("id")
+        .and_then(Value::as_str)
+        .expect("创建用户组应返回 id")
+        .to_string()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn group_permissions_should_extend_role_permissions(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("grouped_{}", Uuid::new_v4().simple());
+    let password = "GroupedPassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let list_users = || {
+        request_json(
+            &server,
+            Method::GET,
+            "/api/v1/users",
+            Some(&token),
+            None,
+            None,
+        )
+    };
+    assert_eq!(list_users().await.status_code(), StatusCode::FORBIDDEN);
+
+    let created = create_group(&server, &admin_token, json!(["users:read", "users:read"])).await;
+    assert_eq!(created.status_code(), StatusCode::CREATED);
+    let created_body = created.json::<Value>();
+    assert_eq!(created_body["permissions"], json!(["users:read"]));
+    let group_id = group_id(created);
+
+    let add_member = request_json(
+        &server,
+        Method::PUT,
+        &format!("/api/v1/groups/{group_id}/members/{user_id}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(add_member.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        list_users().await.status_code(),
+        StatusCode::OK,
+        "组内权限与角色权限合并生效"
+    );
+
+    let members = request_json(
+        &server,
+        Method::GET,
+        &format!("/api/v1/groups/{group_id}/members"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(members.status_code(), StatusCode::OK);
+    assert_eq!(members.json::<Value>()[0]["user_id"], json!(user_id));
+
+    for (body, expected) in [
+        (
+            json!({ "permissions": ["users:destroy"] }),
+            StatusCode::BAD_REQUEST,
+        ),
+        (json!({ "name": "  " }), StatusCode::BAD_REQUEST),
+        (json!({ "permissions": [] }), StatusCode::OK),
+    ] {
+        let response = request_json(
+            &server,
+            Method::PATCH,
+            &format!("/api/v1/groups/{group_id}"),
+            Some(&admin_token),
+            None,
+            Some(body),
+        )
+        .await;
+        assert_eq!(response.status_code(), expected);
+    }
+    assert_eq!(
+        list_users().await.status_code(),
+        StatusCode::FORBIDDEN,
+        "收回组内权限后立即生效"
+    );
+
+    let remove_member = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/groups/{group_id}/members/{user_id}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(remove_member.status_code(), StatusCode::NO_CONTENT);
+
+    let delete_group = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/groups/{group_id}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(delete_group.status_code(), StatusCode::NO_CONTENT);
+
+    let event_count = sqlx::query_scalar!(
+        r#"
+SELECT COUNT(*) AS "count!"
+FROM security_events
+WHERE event_type LIKE 'group_%'
+  AND details->>'group_id' = $1
+        "#,
+        group_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询用户组安全事件失败");
+    assert_eq!(event_count, 5);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn group_management_should_not_escalate(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let role_name = format!("grouper_{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let role_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/roles",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "name": role_name,
+            "permissions": ["groups:read", "groups:write", "users:read"],
+        })),
+    )
+    .await;
+    assert_eq!(role_response.status_code(), StatusCode::CREATED);
+
+    let username = format!("grouper_user_{}", Uuid::new_v4().simple());
+    let password = "GrouperPassword#A123";
+    let grouper_id = create_user_with_password(&pool, &username, password).await;
+    sqlx::query!(
+        "UPDATE users SET role = $2 WHERE id = $1",
+        grouper_id,
+        role_name
+    )
+    .execute(&pool)
+    .await
+    .expect("设置自定义角色失败");
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let escalated = create_group(&server, &token, json!(["users:write"])).await;
+    assert_eq!(escalated.status_code(), StatusCode::FORBIDDEN);
+
+    let own_group = create_group(&server, &token, json!(["users:read"])).await;
+    assert_eq!(own_group.status_code(), StatusCode::CREATED);
+    let own_group_id = group_id(own_group);
+
+    let admin_group = create_group(&server, &admin_token, json!(["users:write"])).await;
+    assert_eq!(admin_group.status_code(), StatusCode::CREATED);
+    let admin_group_id = group_id(admin_group);
+
+    for (method, uri, body) in [
+        (
+            Method::PUT,
+            format!("/api/v1/groups/{admin_group_id}/members/{grouper_id}"),
+            None,
+        ),
+        (
+            Method::PATCH,
+            format!("/api/v1/groups/{admin_group_id}"),
+            Some(json!({ "permissions": [] })),
+        ),
+        (
+            Method::DELETE,
+            format!("/api/v1/groups/{admin_group_id}"),
+            None,
+        ),
+        (
+            Method::PUT,
+            format!("/api/v1/groups/{own_group_id}/members/{admin_id}"),
+            None,
+        ),
+    ] {
+        let response = request_json(&server, method, &uri, Some(&token), None, body).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "{uri} 不应允许借用户组提权"
+        );
+    }
+
+    let add_self = request_json(
+        &server,
+        Method::PUT,
+        &format!("/api/v1/groups/{own_group_id}/members/{grouper_id}"),
+        Some(&token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(add_self.status_code(), StatusCode::NO_CONTENT);
+
+    cleanup_test_users(&pool, &[grouper_id]).await;
+}
@@ -0,0 +1,248 @@
+use super::*;
+
+use serde_json::Value;
+
+async fn enroll_totp(server: &TestServer, token: &str, password: &str) -> (Vec<u8>, Vec<String>) {
+    let start_response = request_json(
+        server,
+        Method::POST,
+        "/api/v1/security/totp",
+        Some(token),
+        None,
+        Some(serde_json::json!({ "current_password": password })),
+    )
+    .await;
+    assert_eq!(start_response.status_code(), StatusCode::OK);
+    let start_body = start_response.json::<Value>();
+    let secret_base32 = start_body
+        .get("secret")
+        .and_then(Value::as_str)
+        .expect("绑定响应缺少 secret");
+    assert!(start_body
+        .get("otpauth_uri")
+        .and_then(Value::as_str)
+        .is_some_and(|uri| uri.starts_with("otpauth://totp/")));
+    let secret = crate::totp::base32_decode(secret_base32).expect("secret 应为合法 base32");
+
+    let step = crate::totp::step_at(chrono::Utc::now().timestamp());
+    let code = crate::totp::code_at_step(&secret, step).expect("计算 TOTP 失败");
+    let confirm_response = request_json(
+        server,
+        Method::POST,
+        "/api/v1/security/totp/confirm",
+        Some(token),
+        None,
+        Some(serde_json::json!({ "code": code })),
+    )
+    .await;
+    assert_eq!(confirm_response.status_code(), StatusCode::OK);
+    let recovery_codes = confirm_response
+        .json::<Value>()
+        .get("recovery_codes")
+        .and_then(Value::as_array)
+        .expect("确认响应缺少 recovery_codes")
+        .iter()
+        .map(|v| v.as_str().expect("恢复码应为字符串").to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(recovery_codes.len(), 10);
+
+    (secret, recovery_codes)
+}
+
+async fn start_mfa_login(server: &TestServer, identifier: &str, password: &str) -> String {
+    let response = request_json(
+        server,
+        Method::POST,
+        "/api/v1/sessions",
+        None,
+        None,
+        Some(serde_json::json!({
+            "identifier": identifier,
+            "password": password,
+        })),
+    )
+    .await;
+    assert_eq!(response.status_code(), StatusCode::ACCEPTED);
+    assert!(
+        response.maybe_cookie("refresh_token").is_none(),
+        "MFA 挑战阶段不应下发 refresh cookie"
+    );
+
+    response
+        .json::<Value>()
+        .get("mfa_token")
+        .and_then(Value::as_str)
+        .expect("挑战响应缺少 mfa_token")
+        .to_string()
+}
+
+async fn complete_mfa_login(server: &TestServer, mfa_token: &str, code: &str) -> TestResponse {
+    request_json(
+        server,
+        Method::POST,
+        "/api/v1/sessions/mfa",
+        None,
+        None,
+        Some(serde_json::json!({
+            "mfa_token": mfa_token,
+            "code": code,
+        })),
+    )
+    .await
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn totp_login_should_require_second_factor_and_consume_recovery_codes(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("mfa_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "MfaPassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    let (access_token, _) = login_and_get_tokens(&server, &username, password).await;
+    let (secret, recovery_codes) = enroll_totp(&server, &access_token, password).await;
+
+    let mfa_token = start_mfa_login(&server, &username, password).await;
+
+    let wrong_code_response = complete_mfa_login(&server, &mfa_token, "not-a-valid-code").await;
+    assert_eq!(wrong_code_response.status_code(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        wrong_code_response
+            .json::<Value>()
+            .get("code")
+            .and_then(Value::as_u64),
+        Some(1002)
+    );
+
+    // 绑定确认已用掉当前步长，这里使用下一个步长（仍在允许的时钟偏移内）。
+    let step = crate::totp::step_at(chrono::Utc::now().timestamp());
+    let next_code = crate::totp::code_at_step(&secret, step + 1).expect("计算 TOTP 失败");
+    let totp_response = complete_mfa_login(&server, &mfa_token, &next_code).await;
+    assert_eq!(totp_response.status_code(), StatusCode::OK);
+    assert!(totp_response.json::<Value>().get("token").is_some());
+    assert!(!totp_response.cookie("refresh_token").value().is_empty());
+
+    let reused_challenge = complete_mfa_login(&server, &mfa_token, &next_code).await;
+    assert_eq!(reused_challenge.status_code(), StatusCode::UNAUTHORIZED);
+
+    let recovery_mfa_token = start_mfa_login(&server, &username, password).await;
+    let recovery_response =
+        complete_mfa_login(&server, &recovery_mfa_token, &recovery_codes[0]).await;
+    assert_eq!(recovery_response.status_code(), StatusCode::OK);
+
+    let reused_recovery_token = start_mfa_login(&server, &username, password).await;
+    let reused_recovery_response =
+        complete_mfa_login(&server, &reused_recovery_token, &recovery_codes[0]).await;
+    assert_eq!(
+        reused_recovery_response.status_code(),
+        StatusCode::UNAUTHORIZED
+    );
+
+    let stored_secret = sqlx::query_scalar!(
+        "SELECT secret_ciphertext FROM user_totp WHERE user_id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询 TOTP 密钥失败");
+    assert_ne!(
+        stored_secret,
+        crate::config::seed::hex_encode(&secret),
+        "TOTP 密钥不应明文落库"
+    );
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn admin_without_totp_should_be_restricted_when_mfa_is_required(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", password).await;
+
+    let patch_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        Some(serde_json::json!({ "security": { "mfa_required_for_admin": true } })),
+    )
+    .await;
+    assert_eq!(patch_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        patch_response.json::<Value>()["security"]["mfa_required_for_admin"],
+        Value::Bool(true)
+    );
+
+    let (restricted_token, refresh_cookie) = login_and_get_tokens(&server, "admin", password).await;
+
+    let settings_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&restricted_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(settings_response.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        settings_response
+            .json::<Value>()
+            .get("code")
+            .and_then(Value::as_u64),
+        Some(1003)
+    );
+
+    let status_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/security/totp",
+        Some(&restricted_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(status_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        status_response.json::<Value>()["required"],
+        Value::Bool(true)
+    );
+
+    enroll_totp(&server, &restricted_token, password).await;
+
+    // 完成绑定后刷新即可拿到不受限的 access token。
+    let refresh_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(refresh_response.status_code(), StatusCode::OK);
+    let refreshed_token = refresh_response
+        .json::<Value>()
+        .get("token")
+        .and_then(Value::as_str)
+        .expect("刷新响应缺少 token")
+        .to_string();
+
+    let settings_after_enroll = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&refreshed_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(settings_after_enroll.status_code(), StatusCode::OK);
+
+    cleanup_test_users(&pool, &[admin_id]).await;
+}
@@ -0,0 +1,406 @@
+use super::*;
+
+use serde_json::{json, Value};
+
+async fn request_in_organization(
+    server: &TestServer,
+    method: Method,
+    uri: &str,
+    token: &str,
+    organization_id: Uuid,
+    body: Option<Value>,
+) -> TestResponse {
+    let mut request = server
+        .method(method, uri)
+        .add_header(header::ACCEPT, "application/json")
+        .add_header(
+            crate::services::organizations::ORGANIZATION_HEADER,
+            organization_id.to_string(),
+        )
+        .authorization_bearer(token);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    request.await
+}
+
+async fn create_organization(server: &TestServer, admin_token: &str) -> Uuid {
+    let slug = format!("org-{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let response = request_json(
+        server,
+        Method::POST,
+        "/api/v1/organizations",
+        Some(admin_token),
+        None,
+        Some(json!({ "slug": slug, "name": format!("组织 {slug}") })),
+    )
+    .await;
+    assert_eq!(response.status_code(), StatusCode::CREATED);
+    let id = response
+        .json::<Value>()
+        .get("id")
+        .and_then(Value::as_str)
+        .expect("创建组织应返回 id")
+        .to_string();
+    Uuid::parse_str(&id).expect("组织 id 应为 UUID")
+}
+
+async fn put_member(
+    server: &TestServer,
+    admin_token: &str,
+    organization_id: Uuid,
+    user_id: Uuid,
+    role: &str,
+) {
+    let response = request_json(
+        server,
+        Method::PUT,
+        &format!("/api/v1/organizations/{organization_id}/members/{user_id}"),
+        Some(admin_token),
+        None,
+        Some(json!({ "role": role })),
+    )
+    .await;
+    assert_eq!(response.status_code(), StatusCode::OK);
+}
+
+fn user_ids(response: TestResponse) -> Vec<String> {
+    response
+        .json::<Value>()
+        .as_array()
+        .expect("用户列表应为数组")
+        .iter()
+        .filter_map(|user| user.get("id").and_then(Value::as_str).map(str::to_string))
+        .collect()
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn organization_admin_should_only_manage_own_tenant(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let org_a = create_organization(&server, &admin_token).await;
+    let org_b = create_organization(&server, &admin_token).await;
+
+    let password = "TenantPassword#A123";
+    let alice_name = format!("alice_{}", Uuid::new_v4().simple());
+    let alice_id = create_user_with_password(&pool, &alice_name, password).await;
+    let bob_id =
+        create_user_with_password(&pool, &format!("bob_{}", Uuid::new_v4().simple()), password)
+            .await;
+    put_member(&server, &admin_token, org_a, alice_id, "admin").await;
+    put_member(&server, &admin_token, org_b, bob_id, "user").await;
+
+    let (alice_token, _) = login_and_get_tokens(&server, &alice_name, password).await;
+
+    // 全局角色仍是 user：没有组织上下文或进入其他组织都无权管理用户。
+    let platform_list = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(&alice_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(platform_list.status_code(), StatusCode::FORBIDDEN);
+    let other_org = request_in_organization(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        &alice_token,
+        org_b,
+        None,
+    )
+    .await;
+    assert_eq!(other_org.status_code(), StatusCode::FORBIDDEN);
+
+    let create_response = request_in_organization(
+        &server,
+        Method::POST,
+        "/api/v1/users",
+        &alice_token,
+        org_a,
+        Some(json!({
+            "display_name": "tenant member",
+            "email": format!("tenant_{}@example.invalid", Uuid::new_v4().simple()),
+            "role": "auditor",
+        })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let created = create_response.json::<Value>();
+    assert_eq!(
+        created.get("role").and_then(Value::as_str),
+        Some("auditor"),
+        "组织上下文中返回组织内角色"
+    );
+    let carol_id = Uuid::parse_str(created.get("id").and_then(Value::as_str).unwrap()).unwrap();
+    let global_role = sqlx::query_scalar!("SELECT role FROM users WHERE id = $1", carol_id)
+        .fetch_one(&pool)
+        .await
+        .expect("查询用户全局角色失败");
+    assert_eq!(global_role, "user");
+
+    let tenant_list = request_in_organization(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        &alice_token,
+        org_a,
+        None,
+    )
+    .await;
+    assert_eq!(tenant_list.status_code(), StatusCode::OK);
+    let mut listed = user_ids(tenant_list);
+    listed.sort();
+    let mut expected = vec![alice_id.to_string(), carol_id.to_string()];
+    expected.sort();
+    assert_eq!(listed, expected, "组织上下文只列出本组织成员");
+
+    // 组织级管理员不能触及其他组织的用户，也不能使用平台级接口。
+    let patch_outsider = request_in_organization(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{bob_id}"),
+        &alice_token,
+        org_a,
+        Some(json!({ "display_name": "taken over" })),
+    )
+    .await;
+    assert_eq!(patch_outsider.status_code(), StatusCode::NOT_FOUND);
+
+    for uri in ["/api/v1/organizations", "/api/v1/security/jwt-keys"] {
+        let response =
+            request_in_organization(&server, Method::GET, uri, &alice_token, org_a, None).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "{uri} 只能在平台上下文访问"
+        );
+    }
+
+    put_member(&server, &admin_token, org_a, bob_id, "user").await;
+    let reset_shared = request_in_organization(
+        &server,
+        Method::POST,
+        &format!("/api/v1/users/{bob_id}/temporary-password"),
+        &alice_token,
+        org_a,
+        None,
+    )
+    .await;
+    assert_eq!(
+        reset_shared.status_code(),
+        StatusCode::FORBIDDEN,
+        "同时属于其他组织的用户只能在平台上下文管理"
+    );
+
+    let my_organizations = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me/organizations",
+        Some(&alice_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(my_organizations.status_code(), StatusCode::OK);
+    let my_organizations = my_organizations.json::<Value>();
+    assert_eq!(my_organizations.as_array().map(Vec::len), Some(1));
+    assert_eq!(my_organizations[0]["id"], json!(org_a));
+    assert_eq!(my_organizations[0]["role"], json!("admin"));
+
+    cleanup_test_users(&pool, &[alice_id, bob_id, carol_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn organization_settings_should_override_global_config(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+    let organization_id = create_organization(&server, &admin_token).await;
+
+    let password = "TenantPassword#A123";
+    let username = format!("tenant_admin_{}", Uuid::new_v4().simple());
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    put_member(&server, &admin_token, organization_id, user_id, "admin").await;
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let global_before = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await
+    .json::<Value>();
+
+    let patch_response = request_in_organization(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        &token,
+        organization_id,
+        Some(json!({ "app": { "welcome_message": "欢迎来到本组织" } })),
+    )
+    .await;
+    assert_eq!(patch_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        patch_response.json::<Value>()["app"]["welcome_message"],
+        json!("欢迎来到本组织")
+    );
+
+    let tenant_settings = request_in_organization(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        &token,
+        organization_id,
+        None,
+    )
+    .await;
+    assert_eq!(
+        tenant_settings.json::<Value>()["app"]["welcome_message"],
+        json!("欢迎来到本组织")
+    );
+
+    let global_after = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await
+    .json::<Value>();
+    assert_eq!(
+        global_after["app"]["welcome_message"], global_before["app"]["welcome_message"],
+        "组织覆盖不影响全局配置"
+    );
+
+    let global_patch = request_in_organization(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        &token,
+        organization_id,
+        Some(json!({ "security": { "login_failure_window_secs": 1 } })),
+    )
+    .await;
+    assert_eq!(global_patch.status_code(), StatusCode::BAD_REQUEST);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn api_token_bound_to_organization_should_use_its_context(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+    let organization_id = create_organization(&server, &admin_token).await;
+    let other_organization_id = create_organization(&server, &admin_token).await;
+
+    let password = "TenantPassword#A123";
+    let username = format!("tenant_pat_{}", Uuid::new_v4().simple());
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    put_member(&server, &admin_token, organization_id, user_id, "auditor").await;
+    put_member(
+        &server,
+        &admin_token,
+        other_organization_id,
+        user_id,
+        "auditor",
+    )
+    .await;
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let rejected = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/tokens",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "name": "not a member",
+            "scopes": ["users:read"],
+            "organization_id": organization_id,
+        })),
+    )
+    .await;
+    assert_eq!(rejected.status_code(), StatusCode::FORBIDDEN);
+
+    let created = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/tokens",
+        Some(&token),
+        None,
+        Some(json!({
+            "name": "tenant reader",
+            "scopes": ["users:read"],
+            "organization_id": organization_id,
+        })),
+    )
+    .await;
+    assert_eq!(created.status_code(), StatusCode::CREATED);
+    let created = created.json::<Value>();
+    assert_eq!(created["organization_id"], json!(organization_id));
+    let api_token = created["token"].as_str().expect("应返回令牌明文");
+
+    let list_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(api_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_response.status_code(), StatusCode::OK);
+    assert_eq!(user_ids(list_response), vec![user_id.to_string()]);
+
+    let mismatched = request_in_organization(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        api_token,
+        other_organization_id,
+        None,
+    )
+    .await;
+    assert_eq!(mismatched.status_code(), StatusCode::FORBIDDEN);
+
+    let removed = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/organizations/{organization_id}/members/{user_id}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(removed.status_code(), StatusCode::NO_CONTENT);
+    let after_removal = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(api_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(after_removal.status_code(), StatusCode::UNAUTHORIZED);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
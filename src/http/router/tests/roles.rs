@@ -0,0 +1,265 @@
+use super::*;
+
+use serde_json::{json, Value};
+
+#[sqlx::test(migrations = "./migrations")]
+async fn permission_catalog_should_match_database(pool: sqlx::PgPool) {
+    let mut in_db = sqlx::query_scalar!("SELECT name FROM permissions")
+        .fetch_all(&pool)
+        .await
+        .expect("查询权限表失败");
+    in_db.sort();
+
+    let mut in_code = crate::api::permissions::ALL
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+    in_code.sort();
+
+    assert_eq!(in_db, in_code, "代码中的权限目录应与 permissions 表一致");
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn auditor_should_read_but_not_modify_users_and_settings(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("auditor_{}", Uuid::new_v4().simple());
+    let password = "AuditorPassword#A123";
+    let auditor_id = create_user_with_password(&pool, &username, password).await;
+    sqlx::query!(
+        "UPDATE users SET role = 'auditor' WHERE id = $1",
+        auditor_id
+    )
+    .execute(&pool)
+    .await
+    .expect("设置审计员角色失败");
+
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    for uri in ["/api/v1/users", "/api/v1/settings", "/api/v1/roles"] {
+        let response = request_json(&server, Method::GET, uri, Some(&token), None, None).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::OK,
+            "审计员读取 {uri} 应成功"
+        );
+    }
+
+    let writes = [
+        (
+            Method::PATCH,
+            "/api/v1/settings".to_string(),
+            Some(json!({ "app": { "welcome_message": "audited" } })),
+        ),
+        (
+            Method::POST,
+            "/api/v1/users".to_string(),
+            Some(json!({
+                "display_name": "created by auditor",
+                "email": format!("by_auditor_{}@example.invalid", Uuid::new_v4().simple()),
+            })),
+        ),
+        (
+            Method::PATCH,
+            format!("/api/v1/users/{auditor_id}"),
+            Some(json!({ "role": "admin" })),
+        ),
+        (
+            Method::POST,
+            "/api/v1/roles".to_string(),
+            Some(json!({ "name": "escalated", "permissions": [] })),
+        ),
+        (Method::GET, "/api/v1/security/jwt-keys".to_string(), None),
+    ];
+    for (method, uri, body) in writes {
+        let response = request_json(&server, method, &uri, Some(&token), None, body).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "审计员访问 {uri} 应返回 403"
+        );
+        assert_eq!(
+            response.json::<Value>().get("code").and_then(Value::as_u64),
+            Some(2002)
+        );
+    }
+
+    cleanup_test_users(&pool, &[auditor_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn custom_role_should_grant_permissions_without_escalation(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let role_name = format!("support_{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let create_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/roles",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "name": role_name,
+            "description": "客服",
+            "permissions": ["users:write", "users:read", "users:read"],
+        })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let created = create_response.json::<Value>();
+    assert_eq!(
+        created.get("permissions"),
+        Some(&json!(["users:read", "users:write"]))
+    );
+    assert_eq!(
+        created.get("is_builtin").and_then(Value::as_bool),
+        Some(false)
+    );
+
+    for (method, uri, body) in [
+        (
+            Method::POST,
+            "/api/v1/roles".to_string(),
+            json!({ "name": "bogus", "permissions": ["users:destroy"] }),
+        ),
+        (
+            Method::PATCH,
+            "/api/v1/roles/auditor".to_string(),
+            json!({ "permissions": ["users:read", "users:write"] }),
+        ),
+    ] {
+        let response =
+            request_json(&server, method, &uri, Some(&admin_token), None, Some(body)).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::BAD_REQUEST,
+            "{uri} 应拒绝未知权限或修改内置角色"
+        );
+    }
+
+    let username = format!("support_user_{}", Uuid::new_v4().simple());
+    let password = "SupportPassword#A123";
+    let support_id = create_user_with_password(&pool, &username, password).await;
+    let assign_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{support_id}"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "role": role_name })),
+    )
+    .await;
+    assert_eq!(assign_response.status_code(), StatusCode::OK);
+
+    let (support_token, _) = login_and_get_tokens(&server, &username, password).await;
+    let list_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(&support_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_response.status_code(), StatusCode::OK);
+
+    // 拥有 users:write 也不能提升自己为管理员，或管理权限更高的管理员账号。
+    for (method, uri, body) in [
+        (
+            Method::PATCH,
+            format!("/api/v1/users/{support_id}"),
+            Some(json!({ "role": "admin" })),
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/users/{admin_id}/temporary-password"),
+            None,
+        ),
+        (
+            Method::PATCH,
+            format!("/api/v1/users/{admin_id}"),
+            Some(json!({ "is_active": false })),
+        ),
+    ] {
+        let response = request_json(&server, method, &uri, Some(&support_token), None, body).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "自定义角色访问 {uri} 不应提权"
+        );
+    }
+
+    let delete_in_use = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/roles/{role_name}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(delete_in_use.status_code(), StatusCode::BAD_REQUEST);
+
+    // 角色权限每次请求从数据库加载，收回后已签发的 token 立即受影响。
+    let revoke_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/roles/{role_name}"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "permissions": [] })),
+    )
+    .await;
+    assert_eq!(revoke_response.status_code(), StatusCode::OK);
+    let list_after_revoke = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(&support_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_after_revoke.status_code(), StatusCode::FORBIDDEN);
+
+    let reassign_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{support_id}"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "role": "user" })),
+    )
+    .await;
+    assert_eq!(reassign_response.status_code(), StatusCode::OK);
+    cleanup_test_users(&pool, &[support_id]).await;
+
+    let delete_response = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/roles/{role_name}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(delete_response.status_code(), StatusCode::NO_CONTENT);
+
+    let event_count = sqlx::query_scalar!(
+        r#"
+SELECT COUNT(*) AS "count!"
+FROM security_events
+WHERE event_type IN ('role_created', 'role_updated', 'role_deleted')
+  AND details->>'role' = $1
+        "#,
+        role_name,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询角色安全事件失败");
+    assert_eq!(event_count, 3);
+}
@@ -120,3 +120,458 @@ async fn internal_error_should_not_expose_raw_database_message(_pool: sqlx::PgPo
     assert!(!message_lower.contains("duplicate"));
     assert!(!message_lower.contains("constraint"));
 }
+
+#[sqlx::test(migrations = "./migrations")]
+async fn jwt_key_rotation_should_keep_old_tokens_valid_during_grace_period(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, password).await;
+    let (legacy_token, _) = login_and_get_tokens(&server, "admin", password).await;
+    let legacy_header = jsonwebtoken::decode_header(&legacy_token).expect("解析 JWT header 失败");
+    assert_eq!(legacy_header.kid.as_deref(), Some("legacy"));
+
+    let rotate_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/jwt-keys/rotate",
+        Some(&legacy_token),
+        None,
+        Some(json!({ "grace_period_secs": 3600 })),
+    )
+    .await;
+    assert_eq!(rotate_response.status_code(), StatusCode::OK);
+    let keys = rotate_response.json::<Value>();
+    let active_kid = keys
+        .as_array()
+        .expect("密钥列表应为数组")
+        .iter()
+        .find(|k| k["is_active"] == Value::Bool(true))
+        .and_then(|k| k["kid"].as_str())
+        .expect("轮换后应存在签名密钥")
+        .to_string();
+    assert_ne!(active_kid, "legacy");
+
+    let (rotated_token, _) = login_and_get_tokens(&server, "admin", password).await;
+    let rotated_header = jsonwebtoken::decode_header(&rotated_token).expect("解析 JWT header 失败");
+    assert_eq!(rotated_header.kid.as_deref(), Some(active_kid.as_str()));
+
+    for token in [&legacy_token, &rotated_token] {
+        let me_response = request_json(
+            &server,
+            Method::GET,
+            "/api/v1/users/me",
+            Some(token),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(me_response.status_code(), StatusCode::OK);
+    }
+
+    // 宽限期为 0 时，上一代签名密钥签发的 token 立即失效。
+    let emergency_rotate = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/jwt-keys/rotate",
+        Some(&rotated_token),
+        None,
+        Some(json!({ "grace_period_secs": 0 })),
+    )
+    .await;
+    assert_eq!(emergency_rotate.status_code(), StatusCode::OK);
+
+    let retired_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&rotated_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(retired_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let legacy_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&legacy_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(legacy_response.status_code(), StatusCode::OK);
+
+    cleanup_test_users(&pool, &[admin_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn switching_jwt_algorithm_to_eddsa_should_publish_verifiable_jwks(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, password).await;
+    let (legacy_token, _) = login_and_get_tokens(&server, "admin", password).await;
+
+    let empty_jwks = server.get("/.well-known/jwks.json").await;
+    assert_eq!(empty_jwks.status_code(), StatusCode::OK);
+    assert_eq!(empty_jwks.json::<Value>(), json!({ "keys": [] }));
+
+    let patch_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        Some(&legacy_token),
+        None,
+        Some(json!({ "security": { "jwt_algorithm": "EdDSA" } })),
+    )
+    .await;
+    assert_eq!(patch_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        patch_response.json::<Value>()["security"]["jwt_algorithm"],
+        "EdDSA"
+    );
+
+    let (token, _) = login_and_get_tokens(&server, "admin", password).await;
+    let header = jsonwebtoken::decode_header(&token).expect("解析 JWT header 失败");
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+    let kid = header.kid.expect("EdDSA token 应携带 kid");
+
+    let jwks_response = server.get("/.well-known/jwks.json").await;
+    assert_eq!(jwks_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        jwks_response.header(header::CACHE_CONTROL),
+        "public, max-age=300"
+    );
+    let jwks = jwks_response.json::<Value>();
+    let keys = jwks["keys"].as_array().expect("keys 应为数组");
+    assert_eq!(keys.len(), 1, "HS256 legacy 密钥不应公开");
+    let jwk = &keys[0];
+    assert_eq!(jwk["kid"], Value::String(kid));
+    assert_eq!(jwk["kty"], "OKP");
+    assert_eq!(jwk["crv"], "Ed25519");
+    assert_eq!(jwk["use"], "sig");
+
+    // 其他服务仅凭公开的 JWK 即可校验 access token。
+    let decoding_key =
+        jsonwebtoken::DecodingKey::from_ed_components(jwk["x"].as_str().expect("JWK 缺少 x"))
+            .expect("解析 JWK 公钥失败");
+    let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::EdDSA);
+    validation.validate_aud = false;
+    let claims = jsonwebtoken::decode::<Value>(&token, &decoding_key, &validation)
+        .expect("使用 JWKS 校验 token 失败")
+        .claims;
+    assert_eq!(claims["sub"], Value::String(admin_id.to_string()));
+
+    // 切换算法时旧 HS256 token 仍在宽限期内可用。
+    for token in [&legacy_token, &token] {
+        let me_response = request_json(
+            &server,
+            Method::GET,
+            "/api/v1/users/me",
+            Some(token),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(me_response.status_code(), StatusCode::OK);
+    }
+
+    cleanup_test_users(&pool, &[admin_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn api_tokens_should_authenticate_within_scopes_until_revoked(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("e2e_pat_{}", Uuid::new_v4().simple());
+    let password = "PatPassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    let (session_token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let create_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/tokens",
+        Some(&session_token),
+        None,
+        Some(json!({ "name": "ci", "scopes": ["users:read"], "expires_in_days": 30 })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let created = create_response.json::<Value>();
+    let api_token = created["token"]
+        .as_str()
+        .expect("应返回令牌明文")
+        .to_string();
+    let token_id = created["id"].as_str().expect("应返回令牌 id").to_string();
+    assert!(api_token.starts_with("pat_"));
+    assert_eq!(created["scopes"], json!(["users:read"]));
+    assert!(created["expires_at"].is_string());
+
+    let duplicate_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/tokens",
+        Some(&session_token),
+        None,
+        Some(json!({ "name": "ci", "scopes": ["users:read"] })),
+    )
+    .await;
+    assert_eq!(duplicate_response.status_code(), StatusCode::BAD_REQUEST);
+
+    let me_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&api_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(me_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        me_response.json::<Value>()["id"],
+        Value::String(user_id.to_string())
+    );
+
+    // 缺少 scope 或访问安全/会话管理接口时一律拒绝。
+    for (method, uri, body) in [
+        (
+            Method::PATCH,
+            "/api/v1/users/me",
+            Some(json!({ "display_name": "pat" })),
+        ),
+        (Method::GET, "/api/v1/settings", None),
+        (Method::GET, "/api/v1/security/tokens", None),
+        (Method::GET, "/api/v1/sessions", None),
+    ] {
+        let response = request_json(&server, method, uri, Some(&api_token), None, body).await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN, "{uri}");
+        assert_eq!(
+            response.json::<Value>().get("code").and_then(Value::as_u64),
+            Some(2002)
+        );
+    }
+
+    let list_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/security/tokens",
+        Some(&session_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_response.status_code(), StatusCode::OK);
+    let tokens = list_response.json::<Value>();
+    let listed = tokens
+        .as_array()
+        .expect("令牌列表应为数组")
+        .iter()
+        .find(|t| t["id"] == Value::String(token_id.clone()))
+        .expect("列表应包含新建的令牌");
+    assert!(listed.get("token").is_none(), "列表不应回传令牌明文");
+    assert!(
+        listed["last_used_at"].is_string(),
+        "使用后应记录最近使用时间"
+    );
+
+    let revoke_response = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/security/tokens/{token_id}"),
+        Some(&session_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoke_response.status_code(), StatusCode::NO_CONTENT);
+
+    let revoked_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&api_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoked_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+async fn wait_for_mail(dir: &std::path::Path) -> String {
+    for _ in 0..50 {
+        if let Ok(mut entries) = std::fs::read_dir(dir) {
+            if let Some(entry) = entries.next() {
+                return std::fs::read_to_string(entry.expect("读取邮件目录失败").path())
+                    .expect("读取邮件文件失败");
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    panic!("等待重置密码邮件超时: {}", dir.display());
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn password_reset_should_email_single_use_link_and_revoke_sessions(pool: sqlx::PgPool) {
+    use base64::Engine as _;
+
+    let mail_dir = std::env::temp_dir().join(format!("e2e-mail-{}", Uuid::new_v4().simple()));
+    sqlx::query!(
+        r#"
+INSERT INTO system_config (key, value)
+VALUES ('mail.transport', '"file"'::jsonb), ('mail.file_dir', $1)
+ON CONFLICT (key) DO UPDATE
+SET value = EXCLUDED.value
+        "#,
+        Value::String(mail_dir.to_string_lossy().into_owned()),
+    )
+    .execute(&pool)
+    .await
+    .expect("写入邮件配置失败");
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("e2e_reset_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let old_password = "OldPassword#R123";
+    let new_password = "NewPassword#R123";
+    let user_id = create_user_with_password(&pool, &username, old_password).await;
+    let (_, old_refresh) = login_and_get_tokens(&server, &username, old_password).await;
+
+    let unknown_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/password-reset",
+        None,
+        None,
+        Some(json!({ "email": "nobody@example.invalid" })),
+    )
+    .await;
+    assert_eq!(unknown_response.status_code(), StatusCode::ACCEPTED);
+
+    let request_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/password-reset",
+        None,
+        None,
+        Some(json!({ "email": email.to_uppercase() })),
+    )
+    .await;
+    assert_eq!(request_response.status_code(), StatusCode::ACCEPTED);
+
+    let mail = wait_for_mail(&mail_dir).await;
+    assert!(mail.contains(&format!("To: {email}")));
+    let (_, encoded_body) = mail.split_once("\r\n\r\n").expect("邮件缺少正文");
+    let body = base64::engine::general_purpose::STANDARD
+        .decode(encoded_body.replace("\r\n", ""))
+        .expect("邮件正文应为 base64");
+    let body = String::from_utf8(body).expect("邮件正文应为 UTF-8");
+    let token = body
+        .split("token=")
+        .nth(1)
+        .and_then(|rest| rest.split_whitespace().next())
+        .expect("邮件中缺少重置链接")
+        .to_string();
+
+    let confirm = |token: String| {
+        request_json(
+            &server,
+            Method::POST,
+            "/api/v1/security/password-reset/confirm",
+            None,
+            None,
+            Some(json!({ "token": token, "new_password": new_password })),
+        )
+    };
+    assert_eq!(
+        confirm(token.clone()).await.status_code(),
+        StatusCode::NO_CONTENT
+    );
+
+    let reused = confirm(token).await;
+    assert_eq!(reused.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(reused.json::<Value>()["code"].as_u64(), Some(1000));
+
+    let old_login = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions",
+        None,
+        None,
+        Some(json!({ "identifier": username, "password": old_password })),
+    )
+    .await;
+    assert_eq!(old_login.status_code(), StatusCode::UNAUTHORIZED);
+    login_and_get_tokens(&server, &username, new_password).await;
+
+    let old_refresh_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&old_refresh),
+        None,
+    )
+    .await;
+    assert_eq!(old_refresh_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let _ = std::fs::remove_dir_all(&mail_dir);
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn password_reset_should_reject_users_without_username(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let user_id = sqlx::query_scalar!(
+        r#"
+INSERT INTO users (display_name, email, is_active, metadata)
+VALUES ('No Login', $1, TRUE, '{}'::jsonb)
+RETURNING id
+        "#,
+        format!("no_login_{}@example.invalid", Uuid::new_v4().simple()),
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("创建无用户名用户失败");
+
+    // 令牌对应的用户没有用户名时，确认应返回参数错误而非 500。
+    let generated = crate::services::password_reset::generate().expect("生成重置令牌失败");
+    sqlx::query!(
+        r#"
+INSERT INTO password_reset_tokens (id, user_id, secret_hash, expires_at)
+VALUES ($1, $2, $3, NOW() + INTERVAL '30 minutes')
+        "#,
+        generated.id,
+        user_id,
+        generated.secret_hash,
+    )
+    .execute(&pool)
+    .await
+    .expect("写入重置令牌失败");
+
+    let response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/password-reset/confirm",
+        None,
+        None,
+        Some(json!({ "token": generated.token, "new_password": "NewPassword#R123" })),
+    )
+    .await;
+    assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+    let password_hash =
+        sqlx::query_scalar!("SELECT password_hash FROM users WHERE id = $1", user_id)
+            .fetch_one(&pool)
+            .await
+            .expect("查询密码哈希失败");
+    assert!(password_hash.is_none());
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
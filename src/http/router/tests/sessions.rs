@@ -231,3 +231,802 @@ async fn concurrent_refresh_with_same_cookie_should_only_succeed_once(pool: sqlx
 
     cleanup_test_users(&pool, &[user_id]).await;
 }
+
+#[sqlx::test(migrations = "./migrations")]
+async fn user_should_list_and_revoke_own_sessions(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("device_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "DevicePassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    let other_username = format!("device_other_{}", Uuid::new_v4().simple());
+    let other_email = format!("{other_username}@example.invalid");
+    let other_password = "DeviceOtherPassword#A123";
+    let other_user_id =
+        create_or_update_user_with_password(&pool, &other_username, &other_email, other_password)
+            .await;
+
+    let (laptop_token, _) = login_and_get_tokens(&server, &username, password).await;
+    let (phone_token, _) = login_and_get_tokens(&server, &username, password).await;
+    let (tablet_token, _) = login_and_get_tokens(&server, &username, password).await;
+    let (other_token, _) = login_and_get_tokens(&server, &other_username, other_password).await;
+
+    let list_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/sessions",
+        Some(&laptop_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_response.status_code(), StatusCode::OK);
+    let sessions = list_response.json::<Value>();
+    let sessions = sessions.as_array().expect("会话列表响应体应为 JSON 数组");
+    assert_eq!(sessions.len(), 3, "应仅列出当前用户自己的有效会话");
+    let current_count = sessions
+        .iter()
+        .filter(|s| s.get("is_current").and_then(Value::as_bool) == Some(true))
+        .count();
+    assert_eq!(current_count, 1, "应恰好标记一个当前会话");
+
+    let phone_claims = decode::<crate::api::auth::Claims>(
+        &phone_token,
+        &DecodingKey::from_secret(E2E_JWT_SECRET.as_bytes()),
+        &Validation::default(),
+    )
+    .expect("解码 access token 失败")
+    .claims;
+
+    let foreign_revoke = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/sessions/{}", phone_claims.sid),
+        Some(&other_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(foreign_revoke.status_code(), StatusCode::NOT_FOUND);
+
+    let revoke_response = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/sessions/{}", phone_claims.sid),
+        Some(&laptop_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoke_response.status_code(), StatusCode::NO_CONTENT);
+
+    let phone_me = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&phone_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(phone_me.status_code(), StatusCode::UNAUTHORIZED);
+
+    let revoke_others_response = request_json(
+        &server,
+        Method::DELETE,
+        "/api/v1/sessions/others",
+        Some(&laptop_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoke_others_response.status_code(), StatusCode::NO_CONTENT);
+
+    let tablet_me = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&tablet_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(tablet_me.status_code(), StatusCode::UNAUTHORIZED);
+
+    for token in [&laptop_token, &other_token] {
+        let me = request_json(
+            &server,
+            Method::GET,
+            "/api/v1/users/me",
+            Some(token),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(
+            me.status_code(),
+            StatusCode::OK,
+            "当前会话与其他用户会话不应受影响"
+        );
+    }
+
+    cleanup_test_users(&pool, &[user_id, other_user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn sessions_should_record_client_metadata_on_login_and_refresh(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("client_meta_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "ClientMetaPassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    let login_response = server
+        .post("/api/v1/sessions")
+        .add_header(header::USER_AGENT, "PhoneApp/1.0")
+        .add_header("x-forwarded-for", "203.0.113.7, 10.0.0.1")
+        .json(&serde_json::json!({
+            "identifier": username,
+            "password": password,
+        }))
+        .await;
+    assert_eq!(login_response.status_code(), StatusCode::OK);
+    let refresh_cookie = format!(
+        "refresh_token={}",
+        login_response.cookie("refresh_token").value()
+    );
+
+    let refresh_response = server
+        .post("/api/v1/sessions/refresh")
+        .add_header(header::COOKIE, refresh_cookie)
+        .add_header(header::USER_AGENT, "PhoneApp/1.1")
+        .add_header("x-forwarded-for", "198.51.100.9")
+        .await;
+    assert_eq!(refresh_response.status_code(), StatusCode::OK);
+    let access_token = refresh_response
+        .json::<Value>()
+        .get("token")
+        .and_then(Value::as_str)
+        .expect("刷新响应缺少 token")
+        .to_string();
+
+    let list_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/sessions",
+        Some(&access_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(list_response.status_code(), StatusCode::OK);
+    let sessions = list_response.json::<Value>();
+    let session = sessions
+        .as_array()
+        .and_then(|items| items.first())
+        .expect("会话列表应包含当前会话");
+
+    assert_eq!(
+        session.get("created_ip").and_then(Value::as_str),
+        Some("203.0.113.7")
+    );
+    assert_eq!(
+        session.get("created_user_agent").and_then(Value::as_str),
+        Some("PhoneApp/1.0")
+    );
+    assert_eq!(
+        session.get("last_ip").and_then(Value::as_str),
+        Some("198.51.100.9")
+    );
+    assert_eq!(
+        session.get("last_user_agent").and_then(Value::as_str),
+        Some("PhoneApp/1.1")
+    );
+    assert!(
+        session
+            .get("last_refreshed_at")
+            .and_then(Value::as_str)
+            .is_some(),
+        "刷新后 last_refreshed_at 应有值"
+    );
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn replayed_refresh_token_should_revoke_whole_session(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("refresh_reuse_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "RefreshReusePassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    let (_, stolen_refresh_cookie) = login_and_get_tokens(&server, &username, password).await;
+
+    let rotate_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&stolen_refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(rotate_response.status_code(), StatusCode::OK);
+    let rotated_refresh_cookie = format!(
+        "refresh_token={}",
+        rotate_response.cookie("refresh_token").value()
+    );
+    let rotated_access_token = rotate_response
+        .json::<Value>()
+        .get("token")
+        .and_then(Value::as_str)
+        .expect("刷新响应缺少 token")
+        .to_string();
+
+    let replay_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&stolen_refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(replay_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let refresh_after_reuse = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&rotated_refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(
+        refresh_after_reuse.status_code(),
+        StatusCode::UNAUTHORIZED,
+        "检测到重放后，最新一代 refresh token 也应失效"
+    );
+
+    let me_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&rotated_access_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(me_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let revoked_reason = sqlx::query_scalar!(
+        "SELECT revoked_reason FROM auth_sessions WHERE user_id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询会话撤销原因失败");
+    assert_eq!(revoked_reason.as_deref(), Some("refresh_reuse_detected"));
+
+    let event_count = sqlx::query_scalar!(
+        r#"
+SELECT COUNT(*) AS "count!"
+FROM security_events
+WHERE user_id = $1
+  AND event_type = 'refresh_reuse_detected'
+        "#,
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询安全事件失败");
+    assert_eq!(event_count, 1);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn replaying_refresh_token_from_two_rotations_back_should_revoke_session(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let username = format!("refresh_reuse_old_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "RefreshReusePassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    let (_, stolen_refresh_cookie) = login_and_get_tokens(&server, &username, password).await;
+
+    let mut latest_refresh_cookie = stolen_refresh_cookie.clone();
+    for _ in 0..2 {
+        let rotate_response = request_json(
+            &server,
+            Method::POST,
+            "/api/v1/sessions/refresh",
+            None,
+            Some(&latest_refresh_cookie),
+            None,
+        )
+        .await;
+        assert_eq!(rotate_response.status_code(), StatusCode::OK);
+        latest_refresh_cookie = format!(
+            "refresh_token={}",
+            rotate_response.cookie("refresh_token").value()
+        );
+    }
+
+    let replay_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&stolen_refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(replay_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let refresh_after_reuse = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&latest_refresh_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(
+        refresh_after_reuse.status_code(),
+        StatusCode::UNAUTHORIZED,
+        "重放两代之前的 refresh token 后，最新一代 refresh token 也应失效"
+    );
+
+    let revoked_reason = sqlx::query_scalar!(
+        "SELECT revoked_reason FROM auth_sessions WHERE user_id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询会话撤销原因失败");
+    assert_eq!(revoked_reason.as_deref(), Some("refresh_reuse_detected"));
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn expired_login_throttle_rows_should_be_deleted(pool: sqlx::PgPool) {
+    sqlx::query!(
+        r#"
+INSERT INTO auth_login_throttles (scope, key, failure_count, first_failure_at, last_failure_at, blocked_until)
+VALUES
+    ('identifier', 'stale', 3, NOW() - INTERVAL '2 hours', NOW() - INTERVAL '2 hours', NULL),
+    ('ip', '198.51.100.1', 60, NOW() - INTERVAL '2 hours', NOW() - INTERVAL '2 hours', NOW() + INTERVAL '1 hour'),
+    ('identifier', 'recent', 1, NOW(), NOW(), NULL)
+        "#
+    )
+    .execute(&pool)
+    .await
+    .expect("写入测试登录失败计数失败");
+
+    let cfg = crate::config::runtime::LoginThrottleConfig {
+        failure_window_secs: 900,
+        backoff_after_failures: 3,
+        backoff_base_secs: 1,
+        backoff_max_secs: 300,
+        lockout_threshold: 10,
+        lockout_secs: 900,
+        ip_failure_threshold: 50,
+        trusted_proxies: Vec::new(),
+    };
+    let deleted = crate::services::login_throttle::delete_expired(&pool, &cfg)
+        .await
+        .expect("清理登录失败计数失败");
+    assert_eq!(deleted, 1);
+
+    let remaining = sqlx::query_scalar!("SELECT key FROM auth_login_throttles ORDER BY key")
+        .fetch_all(&pool)
+        .await
+        .expect("查询登录失败计数失败");
+    assert_eq!(
+        remaining,
+        vec!["198.51.100.1", "recent"],
+        "退避期内与窗口内的计数保留"
+    );
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn repeated_login_failures_should_lock_account_until_admin_unlocks(pool: sqlx::PgPool) {
+    sqlx::query!(
+        r#"
+INSERT INTO system_config (key, value, description)
+VALUES
+    ('security.login_backoff_after_failures', '100'::jsonb, 'e2e'),
+    ('security.login_lockout_threshold', '3'::jsonb, 'e2e')
+ON CONFLICT (key) DO UPDATE
+SET value = EXCLUDED.value
+        "#
+    )
+    .execute(&pool)
+    .await
+    .expect("写入登录限流测试配置失败");
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("lockout_user_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "LockoutPassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+
+    for _ in 0..3 {
+        let response = request_json(
+            &server,
+            Method::POST,
+            "/api/v1/sessions",
+            None,
+            None,
+            Some(serde_json::json!({
+                "identifier": username,
+                "password": "wrong-password",
+            })),
+        )
+        .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+    }
+
+    let locked_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions",
+        None,
+        None,
+        Some(serde_json::json!({
+            "identifier": email,
+            "password": password,
+        })),
+    )
+    .await;
+    assert_eq!(locked_response.status_code(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(locked_response
+        .maybe_header(header::RETRY_AFTER)
+        .is_some_and(|v| v.to_str().unwrap_or("0").parse::<u64>().unwrap_or(0) > 0));
+    assert_eq!(
+        locked_response
+            .json::<Value>()
+            .get("code")
+            .and_then(Value::as_u64),
+        Some(1004)
+    );
+
+    let locked_events = sqlx::query_scalar!(
+        r#"
+SELECT COUNT(*) AS "count!"
+FROM security_events
+WHERE user_id = $1
+  AND event_type = 'account_locked'
+        "#,
+        user_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询安全事件失败");
+    assert_eq!(locked_events, 1);
+
+    let unlock_response = request_json(
+        &server,
+        Method::POST,
+        &format!("/api/v1/users/{user_id}/unlock"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(unlock_response.status_code(), StatusCode::NO_CONTENT);
+
+    login_and_get_tokens(&server, &username, password).await;
+
+    cleanup_test_users(&pool, &[user_id, admin_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn admin_impersonation_should_be_short_lived_limited_and_audited(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, admin_cookie) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("impersonated_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "ImpersonatedPassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+    let (user_token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    // 普通用户没有 users:impersonate，也不能借此接管管理员。
+    let denied = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/impersonation",
+        Some(&user_token),
+        None,
+        Some(serde_json::json!({ "user_id": admin_id, "reason": "test" })),
+    )
+    .await;
+    assert_eq!(denied.status_code(), StatusCode::FORBIDDEN);
+
+    let start_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/impersonation",
+        Some(&admin_token),
+        None,
+        Some(serde_json::json!({ "user_id": user_id, "reason": "复现工单 #42" })),
+    )
+    .await;
+    assert_eq!(start_response.status_code(), StatusCode::OK);
+    assert!(
+        start_response.maybe_cookie("refresh_token").is_none(),
+        "模拟登录不应覆盖操作者的 refresh cookie"
+    );
+    let started = start_response.json::<Value>();
+    assert_eq!(started.get("expires_in").and_then(Value::as_u64), Some(900));
+    let impersonation_token = started
+        .get("token")
+        .and_then(Value::as_str)
+        .expect("应返回 access token")
+        .to_string();
+
+    let claims = decode::<crate::api::auth::Claims>(
+        &impersonation_token,
+        &DecodingKey::from_secret(E2E_JWT_SECRET.as_bytes()),
+        &Validation::default(),
+    )
+    .expect("解码 access token 失败")
+    .claims;
+    assert_eq!(claims.sub, user_id.to_string());
+    let act = claims.act.expect("模拟登录 token 应携带 act");
+    assert_eq!(act.sub, admin_id.to_string());
+    assert_eq!(act.username.as_deref(), Some("admin"));
+    assert!(claims.exp - claims.iat <= 900);
+
+    let me_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&impersonation_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(me_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        me_response.json::<Value>().get("id"),
+        Some(&serde_json::json!(user_id))
+    );
+
+    for (method, uri) in [
+        (Method::GET, "/api/v1/sessions"),
+        (Method::DELETE, "/api/v1/sessions/others"),
+        (Method::GET, "/api/v1/security/tokens"),
+        (Method::POST, "/api/v1/sessions/impersonation"),
+        (Method::DELETE, "/api/v1/users/me"),
+        (Method::GET, "/api/v1/users/me/export"),
+    ] {
+        let response = request_json(
+            &server,
+            method,
+            uri,
+            Some(&impersonation_token),
+            None,
+            Some(serde_json::json!({ "user_id": admin_id, "reason": "nested" })),
+        )
+        .await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "模拟登录会话不应访问 {uri}"
+        );
+    }
+
+    let sessions = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/sessions",
+        Some(&user_token),
+        None,
+        None,
+    )
+    .await
+    .json::<Value>();
+    assert!(
+        sessions
+            .as_array()
+            .expect("会话列表应为数组")
+            .iter()
+            .any(|s| s.get("impersonator_id") == Some(&serde_json::json!(admin_id))),
+        "被模拟用户应能在会话列表中看到模拟登录会话"
+    );
+
+    let stop_response = request_json(
+        &server,
+        Method::DELETE,
+        "/api/v1/sessions/current",
+        Some(&impersonation_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(stop_response.status_code(), StatusCode::NO_CONTENT);
+    assert!(
+        stop_response.maybe_cookie("refresh_token").is_none(),
+        "结束模拟登录不应清除操作者的 refresh cookie"
+    );
+
+    let after_stop = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&impersonation_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(after_stop.status_code(), StatusCode::UNAUTHORIZED);
+
+    let admin_refresh = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions/refresh",
+        None,
+        Some(&admin_cookie),
+        None,
+    )
+    .await;
+    assert_eq!(admin_refresh.status_code(), StatusCode::OK);
+
+    let events = sqlx::query!(
+        r#"
+SELECT event_type, details
+FROM security_events
+WHERE user_id = $1
+  AND event_type IN ('impersonation_started', 'impersonation_ended')
+ORDER BY created_at
+        "#,
+        admin_id,
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("查询模拟登录安全事件失败");
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_type, "impersonation_started");
+    assert_eq!(events[0].details["reason"], "复现工单 #42");
+    assert_eq!(events[1].event_type, "impersonation_ended");
+    assert_eq!(
+        events[1].details["target_user_id"],
+        serde_json::json!(user_id)
+    );
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn impersonation_should_be_audited_as_ended_when_revoked_or_expired(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("impersonated_{}", Uuid::new_v4().simple());
+    let email = format!("{username}@example.invalid");
+    let password = "ImpersonatedPassword#A123";
+    let user_id = create_or_update_user_with_password(&pool, &username, &email, password).await;
+    let (user_token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let start = |reason: &'static str| {
+        let server = &server;
+        let admin_token = &admin_token;
+        async move {
+            let response = request_json(
+                server,
+                Method::POST,
+                "/api/v1/sessions/impersonation",
+                Some(admin_token),
+                None,
+                Some(serde_json::json!({ "user_id": user_id, "reason": reason })),
+            )
+            .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+        }
+    };
+
+    // 被模拟用户在自己的会话列表中撤销模拟登录会话。
+    start("revoked").await;
+    let revoked_session_id = sqlx::query_scalar!(
+        "SELECT id FROM auth_sessions WHERE user_id = $1 AND impersonator_id IS NOT NULL",
+        user_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询模拟登录会话失败");
+    let revoke_response = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/sessions/{revoked_session_id}"),
+        Some(&user_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoke_response.status_code(), StatusCode::NO_CONTENT);
+
+    // 到期未结束的模拟登录会话由后台任务补记结束事件。
+    start("expired").await;
+    sqlx::query!(
+        r#"
+UPDATE auth_sessions
+SET expires_at = NOW() - INTERVAL '1 second'
+WHERE user_id = $1
+  AND impersonator_id IS NOT NULL
+  AND revoked_at IS NULL
+        "#,
+        user_id,
+    )
+    .execute(&pool)
+    .await
+    .expect("更新模拟登录会话过期时间失败");
+    let ended = crate::services::impersonation::end_expired(&pool)
+        .await
+        .expect("结束到期模拟登录会话失败");
+    assert_eq!(ended, 1);
+    assert_eq!(
+        crate::services::impersonation::end_expired(&pool)
+            .await
+            .expect("结束到期模拟登录会话失败"),
+        0,
+        "已结束的模拟登录会话不应重复记录"
+    );
+
+    let events = sqlx::query!(
+        r#"
+SELECT event_type, session_id, details
+FROM security_events
+WHERE user_id = $1
+  AND event_type IN ('impersonation_started', 'impersonation_ended')
+ORDER BY created_at
+        "#,
+        admin_id,
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("查询模拟登录安全事件失败");
+    let kinds: Vec<_> = events
+        .iter()
+        .map(|e| (e.event_type.as_str(), e.details["reason"].as_str()))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("impersonation_started", Some("revoked")),
+            ("impersonation_ended", Some("manual_revoke")),
+            ("impersonation_started", Some("expired")),
+            ("impersonation_ended", Some("impersonation_expired")),
+        ]
+    );
+    assert!(events[0].details["expires_at"].is_string());
+    assert_eq!(events[1].session_id, Some(revoked_session_id));
+    assert_eq!(
+        events[1].details["target_user_id"],
+        serde_json::json!(user_id)
+    );
+    assert!(events[3].details["expires_at"].is_string());
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
@@ -61,6 +61,28 @@ async fn get_settings_should_require_auth_and_return_runtime_config(pool: sqlx::
             .is_some(),
         "settings.integrations.example_api_key_is_set 应返回布尔值"
     );
+    assert!(
+        body.get("security").is_none(),
+        "不具备 settings:write 时不返回安全配置"
+    );
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+    let admin_body = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await
+    .json::<Value>();
+    assert!(
+        admin_body["security"]["access_token_ttl_secs"].is_u64(),
+        "具备 settings:write 时返回安全配置"
+    );
 
     cleanup_test_users(&pool, &[user_id]).await;
 }
@@ -95,3 +117,177 @@ async fn non_admin_should_be_forbidden_to_patch_settings(pool: sqlx::PgPool) {
 
     cleanup_test_users(&pool, &[user_id]).await;
 }
+
+#[sqlx::test(migrations = "./migrations")]
+async fn admin_should_configure_token_lifetimes_and_refresh_cookie(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, password).await;
+    let (token, _) = login_and_get_tokens(&server, "admin", password).await;
+
+    for body in [
+        serde_json::json!({ "security": { "login_lockout_secs": u64::MAX } }),
+        serde_json::json!({ "security": { "login_backoff_max_secs": 2_592_001 } }),
+    ] {
+        let response = request_json(
+            &server,
+            Method::PATCH,
+            "/api/v1/settings",
+            Some(&token),
+            None,
+            Some(body.clone()),
+        )
+        .await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::BAD_REQUEST,
+            "{body} 超出登录防爆破时长上限"
+        );
+    }
+
+    let invalid_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        Some(&token),
+        None,
+        Some(serde_json::json!({
+            "security": { "refresh_cookie_same_site": "None" }
+        })),
+    )
+    .await;
+    assert_eq!(invalid_response.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        invalid_response
+            .json::<Value>()
+            .get("code")
+            .and_then(Value::as_u64),
+        Some(1000)
+    );
+
+    let uncovered_path_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        Some(&token),
+        None,
+        Some(serde_json::json!({
+            "security": { "refresh_cookie_path": "/api/v1/users" }
+        })),
+    )
+    .await;
+    assert_eq!(
+        uncovered_path_response.status_code(),
+        StatusCode::BAD_REQUEST
+    );
+
+    let patch_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/settings",
+        Some(&token),
+        None,
+        Some(serde_json::json!({
+            "security": {
+                "access_token_ttl_secs": 300,
+                "refresh_token_ttl_secs": 86400,
+                "refresh_cookie_path": "/",
+                "refresh_cookie_domain": "Example.com",
+                "refresh_cookie_same_site": "None",
+                "refresh_cookie_secure": "always"
+            }
+        })),
+    )
+    .await;
+    assert_eq!(patch_response.status_code(), StatusCode::OK);
+    let security = patch_response.json::<Value>()["security"].clone();
+    assert_eq!(security["access_token_ttl_secs"], 300);
+    assert_eq!(security["refresh_token_ttl_secs"], 86400);
+    assert_eq!(security["refresh_cookie_domain"], "example.com");
+    assert_eq!(security["refresh_cookie_same_site"], "None");
+    assert_eq!(security["refresh_cookie_secure"], "always");
+
+    let login_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/sessions",
+        None,
+        None,
+        Some(serde_json::json!({ "identifier": "admin", "password": password })),
+    )
+    .await;
+    assert_eq!(login_response.status_code(), StatusCode::OK);
+    assert_eq!(login_response.json::<Value>()["expires_in"], 300);
+    let set_cookie = login_response.header(header::SET_COOKIE);
+    let set_cookie = set_cookie.to_str().expect("Set-Cookie 应为 ASCII");
+    for attribute in [
+        "Path=/;",
+        "SameSite=None",
+        "Max-Age=86400",
+        "Domain=example.com",
+        "Secure",
+    ] {
+        assert!(
+            set_cookie.contains(attribute),
+            "Set-Cookie 缺少 {attribute}: {set_cookie}"
+        );
+    }
+
+    cleanup_test_users(&pool, &[admin_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn patch_settings_should_honour_if_match(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let settings = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    let etag = settings.header(header::ETAG).to_str().unwrap().to_string();
+
+    let patch_with = |if_match: String, welcome_message: &str| {
+        server
+            .method(Method::PATCH, "/api/v1/settings")
+            .add_header(header::ACCEPT, "application/json")
+            .add_header(header::IF_MATCH, if_match)
+            .authorization_bearer(&admin_token)
+            .json(&serde_json::json!({ "app": { "welcome_message": welcome_message } }))
+    };
+
+    let first = patch_with(etag.clone(), "第一次修改").await;
+    assert_eq!(first.status_code(), StatusCode::OK);
+    assert_ne!(first.header(header::ETAG).to_str().unwrap(), etag);
+
+    let stale = patch_with(etag, "覆盖他人修改").await;
+    assert_eq!(stale.status_code(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(
+        stale.json::<Value>().get("code").and_then(Value::as_u64),
+        Some(2003)
+    );
+
+    let current = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(
+        current.json::<Value>()["app"]["welcome_message"],
+        Value::String("第一次修改".to_string()),
+        "412 时不写入任何修改"
+    );
+}
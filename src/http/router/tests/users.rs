@@ -565,3 +565,1171 @@ async fn user_can_patch_me_but_cannot_patch_others(pool: sqlx::PgPool) {
 
     cleanup_test_users(&pool, &[user_a_id, user_b_id]).await;
 }
+
+#[sqlx::test(migrations = "./migrations")]
+async fn admin_password_reset_should_revoke_sessions_and_force_change_at_login(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("admin_reset_{}", Uuid::new_v4().simple());
+    let create_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/users",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "username": username,
+            "display_name": "Admin Reset",
+            "email": format!("{username}@example.invalid"),
+        })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let user_id = create_response.json::<Value>()["id"]
+        .as_str()
+        .expect("创建用户响应缺少 id")
+        .to_string();
+
+    let initial_password = "InitialPassword#A123";
+    let set_response = request_json(
+        &server,
+        Method::PUT,
+        &format!("/api/v1/users/{user_id}/password"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "password": initial_password })),
+    )
+    .await;
+    assert_eq!(set_response.status_code(), StatusCode::NO_CONTENT);
+    let (user_token, _) = login_and_get_tokens(&server, &username, initial_password).await;
+
+    let forbidden_response = request_json(
+        &server,
+        Method::PUT,
+        &format!("/api/v1/users/{admin_id}/password"),
+        Some(&user_token),
+        None,
+        Some(json!({ "password": "Hijacked#A123" })),
+    )
+    .await;
+    assert_eq!(forbidden_response.status_code(), StatusCode::FORBIDDEN);
+
+    let temporary_response = request_json(
+        &server,
+        Method::POST,
+        &format!("/api/v1/users/{user_id}/temporary-password"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(temporary_response.status_code(), StatusCode::OK);
+    let temporary_password = temporary_response.json::<Value>()["temporary_password"]
+        .as_str()
+        .expect("响应缺少临时密码")
+        .to_string();
+
+    let revoked_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&user_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(revoked_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let (restricted_token, _) = login_and_get_tokens(&server, &username, &temporary_password).await;
+    let restricted_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/sessions",
+        Some(&restricted_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(restricted_response.status_code(), StatusCode::FORBIDDEN);
+    assert_eq!(
+        restricted_response.json::<Value>()["code"].as_u64(),
+        Some(1005)
+    );
+
+    let new_password = "ChangedPassword#A123";
+    let change_response = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/security/password",
+        Some(&restricted_token),
+        None,
+        Some(json!({
+            "current_password": temporary_password,
+            "new_password": new_password,
+        })),
+    )
+    .await;
+    assert_eq!(change_response.status_code(), StatusCode::NO_CONTENT);
+
+    let (token, _) = login_and_get_tokens(&server, &username, new_password).await;
+    let sessions_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/sessions",
+        Some(&token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(sessions_response.status_code(), StatusCode::OK);
+
+    let user_id = Uuid::parse_str(&user_id).expect("用户 id 应为 UUID");
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn setting_password_without_username_should_be_rejected(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let create_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/users",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "display_name": "No Login",
+            "email": format!("no_login_{}@example.invalid", Uuid::new_v4().simple()),
+        })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let user_id = create_response.json::<Value>()["id"]
+        .as_str()
+        .expect("创建用户响应缺少 id")
+        .to_string();
+
+    for (method, uri, body) in [
+        (
+            Method::PUT,
+            format!("/api/v1/users/{user_id}/password"),
+            Some(json!({ "password": "InitialPassword#A123" })),
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/users/{user_id}/temporary-password"),
+            None,
+        ),
+    ] {
+        let response = request_json(&server, method, &uri, Some(&admin_token), None, body).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::BAD_REQUEST,
+            "{uri} 应拒绝为无用户名的用户设置密码"
+        );
+    }
+
+    let user_id = Uuid::parse_str(&user_id).expect("用户 id 应为 UUID");
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn role_changes_should_bump_auth_version_and_keep_last_admin(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    let admin_id = ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let demote_self_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{admin_id}"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "role": "user" })),
+    )
+    .await;
+    assert_eq!(demote_self_response.status_code(), StatusCode::BAD_REQUEST);
+
+    let invalid_role_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/users",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "display_name": "Invalid Role",
+            "email": format!("invalid_role_{}@example.invalid", Uuid::new_v4().simple()),
+            "role": "root",
+        })),
+    )
+    .await;
+    assert_eq!(invalid_role_response.status_code(), StatusCode::BAD_REQUEST);
+
+    let second_admin_name = format!("second_admin_{}", Uuid::new_v4().simple());
+    let create_response = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/users",
+        Some(&admin_token),
+        None,
+        Some(json!({
+            "username": second_admin_name,
+            "display_name": "Second Admin",
+            "email": format!("{second_admin_name}@example.invalid"),
+            "role": "admin",
+        })),
+    )
+    .await;
+    assert_eq!(create_response.status_code(), StatusCode::CREATED);
+    let created = create_response.json::<Value>();
+    assert_eq!(created["role"].as_str(), Some("admin"));
+    let second_admin_id = created["id"]
+        .as_str()
+        .expect("创建用户响应缺少 id")
+        .to_string();
+
+    let second_admin_password = "SecondAdmin#A123";
+    let set_password_response = request_json(
+        &server,
+        Method::PUT,
+        &format!("/api/v1/users/{second_admin_id}/password"),
+        Some(&admin_token),
+        None,
+        Some(json!({ "password": second_admin_password })),
+    )
+    .await;
+    assert_eq!(set_password_response.status_code(), StatusCode::NO_CONTENT);
+    let (second_admin_token, _) =
+        login_and_get_tokens(&server, &second_admin_name, second_admin_password).await;
+
+    let demote_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{admin_id}"),
+        Some(&second_admin_token),
+        None,
+        Some(json!({ "role": "user" })),
+    )
+    .await;
+    assert_eq!(demote_response.status_code(), StatusCode::OK);
+    assert_eq!(
+        demote_response.json::<Value>()["role"].as_str(),
+        Some("user")
+    );
+
+    let stale_token_response = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(stale_token_response.status_code(), StatusCode::UNAUTHORIZED);
+
+    let demote_last_response = request_json(
+        &server,
+        Method::PATCH,
+        &format!("/api/v1/users/{second_admin_id}"),
+        Some(&second_admin_token),
+        None,
+        Some(json!({ "role": "user" })),
+    )
+    .await;
+    assert_eq!(demote_last_response.status_code(), StatusCode::BAD_REQUEST);
+
+    let second_admin_id = Uuid::parse_str(&second_admin_id).expect("用户 id 应为 UUID");
+    cleanup_test_users(&pool, &[admin_id, second_admin_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn list_users_should_paginate_filter_and_search(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let marker = Uuid::new_v4().simple().to_string()[..10].to_string();
+    let mut user_ids = Vec::new();
+    for suffix in ["c", "a", "b"] {
+        let username = format!("page_{marker}_{suffix}");
+        let email = format!("{username}@example.invalid");
+        user_ids.push(
+            create_or_update_user_with_password(&pool, &username, &email, "PagePassword#A123")
+                .await,
+        );
+    }
+    sqlx::query!(
+        "UPDATE users SET is_active = FALSE WHERE id = $1",
+        user_ids[0]
+    )
+    .execute(&pool)
+    .await
+    .expect("停用测试用户失败");
+
+    let list = |query: String| {
+        let server = &server;
+        let token = admin_token.clone();
+        async move {
+            request_json(
+                server,
+                Method::GET,
+                &format!("/api/v1/users?{query}"),
+                Some(&token),
+                None,
+                None,
+            )
+            .await
+        }
+    };
+    let usernames = |response: &TestResponse| -> Vec<String> {
+        response
+            .json::<Value>()
+            .as_array()
+            .expect("用户列表响应体应为 JSON 数组")
+            .iter()
+            .filter_map(|user| user["username"].as_str().map(str::to_string))
+            .collect()
+    };
+
+    let first_page = list(format!("q={marker}&sort=username&per_page=2")).await;
+    assert_eq!(first_page.status_code(), StatusCode::OK);
+    assert_eq!(first_page.header("x-total-count"), "3");
+    assert_eq!(
+        usernames(&first_page),
+        vec![format!("page_{marker}_a"), format!("page_{marker}_b")]
+    );
+    let link = first_page.header("link");
+    let link = link.to_str().expect("Link 头应为 ASCII");
+    assert!(link.contains(&format!(
+        "</api/v1/users?q={marker}&sort=username&per_page=2&page=2>; rel=\"next\""
+    )));
+    assert!(link.contains("page=2>; rel=\"last\""));
+    assert!(!link.contains("rel=\"prev\""));
+
+    let second_page = list(format!("q={marker}&sort=username&per_page=2&page=2")).await;
+    assert_eq!(usernames(&second_page), vec![format!("page_{marker}_c")]);
+    assert!(!second_page
+        .header("link")
+        .to_str()
+        .unwrap()
+        .contains("rel=\"next\""));
+
+    let inactive = list(format!("q={}&is_active=false", marker.to_uppercase())).await;
+    assert_eq!(inactive.header("x-total-count"), "1", "搜索不区分大小写");
+    assert_eq!(usernames(&inactive), vec![format!("page_{marker}_c")]);
+
+    let wildcard = list(format!("q={marker}%25")).await;
+    assert_eq!(
+        wildcard.header("x-total-count"),
+        "0",
+        "搜索词中的通配符按字面匹配"
+    );
+
+    for query in [
+        "per_page=500",
+        "page=0",
+        "sort=password",
+        "created_from=yesterday",
+    ] {
+        let response = list(query.to_string()).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::BAD_REQUEST,
+            "{query} 应被拒绝"
+        );
+    }
+
+    cleanup_test_users(&pool, &user_ids).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn get_user_should_return_detail_including_soft_deleted(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("detail_{}", Uuid::new_v4().simple());
+    let password = "DetailPassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+
+    let detail_uri = format!("/api/v1/users/{user_id}");
+    let get_detail = || {
+        request_json(
+            &server,
+            Method::GET,
+            &detail_uri,
+            Some(&admin_token),
+            None,
+            None,
+        )
+    };
+
+    let before_login = get_detail().await;
+    assert_eq!(before_login.status_code(), StatusCode::OK);
+    let before_login = before_login.json::<Value>();
+    assert_eq!(before_login["username"], json!(username));
+    assert_eq!(before_login["role"], json!("user"));
+    assert_eq!(before_login["last_login_at"], Value::Null);
+    assert_eq!(before_login["active_session_count"], json!(0));
+
+    login_and_get_tokens(&server, &username, password).await;
+    let after_login = get_detail().await.json::<Value>();
+    assert!(
+        after_login["last_login_at"].is_string(),
+        "登录后记录最近登录时间"
+    );
+    assert_eq!(after_login["active_session_count"], json!(1));
+    assert_eq!(after_login["deleted_at"], Value::Null);
+
+    let delete_response = request_json(
+        &server,
+        Method::DELETE,
+        &format!("/api/v1/users/{user_id}"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(delete_response.status_code(), StatusCode::NO_CONTENT);
+
+    let deleted = get_detail().await;
+    assert_eq!(deleted.status_code(), StatusCode::OK);
+    let deleted = deleted.json::<Value>();
+    assert!(deleted["deleted_at"].is_string(), "已逻辑删除用户仍可查看");
+    assert_eq!(deleted["is_active"], json!(false));
+    assert_eq!(deleted["active_session_count"], json!(0));
+
+    let missing = request_json(
+        &server,
+        Method::GET,
+        &format!("/api/v1/users/{}", Uuid::new_v4()),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(missing.status_code(), StatusCode::NOT_FOUND);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn patch_user_should_follow_json_merge_patch(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("merge_patch_{}", Uuid::new_v4().simple());
+    let password = "MergePatchPassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    let uri = format!("/api/v1/users/{user_id}");
+
+    let merge_patch = |body: Value| {
+        server
+            .method(Method::PATCH, &uri)
+            .add_header(header::ACCEPT, "application/json")
+            .add_header(
+                header::CONTENT_TYPE,
+                crate::api::merge_patch::MERGE_PATCH_CONTENT_TYPE,
+            )
+            .authorization_bearer(&admin_token)
+            .bytes(body.to_string().into())
+    };
+
+    let set = merge_patch(json!({
+        "phone": "13800000000",
+        "avatar_url": "https://example.invalid/a.png",
+        "metadata": { "profile": { "team": "core", "level": 3 }, "tags": ["a"] },
+    }))
+    .await;
+    assert_eq!(set.status_code(), StatusCode::OK);
+
+    let cleared = merge_patch(json!({
+        "phone": null,
+        "avatar_url": "",
+        "metadata": { "profile": { "level": null, "city": "上海" }, "tags": null },
+    }))
+    .await;
+    assert_eq!(cleared.status_code(), StatusCode::OK);
+    let cleared = cleared.json::<Value>();
+    assert_eq!(cleared["phone"], Value::Null, "null 清空可置空字段");
+    assert_eq!(cleared["avatar_url"], Value::Null);
+    assert_eq!(
+        cleared["display_name"],
+        json!(format!("{username}-display")),
+        "缺省字段保持不变"
+    );
+    assert_eq!(
+        cleared["metadata"],
+        json!({ "profile": { "team": "core", "city": "上海" } }),
+        "metadata 按 RFC 7396 深度合并"
+    );
+
+    let reset_metadata = merge_patch(json!({ "metadata": null })).await;
+    assert_eq!(reset_metadata.json::<Value>()["metadata"], json!({}));
+
+    for body in [
+        json!({ "display_name": null }),
+        json!({ "is_active": null }),
+    ] {
+        let response = merge_patch(body.clone()).await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::BAD_REQUEST,
+            "{body} 不可置空"
+        );
+    }
+
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+    let set_me = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/users/me",
+        Some(&token),
+        None,
+        Some(json!({ "phone": "13900000000" })),
+    )
+    .await;
+    assert_eq!(set_me.json::<Value>()["phone"], json!("13900000000"));
+    let clear_me = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/users/me",
+        Some(&token),
+        None,
+        Some(json!({ "phone": null })),
+    )
+    .await;
+    assert_eq!(clear_me.status_code(), StatusCode::OK);
+    assert_eq!(clear_me.json::<Value>()["phone"], Value::Null);
+    let injected_email = request_json(
+        &server,
+        Method::PATCH,
+        "/api/v1/users/me",
+        Some(&token),
+        None,
+        Some(json!({ "email": "me@example.com>\r\nRCPT TO:<victim@example.com" })),
+    )
+    .await;
+    assert_eq!(
+        injected_email.status_code(),
+        StatusCode::BAD_REQUEST,
+        "邮箱不能包含换行等控制字符"
+    );
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn patch_user_should_honour_if_match(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("etag_{}", Uuid::new_v4().simple());
+    let password = "EtagPassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    let uri = format!("/api/v1/users/{user_id}");
+
+    let patch_with = |uri: String, token: String, if_match: String, display_name: &str| {
+        server
+            .method(Method::PATCH, &uri)
+            .add_header(header::ACCEPT, "application/json")
+            .add_header(header::IF_MATCH, if_match)
+            .authorization_bearer(&token)
+            .json(&json!({ "display_name": display_name }))
+    };
+
+    let detail = request_json(&server, Method::GET, &uri, Some(&admin_token), None, None).await;
+    let etag = detail.header(header::ETAG).to_str().unwrap().to_string();
+
+    let first = patch_with(uri.clone(), admin_token.clone(), etag.clone(), "first").await;
+    assert_eq!(first.status_code(), StatusCode::OK);
+    let new_etag = first.header(header::ETAG).to_str().unwrap().to_string();
+    assert_ne!(new_etag, etag, "更新后 ETag 变化");
+
+    let stale = patch_with(uri.clone(), admin_token.clone(), etag, "second").await;
+    assert_eq!(stale.status_code(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(stale.json::<Value>()["code"], json!(2003));
+
+    let any = patch_with(uri.clone(), admin_token.clone(), "*".to_string(), "third").await;
+    assert_eq!(any.status_code(), StatusCode::OK);
+
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+    let me = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&token),
+        None,
+        None,
+    )
+    .await;
+    let me_etag = me.header(header::ETAG).to_str().unwrap().to_string();
+    assert_eq!(
+        me_etag,
+        any.header(header::ETAG).to_str().unwrap(),
+        "同一用户的 ETag 在各接口一致"
+    );
+    let stale_me = patch_with(
+        "/api/v1/users/me".to_string(),
+        token.clone(),
+        new_etag,
+        "mine",
+    )
+    .await;
+    assert_eq!(stale_me.status_code(), StatusCode::PRECONDITION_FAILED);
+    let fresh_me = patch_with("/api/v1/users/me".to_string(), token, me_etag, "mine").await;
+    assert_eq!(fresh_me.status_code(), StatusCode::OK);
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn import_users_should_report_per_row_and_support_dry_run(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let marker = Uuid::new_v4().simple().to_string()[..10].to_string();
+    let existing_email = format!("existing_{marker}@example.invalid");
+    let existing_id = create_or_update_user_with_password(
+        &pool,
+        &format!("existing_{marker}"),
+        &existing_email,
+        "ExistingPassword#A123",
+    )
+    .await;
+
+    let csv = format!(
+        "username,display_name,email,role,metadata\r\n\
+         imp_a_{marker},\"Doe, Jane\",imp_a_{marker}@example.invalid,auditor,\"{{\"\"team\"\":\"\"ops\"\"}}\"\r\n\
+         imp_b_{marker},Bad Email,not-an-email,,\r\n\
+         imp_c_{marker},Duplicate,imp_a_{marker}@example.invalid,,\r\n\
+         imp_d_{marker},Existing,{existing_email},,\r\n\
+         imp_e_{marker},Unknown Role,imp_e_{marker}@example.invalid,no_such_role,\r\n\
+         imp_f_{marker},Short Row\r\n"
+    );
+    let import = |dry_run: bool, content_type: &'static str, body: String| {
+        server
+            .method(
+                Method::POST,
+                &format!("/api/v1/users/import?dry_run={dry_run}"),
+            )
+            .add_header(header::ACCEPT, "application/json")
+            .add_header(header::CONTENT_TYPE, content_type)
+            .authorization_bearer(&admin_token)
+            .bytes(body.into())
+    };
+    let imported_count = || async {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM users WHERE username LIKE $1"#,
+            format!("imp_%_{marker}"),
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("统计导入用户失败")
+    };
+
+    let dry_run = import(true, "text/csv; charset=utf-8", csv.clone()).await;
+    assert_eq!(dry_run.status_code(), StatusCode::OK);
+    let report = dry_run.json::<Value>();
+    assert_eq!(report["dry_run"], json!(true));
+    assert_eq!(report["total"], json!(6));
+    assert_eq!(report["succeeded"], json!(1));
+    assert_eq!(report["failed"], json!(5));
+    let lines: Vec<_> = report["rows"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| (row["line"].as_u64().unwrap(), row["error"].is_null()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (2, true),
+            (3, false),
+            (4, false),
+            (5, false),
+            (6, false),
+            (7, false)
+        ]
+    );
+    assert!(report["rows"][1]["details"]["email"].is_array());
+    assert_eq!(report["rows"][0]["user_id"], Value::Null);
+    assert_eq!(imported_count().await, 0, "dry-run 不写入");
+
+    let applied = import(false, "text/csv", csv).await.json::<Value>();
+    assert_eq!(applied["succeeded"], json!(1));
+    let user_id = Uuid::parse_str(applied["rows"][0]["user_id"].as_str().unwrap()).unwrap();
+    let created = sqlx::query!(
+        "SELECT display_name, role, metadata FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询导入用户失败");
+    assert_eq!(created.display_name, "Doe, Jane");
+    assert_eq!(created.role, "auditor");
+    assert_eq!(created.metadata, json!({ "team": "ops" }));
+    assert_eq!(imported_count().await, 1, "失败行不影响有效行写入");
+
+    let ndjson = format!(
+        "{{\"username\":\"imp_g_{marker}\",\"display_name\":\"G\",\"email\":\"imp_g_{marker}@example.invalid\"}}\n\n{{\"display_name\":\"missing email\"}}\n"
+    );
+    let ndjson_report = import(false, "application/x-ndjson", ndjson)
+        .await
+        .json::<Value>();
+    assert_eq!(ndjson_report["succeeded"], json!(1));
+    assert_eq!(ndjson_report["rows"][1]["line"], json!(3));
+    assert_eq!(imported_count().await, 2);
+
+    let unsupported = import(false, "application/json", "[]".to_string()).await;
+    assert_eq!(unsupported.status_code(), StatusCode::BAD_REQUEST);
+    let unknown_column = import(false, "text/csv", "email,password\r\n".to_string()).await;
+    assert_eq!(unknown_column.status_code(), StatusCode::BAD_REQUEST);
+
+    let imported_ids = sqlx::query_scalar!(
+        "SELECT id FROM users WHERE username LIKE $1",
+        format!("imp_%_{marker}"),
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("查询导入用户失败");
+    cleanup_test_users(&pool, &[imported_ids, vec![existing_id]].concat()).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn export_users_should_stream_filtered_rows(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let marker = Uuid::new_v4().simple().to_string()[..10].to_string();
+    let mut user_ids = Vec::new();
+    for suffix in ["a", "b"] {
+        let username = format!("export_{marker}_{suffix}");
+        let email = format!("{username}@example.invalid");
+        user_ids.push(
+            create_or_update_user_with_password(&pool, &username, &email, "ExportPassword#A123")
+                .await,
+        );
+    }
+    sqlx::query!(
+        "UPDATE users SET display_name = 'Doe, \"B\"', deleted_at = NOW() WHERE id = $1",
+        user_ids[1]
+    )
+    .execute(&pool)
+    .await
+    .expect("逻辑删除测试用户失败");
+
+    let export = |query: String| {
+        let (server, token) = (&server, &admin_token);
+        let uri = format!("/api/v1/users/export?q={marker}&sort=username&{query}");
+        async move { request_json(server, Method::GET, &uri, Some(token), None, None).await }
+    };
+
+    let csv = export("columns=username,display_name,deleted_at".to_string()).await;
+    assert_eq!(csv.status_code(), StatusCode::OK);
+    assert_eq!(csv.header(header::CONTENT_TYPE), "text/csv; charset=utf-8");
+    let disposition = csv.header(header::CONTENT_DISPOSITION);
+    let disposition = disposition.to_str().unwrap();
+    assert!(disposition.starts_with("attachment; filename=\"users-"));
+    assert!(disposition.ends_with(".csv\""));
+    assert_eq!(
+        csv.text(),
+        format!(
+            "username,display_name,deleted_at\r\nexport_{marker}_a,export_{marker}_a-display,\r\n"
+        ),
+        "默认不包含已逻辑删除用户"
+    );
+
+    let with_deleted = export("columns=username,display_name&include_deleted=true".to_string())
+        .await
+        .text();
+    assert!(with_deleted.ends_with(&format!("export_{marker}_b,\"Doe, \"\"B\"\"\"\r\n")));
+
+    let ndjson = export("format=ndjson&include_deleted=true".to_string()).await;
+    assert_eq!(ndjson.header(header::CONTENT_TYPE), "application/x-ndjson");
+    let lines: Vec<Value> = ndjson
+        .text()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("每行应为 JSON 对象"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["id"], json!(user_ids[0]));
+    assert_eq!(lines[0]["metadata"], json!({}));
+    let deleted_at = lines[1]["deleted_at"].as_str().expect("应导出删除时间");
+    assert!(
+        chrono::DateTime::parse_from_rfc3339(deleted_at).is_ok(),
+        "时间字段为 ISO 8601"
+    );
+
+    let unknown_column = export("columns=password_hash".to_string()).await;
+    assert_eq!(unknown_column.status_code(), StatusCode::BAD_REQUEST);
+
+    let event_count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM security_events WHERE event_type = 'users_exported'"#
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询导出安全事件失败");
+    assert_eq!(event_count, 3);
+
+    cleanup_test_users(&pool, &user_ids).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn deleted_users_should_be_purged_after_retention(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let password = "PurgePassword#A123";
+    let mut user_ids = Vec::new();
+    for suffix in ["expired", "recent", "active"] {
+        let username = format!("purge_{suffix}_{}", Uuid::new_v4().simple());
+        user_ids.push(create_user_with_password(&pool, &username, password).await);
+        if suffix != "active" {
+            login_and_get_tokens(&server, &username, password).await;
+        }
+    }
+    let [expired_id, recent_id, active_id] = user_ids[..] else {
+        unreachable!()
+    };
+    for user_id in [expired_id, recent_id] {
+        let response = request_json(
+            &server,
+            Method::DELETE,
+            &format!("/api/v1/users/{user_id}"),
+            Some(&admin_token),
+            None,
+            None,
+        )
+        .await;
+        assert_eq!(response.status_code(), StatusCode::NO_CONTENT);
+    }
+    sqlx::query!(
+        "UPDATE users SET deleted_at = NOW() - INTERVAL '31 days' WHERE id = $1",
+        expired_id
+    )
+    .execute(&pool)
+    .await
+    .expect("回拨删除时间失败");
+    sqlx::query!(
+        r#"
+INSERT INTO security_events (event_type, user_id, client_ip, user_agent, details)
+VALUES ('account_unlocked', $1, '203.0.113.7', 'purge-test', '{"email": "expired@example.invalid"}')
+        "#,
+        expired_id,
+    )
+    .execute(&pool)
+    .await
+    .expect("写入测试安全事件失败");
+    sqlx::query!(
+        r#"
+INSERT INTO auth_login_throttles (scope, key, failure_count)
+SELECT 'identifier', lower(email), 1
+FROM users
+WHERE id = $1
+        "#,
+        expired_id,
+    )
+    .execute(&pool)
+    .await
+    .expect("写入测试登录失败计数失败");
+
+    let purged = crate::services::user_purge::purge_expired(&pool, 30)
+        .await
+        .expect("清除已删除用户失败");
+    assert_eq!(purged, 1, "只清除超过保留期的用户");
+    let remaining = sqlx::query_scalar!(
+        r#"
+SELECT
+    (SELECT COUNT(*) FROM users WHERE id = $1)
+    + (SELECT COUNT(*) FROM auth_sessions WHERE user_id = $1) AS "count!"
+        "#,
+        expired_id,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询清除结果失败");
+    assert_eq!(remaining, 0, "用户与会话一并物理删除");
+    let throttle_rows = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM auth_login_throttles WHERE scope = 'identifier'"#
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询登录失败计数失败");
+    assert_eq!(
+        throttle_rows, 0,
+        "以被清除用户邮箱为键的登录失败计数一并删除"
+    );
+    let scrubbed = sqlx::query!(
+        r#"
+SELECT user_id, client_ip, user_agent, details
+FROM security_events
+WHERE event_type = 'account_unlocked'
+        "#
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询清除后的安全事件失败");
+    assert_eq!(
+        (
+            scrubbed.user_id,
+            scrubbed.client_ip,
+            scrubbed.user_agent,
+            scrubbed.details
+        ),
+        (None, None, None, json!({})),
+        "安全事件中不再保留可识别信息"
+    );
+
+    let purge = |user_id: Uuid| {
+        let (server, token) = (&server, &admin_token);
+        let uri = format!("/api/v1/users/{user_id}/purge");
+        async move { request_json(server, Method::POST, &uri, Some(token), None, None).await }
+    };
+    assert_eq!(
+        purge(active_id).await.status_code(),
+        StatusCode::BAD_REQUEST,
+        "未删除的用户不能直接清除"
+    );
+    assert_eq!(purge(recent_id).await.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(purge(recent_id).await.status_code(), StatusCode::NOT_FOUND);
+
+    let purged_events = sqlx::query_scalar!(
+        r#"
+SELECT details->>'reason' AS "reason!"
+FROM security_events
+WHERE event_type = 'user_purged'
+ORDER BY created_at, id
+        "#
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("查询清除安全事件失败");
+    assert_eq!(purged_events, vec!["retention", "forced"]);
+
+    for (days, expected) in [
+        (json!(7), StatusCode::OK),
+        (json!(3651), StatusCode::BAD_REQUEST),
+    ] {
+        let response = request_json(
+            &server,
+            Method::PATCH,
+            "/api/v1/settings",
+            Some(&admin_token),
+            None,
+            Some(json!({ "users": { "purge_after_days": days } })),
+        )
+        .await;
+        assert_eq!(response.status_code(), expected);
+    }
+    let settings = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/settings",
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await
+    .json::<Value>();
+    assert_eq!(settings["users"]["purge_after_days"], json!(7));
+
+    cleanup_test_users(&pool, &[active_id]).await;
+}
+
+#[sqlx::test(migrations = "./migrations")]
+async fn users_should_export_personal_data_and_delete_own_account(pool: sqlx::PgPool) {
+    let server = setup_user_management_test_app(pool.clone()).await;
+
+    let admin_password = "AdminPassword#A123";
+    ensure_admin_user_with_password(&pool, admin_password).await;
+    let (admin_token, _) = login_and_get_tokens(&server, "admin", admin_password).await;
+
+    let username = format!("self_service_{}", Uuid::new_v4().simple());
+    let password = "SelfServicePassword#A123";
+    let user_id = create_user_with_password(&pool, &username, password).await;
+    let (token, _) = login_and_get_tokens(&server, &username, password).await;
+
+    let created_token = request_json(
+        &server,
+        Method::POST,
+        "/api/v1/security/tokens",
+        Some(&token),
+        None,
+        Some(json!({ "name": "exporter", "scopes": ["users:read", "users:write"] })),
+    )
+    .await;
+    assert_eq!(created_token.status_code(), StatusCode::CREATED);
+    let api_token = created_token.json::<Value>()["token"]
+        .as_str()
+        .expect("应返回令牌明文")
+        .to_string();
+
+    let export = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me/export",
+        Some(&token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(export.status_code(), StatusCode::OK);
+    let disposition = export.header(header::CONTENT_DISPOSITION);
+    let disposition = disposition.to_str().unwrap();
+    assert!(disposition.starts_with("attachment; filename=\"personal-data-"));
+    assert!(disposition.ends_with(".json\""));
+    let archive = export.json::<Value>();
+    assert_eq!(archive["profile"]["id"], json!(user_id));
+    assert_eq!(archive["profile"]["metadata"], json!({}));
+    assert_eq!(archive["sessions"].as_array().map(Vec::len), Some(1));
+    assert_eq!(archive["api_tokens"][0]["name"], json!("exporter"));
+    assert!(
+        archive["api_tokens"][0].get("secret_hash").is_none(),
+        "归档不包含 token 密钥"
+    );
+    assert_eq!(
+        archive["security_events"][0]["event_type"],
+        json!("api_token_created")
+    );
+
+    for (method, uri) in [
+        (Method::GET, "/api/v1/users/me/export"),
+        (Method::DELETE, "/api/v1/users/me"),
+    ] {
+        let response = request_json(
+            &server,
+            method,
+            uri,
+            Some(&api_token),
+            None,
+            Some(json!({ "password": password })),
+        )
+        .await;
+        assert_eq!(
+            response.status_code(),
+            StatusCode::FORBIDDEN,
+            "API token 不能访问 {uri}"
+        );
+    }
+
+    let delete_me = |body: Value| {
+        let (server, token) = (&server, &token);
+        async move {
+            request_json(
+                server,
+                Method::DELETE,
+                "/api/v1/users/me",
+                Some(token),
+                None,
+                Some(body),
+            )
+            .await
+        }
+    };
+    assert_eq!(
+        delete_me(json!({ "password": "WrongPassword#A123" }))
+            .await
+            .status_code(),
+        StatusCode::UNAUTHORIZED
+    );
+    let failure_count = sqlx::query_scalar!(
+        r#"
+SELECT failure_count
+FROM auth_login_throttles
+WHERE scope = 'identifier'
+  AND key = $1
+        "#,
+        username,
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("查询登录失败计数失败");
+    assert_eq!(failure_count, 1, "注销时密码错误计入登录失败次数");
+    let deleted = delete_me(json!({ "password": password })).await;
+    assert_eq!(deleted.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        deleted
+            .maybe_cookie("refresh_token")
+            .and_then(|cookie| cookie.max_age())
+            .map(|max_age| max_age.is_zero()),
+        Some(true),
+        "注销后清除 refresh cookie"
+    );
+
+    let me = request_json(
+        &server,
+        Method::GET,
+        "/api/v1/users/me",
+        Some(&token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(me.status_code(), StatusCode::UNAUTHORIZED);
+
+    let restored = request_json(
+        &server,
+        Method::POST,
+        &format!("/api/v1/users/{user_id}/restore"),
+        Some(&admin_token),
+        None,
+        None,
+    )
+    .await;
+    assert_eq!(restored.status_code(), StatusCode::OK, "保留期内可以恢复");
+    login_and_get_tokens(&server, &username, password).await;
+
+    let event_types = sqlx::query_scalar!(
+        r#"
+SELECT event_type
+FROM security_events
+WHERE user_id = $1
+ORDER BY created_at, id
+        "#,
+        user_id,
+    )
+    .fetch_all(&pool)
+    .await
+    .expect("查询安全事件失败");
+    assert_eq!(
+        event_types,
+        vec![
+            "api_token_created",
+            "personal_data_exported",
+            "account_deletion_requested"
+        ]
+    );
+
+    cleanup_test_users(&pool, &[user_id]).await;
+}
@@ -1,11 +1,14 @@
 pub mod api;
 pub mod config;
+pub mod crypto;
+pub mod csv;
 pub mod db;
 pub mod error;
 pub mod http;
 pub mod modules;
 pub mod password;
 pub mod services;
+pub mod totp;
 pub mod web_assets;
 
 use crate::api::request_id::request_id_middleware;
@@ -16,7 +19,6 @@ use crate::db::connect as connect_db;
 use crate::http::router::{app_router, AppState};
 use anyhow::Context;
 use arc_swap::ArcSwap;
-use axum::extract::ConnectInfo;
 use axum::middleware;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -67,6 +69,8 @@ async fn main() -> anyhow::Result<()> {
         db,
     };
 
+    crate::services::user_purge::spawn(state.clone());
+
     let cors = CorsLayer::permissive();
 
     let access_log = TraceLayer::new_for_http()
@@ -76,11 +80,8 @@ async fn main() -> anyhow::Result<()> {
                 return tracing::Span::none();
             }
 
-            let user_agent = request
-                .headers()
-                .get(axum::http::header::USER_AGENT)
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or("-");
+            let user_agent = crate::api::client_info::user_agent(request.headers())
+                .unwrap_or_else(|| "-".to_string());
 
             let request_id = request
                 .headers()
@@ -88,23 +89,9 @@ async fn main() -> anyhow::Result<()> {
                 .and_then(|v| v.to_str().ok())
                 .unwrap_or("-");
 
-            let forwarded_for = request
-                .headers()
-                .get("x-forwarded-for")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.split(',').next())
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty());
-
-            let remote_ip = request
-                .extensions()
-                .get::<ConnectInfo<SocketAddr>>()
-                .map(|ConnectInfo(addr)| addr.ip().to_string());
-
-            let client_ip = forwarded_for
-                .map(str::to_string)
-                .or(remote_ip)
-                .unwrap_or_else(|| "-".to_string());
+            let client_ip =
+                crate::api::client_info::client_ip(request.headers(), request.extensions())
+                    .unwrap_or_else(|| "-".to_string());
 
             tracing::info_span!(
                 "access",
@@ -0,0 +1,620 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::permissions::{GroupsRead, GroupsWrite, RequirePermission};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::http::router::AppState;
+use crate::services::rbac;
+use crate::services::security_events::{self, SecurityEvent};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GroupResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    /// 授予组内全部成员的权限，与成员自身角色的权限合并生效。
+    pub permissions: Vec<String>,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GroupMemberResponse {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub display_name: String,
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateGroupRequest {
+    #[schema(min_length = 1, max_length = 64)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+
+    #[schema(max_length = 256)]
+    #[serde(default)]
+    #[garde(length(max = 256))]
+    pub description: String,
+
+    /// 权限名列表（见 `GET /api/v1/permissions`），不能超出操作者自身的权限。
+    #[serde(default)]
+    #[garde(length(max = 64))]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PatchGroupRequest {
+    #[schema(min_length = 1, max_length = 64)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
+    #[garde(length(max = 64))]
+    pub name: Option<String>,
+
+    #[schema(max_length = 256)]
+    #[garde(length(max = 256))]
+    pub description: Option<String>,
+
+    /// 整体替换用户组的权限列表。
+    #[garde(length(max = 64))]
+    pub permissions: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups",
+    tag = "groups",
+    responses(
+        (status = 200, description = "获取用户组列表（含权限）", body = [GroupResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_groups_handler(
+    _: RequirePermission<GroupsRead>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<GroupResponse>>, AppError> {
+    let groups = sqlx::query_as!(
+        GroupResponse,
+        r#"
+SELECT
+    g.id,
+    g.name,
+    g.description,
+    ARRAY(
+        SELECT gp.permission
+        FROM user_group_permissions gp
+        WHERE gp.group_id = g.id
+        ORDER BY gp.permission
+    ) AS "permissions!",
+    (
+        SELECT COUNT(*)
+        FROM user_group_members gm
+        WHERE gm.group_id = g.id
+    ) AS "member_count!",
+    g.created_at,
+    g.updated_at
+FROM user_groups g
+ORDER BY g.name
+        "#
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户组列表失败: {e}")))?;
+
+    Ok(Json(groups))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/groups",
+    tag = "groups",
+    request_body = CreateGroupRequest,
+    responses(
+        (status = 201, description = "创建用户组", body = GroupResponse),
+        (status = 400, description = "请求参数错误（组名已存在、权限不存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:write`，且不能授予超出自身的权限）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_group_handler(
+    RequirePermission(current_user, _): RequirePermission<GroupsWrite>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        CreateGroupRequest,
+    >,
+) -> Result<(StatusCode, Json<GroupResponse>), AppError> {
+    let permissions = rbac::normalize_permissions(payload.permissions);
+    rbac::ensure_permissions_exist(&state.db, &permissions).await?;
+    rbac::ensure_within_own_permissions(&current_user, &permissions)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启创建用户组事务失败: {e}")))?;
+
+    let group_id = sqlx::query_scalar!(
+        r#"
+INSERT INTO user_groups (name, description)
+VALUES ($1, $2)
+RETURNING id
+        "#,
+        payload.name,
+        payload.description.trim(),
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| map_group_db_error("创建用户组失败", e))?;
+
+    replace_group_permissions(&mut tx, group_id, &permissions).await?;
+    record_group_event(
+        &mut tx,
+        &current_user,
+        &client,
+        security_events::GROUP_CREATED,
+        None,
+        serde_json::json!({
+            "group_id": group_id,
+            "name": payload.name,
+            "permissions": permissions,
+        }),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交创建用户组事务失败: {e}")))?;
+
+    let group = get_group(&state.db, group_id).await?;
+    Ok((StatusCode::CREATED, Json(group)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/groups/{group_id}",
+    tag = "groups",
+    params(("group_id" = Uuid, Path, description = "用户组 ID")),
+    request_body = PatchGroupRequest,
+    responses(
+        (status = 200, description = "更新用户组（权限变更即时生效）", body = GroupResponse),
+        (status = 400, description = "请求参数错误（组名已存在、权限不存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:write`，且不能授予超出自身的权限）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户组不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn patch_group_handler(
+    RequirePermission(current_user, _): RequirePermission<GroupsWrite>,
+    Path(group_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        PatchGroupRequest,
+    >,
+) -> Result<Json<GroupResponse>, AppError> {
+    if payload.name.is_none() && payload.description.is_none() && payload.permissions.is_none() {
+        return Err(AppError::validation("至少需要提供一个可更新字段"));
+    }
+
+    let existing = get_group(&state.db, group_id).await?;
+    rbac::ensure_within_own_permissions(&current_user, &existing.permissions)?;
+
+    let permissions = payload.permissions.map(rbac::normalize_permissions);
+    if let Some(permissions) = permissions.as_deref() {
+        rbac::ensure_permissions_exist(&state.db, permissions).await?;
+        rbac::ensure_within_own_permissions(&current_user, permissions)?;
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启更新用户组事务失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+UPDATE user_groups
+SET name = COALESCE($2, name),
+    description = COALESCE($3, description),
+    updated_at = NOW()
+WHERE id = $1
+        "#,
+        group_id,
+        payload.name,
+        payload.description.as_deref().map(str::trim),
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| map_group_db_error("更新用户组失败", e))?;
+
+    if let Some(permissions) = permissions.as_deref() {
+        replace_group_permissions(&mut tx, group_id, permissions).await?;
+        record_group_event(
+            &mut tx,
+            &current_user,
+            &client,
+            security_events::GROUP_UPDATED,
+            None,
+            serde_json::json!({
+                "group_id": group_id,
+                "from": existing.permissions,
+                "to": permissions,
+            }),
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交更新用户组事务失败: {e}")))?;
+
+    Ok(Json(get_group(&state.db, group_id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/groups/{group_id}",
+    tag = "groups",
+    params(("group_id" = Uuid, Path, description = "用户组 ID")),
+    responses(
+        (status = 204, description = "删除用户组（成员随之失去组内权限）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户组不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_group_handler(
+    RequirePermission(current_user, _): RequirePermission<GroupsWrite>,
+    Path(group_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let existing = get_group(&state.db, group_id).await?;
+    rbac::ensure_within_own_permissions(&current_user, &existing.permissions)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启删除用户组事务失败: {e}")))?;
+
+    sqlx::query!("DELETE FROM user_groups WHERE id = $1", group_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("删除用户组失败: {e}")))?;
+
+    record_group_event(
+        &mut tx,
+        &current_user,
+        &client,
+        security_events::GROUP_DELETED,
+        None,
+        serde_json::json!({
+            "group_id": group_id,
+            "name": existing.name,
+            "permissions": existing.permissions,
+        }),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交删除用户组事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/groups/{group_id}/members",
+    tag = "groups",
+    params(("group_id" = Uuid, Path, description = "用户组 ID")),
+    responses(
+        (status = 200, description = "获取用户组成员", body = [GroupMemberResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户组不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_group_members_handler(
+    _: RequirePermission<GroupsRead>,
+    Path(group_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<GroupMemberResponse>>, AppError> {
+    get_group(&state.db, group_id).await?;
+
+    let members = sqlx::query_as!(
+        GroupMemberResponse,
+        r#"
+SELECT
+    gm.user_id,
+    u.username,
+    u.display_name,
+    u.email,
+    gm.created_at
+FROM user_group_members gm
+INNER JOIN users u ON u.id = gm.user_id
+WHERE gm.group_id = $1
+  AND u.deleted_at IS NULL
+ORDER BY gm.created_at, gm.user_id
+        "#,
+        group_id,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户组成员失败: {e}")))?;
+
+    Ok(Json(members))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/groups/{group_id}/members/{user_id}",
+    tag = "groups",
+    params(
+        ("group_id" = Uuid, Path, description = "用户组 ID"),
+        ("user_id" = Uuid, Path, description = "用户 ID")
+    ),
+    responses(
+        (status = 204, description = "将用户加入用户组（已是成员时无变化）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:write`，组内权限与目标用户的权限均不能超出自身）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户组或用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn put_group_member_handler(
+    RequirePermission(current_user, _): RequirePermission<GroupsWrite>,
+    Path((group_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let group = get_group(&state.db, group_id).await?;
+    rbac::ensure_within_own_permissions(&current_user, &group.permissions)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启添加用户组成员事务失败: {e}")))?;
+
+    let user_exists = sqlx::query_scalar!(
+        r#"
+SELECT EXISTS (
+    SELECT 1 FROM users WHERE id = $1 AND deleted_at IS NULL
+) AS "exists!"
+        "#,
+        user_id,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户失败: {e}")))?;
+    if !user_exists {
+        return Err(AppError::NotFound(format!("用户不存在: {user_id}")));
+    }
+    rbac::ensure_user_manageable(&mut *tx, &current_user, user_id).await?;
+
+    let added = sqlx::query!(
+        r#"
+INSERT INTO user_group_members (group_id, user_id)
+VALUES ($1, $2)
+ON CONFLICT (group_id, user_id) DO NOTHING
+        "#,
+        group_id,
+        user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("添加用户组成员失败: {e}")))?;
+
+    if added.rows_affected() > 0 {
+        record_group_event(
+            &mut tx,
+            &current_user,
+            &client,
+            security_events::GROUP_MEMBER_ADDED,
+            Some(user_id),
+            serde_json::json!({
+                "group_id": group_id,
+                "permissions": group.permissions,
+                "changed_by": current_user.user_id,
+            }),
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交添加用户组成员事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/groups/{group_id}/members/{user_id}",
+    tag = "groups",
+    params(
+        ("group_id" = Uuid, Path, description = "用户组 ID"),
+        ("user_id" = Uuid, Path, description = "用户 ID")
+    ),
+    responses(
+        (status = 204, description = "将用户移出用户组"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `groups:write`，且目标用户的权限不能超出自身）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不是该组成员", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_group_member_handler(
+    RequirePermission(current_user, _): RequirePermission<GroupsWrite>,
+    Path((group_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启移除用户组成员事务失败: {e}")))?;
+
+    let removed = sqlx::query!(
+        "DELETE FROM user_group_members WHERE group_id = $1 AND user_id = $2",
+        group_id,
+        user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("移除用户组成员失败: {e}")))?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("用户组成员不存在: {user_id}")));
+    }
+
+    record_group_event(
+        &mut tx,
+        &current_user,
+        &client,
+        security_events::GROUP_MEMBER_REMOVED,
+        Some(user_id),
+        serde_json::json!({
+            "group_id": group_id,
+            "changed_by": current_user.user_id,
+        }),
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交移除用户组成员事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_group(db: &DbPool, group_id: Uuid) -> Result<GroupResponse, AppError> {
+    sqlx::query_as!(
+        GroupResponse,
+        r#"
+SELECT
+    g.id,
+    g.name,
+    g.description,
+    ARRAY(
+        SELECT gp.permission
+        FROM user_group_permissions gp
+        WHERE gp.group_id = g.id
+        ORDER BY gp.permission
+    ) AS "permissions!",
+    (
+        SELECT COUNT(*)
+        FROM user_group_members gm
+        WHERE gm.group_id = g.id
+    ) AS "member_count!",
+    g.created_at,
+    g.updated_at
+FROM user_groups g
+WHERE g.id = $1
+        "#,
+        group_id,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户组失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户组不存在: {group_id}")))
+}
+
+async fn replace_group_permissions(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    group_id: Uuid,
+    permissions: &[String],
+) -> Result<(), AppError> {
+    sqlx::query!(
+        "DELETE FROM user_group_permissions WHERE group_id = $1",
+        group_id
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清除用户组权限失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO user_group_permissions (group_id, permission)
+SELECT $1, permission
+FROM UNNEST($2::text[]) AS permission
+        "#,
+        group_id,
+        permissions,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| map_group_db_error("写入用户组权限失败", e))?;
+
+    Ok(())
+}
+
+/// 成员变更的事件归属于被加入/移出的用户，其余归属于操作者。
+async fn record_group_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    current_user: &CurrentUser,
+    client: &ClientInfo,
+    event_type: &str,
+    member_id: Option<Uuid>,
+    details: serde_json::Value,
+) -> Result<(), AppError> {
+    security_events::record(
+        &mut **tx,
+        SecurityEvent {
+            event_type,
+            user_id: Some(member_id.unwrap_or(current_user.user_id)),
+            session_id: member_id.map_or(current_user.session_id, |_| None),
+            client,
+            details,
+        },
+    )
+    .await
+}
+
+fn map_group_db_error(prefix: &str, err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_err) = &err {
+        if let Some(code) = db_err.code().as_deref() {
+            return match code {
+                "23505" => AppError::validation(format!("{prefix}: 用户组名称已存在")),
+                "23503" => AppError::validation(format!("{prefix}: 关联数据不存在")),
+                _ => AppError::InternalError(format!("{prefix}: {err}")),
+            };
+        }
+    }
+
+    AppError::InternalError(format!("{prefix}: {err}"))
+}
@@ -1,3 +1,6 @@
+pub mod groups;
+pub mod organizations;
+pub mod roles;
 pub mod security;
 pub mod sessions;
 pub mod settings;
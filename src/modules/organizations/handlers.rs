@@ -0,0 +1,609 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::permissions::{OrganizationsRead, OrganizationsWrite, RequirePermission};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::http::router::AppState;
+use crate::services::rbac;
+use crate::services::security_events::{self, SecurityEvent};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationResponse {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub member_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationMemberResponse {
+    pub user_id: Uuid,
+    pub username: Option<String>,
+    pub display_name: String,
+    pub email: String,
+    /// 组织内角色，只有组织级权限在该组织上下文中生效。
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MyOrganizationResponse {
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateOrganizationRequest {
+    /// 组织标识，小写字母或数字开头，只能包含小写字母、数字或连字符。
+    #[schema(min_length = 1, max_length = 64)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 64))]
+    #[garde(custom(crate::api::garde_helpers::organization_slug_format))]
+    pub slug: String,
+
+    #[schema(min_length = 1, max_length = 128)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 128))]
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PatchOrganizationRequest {
+    #[schema(min_length = 1, max_length = 128)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
+    #[garde(length(max = 128))]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PutOrganizationMemberRequest {
+    /// 组织内角色，不能超出操作者自身的权限。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 32))]
+    pub role: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations",
+    tag = "organizations",
+    responses(
+        (status = 200, description = "获取组织列表", body = [OrganizationResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_organizations_handler(
+    _: RequirePermission<OrganizationsRead>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<OrganizationResponse>>, AppError> {
+    let organizations = sqlx::query_as!(
+        OrganizationResponse,
+        r#"
+SELECT
+    o.id,
+    o.slug,
+    o.name,
+    (
+        SELECT COUNT(*)
+        FROM organization_members m
+        WHERE m.organization_id = o.id
+    ) AS "member_count!",
+    o.created_at,
+    o.updated_at
+FROM organizations o
+ORDER BY o.slug
+        "#
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询组织列表失败: {e}")))?;
+
+    Ok(Json(organizations))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/organizations",
+    tag = "organizations",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, description = "创建组织", body = OrganizationResponse),
+        (status = 400, description = "请求参数错误（组织标识已存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_organization_handler(
+    RequirePermission(current_user, _): RequirePermission<OrganizationsWrite>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        CreateOrganizationRequest,
+    >,
+) -> Result<(StatusCode, Json<OrganizationResponse>), AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启创建组织事务失败: {e}")))?;
+
+    let organization_id = sqlx::query_scalar!(
+        r#"
+INSERT INTO organizations (slug, name)
+VALUES ($1, $2)
+RETURNING id
+        "#,
+        payload.slug,
+        payload.name,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| map_organization_db_error("创建组织失败", e))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::ORGANIZATION_CREATED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "organization_id": organization_id,
+                "slug": payload.slug,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交创建组织事务失败: {e}")))?;
+
+    let organization = get_organization(&state.db, organization_id).await?;
+    Ok((StatusCode::CREATED, Json(organization)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/organizations/{organization_id}",
+    tag = "organizations",
+    params(("organization_id" = Uuid, Path, description = "组织 ID")),
+    request_body = PatchOrganizationRequest,
+    responses(
+        (status = 200, description = "更新组织", body = OrganizationResponse),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "组织不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn patch_organization_handler(
+    _: RequirePermission<OrganizationsWrite>,
+    Path(organization_id): Path<Uuid>,
+    State(state): State<AppState>,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        PatchOrganizationRequest,
+    >,
+) -> Result<Json<OrganizationResponse>, AppError> {
+    let Some(name) = payload.name else {
+        return Err(AppError::validation("至少需要提供一个可更新字段"));
+    };
+
+    let updated = sqlx::query!(
+        r#"
+UPDATE organizations
+SET name = $2,
+    updated_at = NOW()
+WHERE id = $1
+        "#,
+        organization_id,
+        name,
+    )
+    .execute(&state.db)
+    .await
+    .map_err(|e| map_organization_db_error("更新组织失败", e))?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("组织不存在: {organization_id}")));
+    }
+
+    Ok(Json(get_organization(&state.db, organization_id).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{organization_id}",
+    tag = "organizations",
+    params(("organization_id" = Uuid, Path, description = "组织 ID")),
+    responses(
+        (status = 204, description = "删除组织（同时删除成员关系、组织配置与绑定该组织的 API token，用户账号保留）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "组织不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_organization_handler(
+    RequirePermission(current_user, _): RequirePermission<OrganizationsWrite>,
+    Path(organization_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启删除组织事务失败: {e}")))?;
+
+    let slug = sqlx::query_scalar!(
+        "DELETE FROM organizations WHERE id = $1 RETURNING slug",
+        organization_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("删除组织失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("组织不存在: {organization_id}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::ORGANIZATION_DELETED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "organization_id": organization_id,
+                "slug": slug,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交删除组织事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/organizations/{organization_id}/members",
+    tag = "organizations",
+    params(("organization_id" = Uuid, Path, description = "组织 ID")),
+    responses(
+        (status = 200, description = "获取组织成员", body = [OrganizationMemberResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "组织不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_organization_members_handler(
+    _: RequirePermission<OrganizationsRead>,
+    Path(organization_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<OrganizationMemberResponse>>, AppError> {
+    get_organization(&state.db, organization_id).await?;
+
+    let members = sqlx::query_as!(
+        OrganizationMemberResponse,
+        r#"
+SELECT
+    m.user_id,
+    u.username,
+    u.display_name,
+    u.email,
+    m.role,
+    m.created_at,
+    m.updated_at
+FROM organization_members m
+INNER JOIN users u ON u.id = m.user_id
+WHERE m.organization_id = $1
+  AND u.deleted_at IS NULL
+ORDER BY m.created_at, m.user_id
+        "#,
+        organization_id,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询组织成员失败: {e}")))?;
+
+    Ok(Json(members))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/organizations/{organization_id}/members/{user_id}",
+    tag = "organizations",
+    params(
+        ("organization_id" = Uuid, Path, description = "组织 ID"),
+        ("user_id" = Uuid, Path, description = "用户 ID")
+    ),
+    request_body = PutOrganizationMemberRequest,
+    responses(
+        (status = 200, description = "添加成员或修改其组织内角色", body = OrganizationMemberResponse),
+        (status = 400, description = "请求参数错误（角色不存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:write`，且不能分配超出自身权限的角色）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "组织或用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn put_organization_member_handler(
+    RequirePermission(current_user, _): RequirePermission<OrganizationsWrite>,
+    Path((organization_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        PutOrganizationMemberRequest,
+    >,
+) -> Result<Json<OrganizationMemberResponse>, AppError> {
+    get_organization(&state.db, organization_id).await?;
+    rbac::ensure_role_assignable(&state.db, &current_user, &payload.role).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启更新组织成员事务失败: {e}")))?;
+
+    let user_exists = sqlx::query_scalar!(
+        r#"
+SELECT EXISTS (
+    SELECT 1 FROM users WHERE id = $1 AND deleted_at IS NULL
+) AS "exists!"
+        "#,
+        user_id,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户失败: {e}")))?;
+    if !user_exists {
+        return Err(AppError::NotFound(format!("用户不存在: {user_id}")));
+    }
+
+    sqlx::query!(
+        r#"
+INSERT INTO organization_members (organization_id, user_id, role)
+VALUES ($1, $2, $3)
+ON CONFLICT (organization_id, user_id)
+DO UPDATE SET role = EXCLUDED.role, updated_at = NOW()
+        "#,
+        organization_id,
+        user_id,
+        payload.role,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| map_organization_db_error("更新组织成员失败", e))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::ORGANIZATION_MEMBER_UPDATED,
+            user_id: Some(user_id),
+            session_id: None,
+            client: &client,
+            details: serde_json::json!({
+                "organization_id": organization_id,
+                "role": payload.role,
+                "changed_by": current_user.user_id,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交更新组织成员事务失败: {e}")))?;
+
+    let member = sqlx::query_as!(
+        OrganizationMemberResponse,
+        r#"
+SELECT
+    m.user_id,
+    u.username,
+    u.display_name,
+    u.email,
+    m.role,
+    m.created_at,
+    m.updated_at
+FROM organization_members m
+INNER JOIN users u ON u.id = m.user_id
+WHERE m.organization_id = $1
+  AND m.user_id = $2
+        "#,
+        organization_id,
+        user_id,
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询组织成员失败: {e}")))?;
+
+    Ok(Json(member))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/organizations/{organization_id}/members/{user_id}",
+    tag = "organizations",
+    params(
+        ("organization_id" = Uuid, Path, description = "组织 ID"),
+        ("user_id" = Uuid, Path, description = "用户 ID")
+    ),
+    responses(
+        (status = 204, description = "移除组织成员（同时吊销其绑定该组织的 API token）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要平台上下文的 `organizations:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "成员不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_organization_member_handler(
+    RequirePermission(current_user, _): RequirePermission<OrganizationsWrite>,
+    Path((organization_id, user_id)): Path<(Uuid, Uuid)>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启移除组织成员事务失败: {e}")))?;
+
+    let removed = sqlx::query!(
+        "DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+        organization_id,
+        user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("移除组织成员失败: {e}")))?;
+    if removed.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("组织成员不存在: {user_id}")));
+    }
+
+    // 绑定该组织的 token 离开组织后无法再使用，直接吊销，避免列表中残留无效 token。
+    sqlx::query!(
+        r#"
+UPDATE api_tokens
+SET revoked_at = NOW()
+WHERE organization_id = $1
+  AND user_id = $2
+  AND revoked_at IS NULL
+        "#,
+        organization_id,
+        user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("吊销组织 API token 失败: {e}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::ORGANIZATION_MEMBER_REMOVED,
+            user_id: Some(user_id),
+            session_id: None,
+            client: &client,
+            details: serde_json::json!({
+                "organization_id": organization_id,
+                "changed_by": current_user.user_id,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交移除组织成员事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/organizations",
+    tag = "organizations",
+    responses(
+        (status = 200, description = "获取当前用户所属的组织及组织内角色", body = [MyOrganizationResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_my_organizations_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MyOrganizationResponse>>, AppError> {
+    let organizations = sqlx::query_as!(
+        MyOrganizationResponse,
+        r#"
+SELECT o.id, o.slug, o.name, m.role
+FROM organization_members m
+INNER JOIN organizations o ON o.id = m.organization_id
+WHERE m.user_id = $1
+ORDER BY o.slug
+        "#,
+        current_user.user_id,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询所属组织失败: {e}")))?;
+
+    Ok(Json(organizations))
+}
+
+async fn get_organization(
+    db: &DbPool,
+    organization_id: Uuid,
+) -> Result<OrganizationResponse, AppError> {
+    sqlx::query_as!(
+        OrganizationResponse,
+        r#"
+SELECT
+    o.id,
+    o.slug,
+    o.name,
+    (
+        SELECT COUNT(*)
+        FROM organization_members m
+        WHERE m.organization_id = o.id
+    ) AS "member_count!",
+    o.created_at,
+    o.updated_at
+FROM organizations o
+WHERE o.id = $1
+        "#,
+        organization_id,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询组织失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("组织不存在: {organization_id}")))
+}
+
+fn map_organization_db_error(prefix: &str, err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_err) = &err {
+        if let Some(code) = db_err.code().as_deref() {
+            return match code {
+                "23505" => AppError::validation(format!("{prefix}: 组织标识已存在")),
+                "23503" => AppError::validation(format!("{prefix}: 关联数据不存在")),
+                _ => AppError::InternalError(format!("{prefix}: {err}")),
+            };
+        }
+    }
+
+    AppError::InternalError(format!("{prefix}: {err}"))
+}
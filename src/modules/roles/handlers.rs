@@ -0,0 +1,475 @@
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use chrono::{DateTime, Utc};
+use garde::Validate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::permissions::{RequirePermission, RolesRead, RolesWrite};
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::http::router::AppState;
+use crate::services::rbac;
+use crate::services::security_events::{self, SecurityEvent};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RoleResponse {
+    pub name: String,
+    pub description: String,
+    /// 内置角色（`admin` / `user` / `auditor`）不可修改或删除。
+    pub is_builtin: bool,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PermissionResponse {
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateRoleRequest {
+    /// 小写字母开头，只能包含小写字母、数字或下划线。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 32))]
+    #[garde(custom(crate::api::garde_helpers::role_name_format))]
+    pub name: String,
+
+    #[schema(max_length = 256)]
+    #[serde(default)]
+    #[garde(length(max = 256))]
+    pub description: String,
+
+    /// 权限名列表（见 `GET /api/v1/permissions`），不能超出操作者自身的权限。
+    #[serde(default)]
+    #[garde(length(max = 64))]
+    pub permissions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+#[serde(deny_unknown_fields)]
+pub struct PatchRoleRequest {
+    #[schema(max_length = 256)]
+    #[garde(length(max = 256))]
+    pub description: Option<String>,
+
+    /// 整体替换角色的权限列表。
+    #[garde(length(max = 64))]
+    pub permissions: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/roles",
+    tag = "roles",
+    responses(
+        (status = 200, description = "获取角色列表（含权限）", body = [RoleResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `roles:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_roles_handler(
+    _: RequirePermission<RolesRead>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<RoleResponse>>, AppError> {
+    let rows = sqlx::query_as!(
+        RoleRow,
+        r#"
+SELECT
+    r.name,
+    r.description,
+    r.is_builtin,
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = r.name
+        ORDER BY rp.permission
+    ) AS "permissions!",
+    r.created_at,
+    r.updated_at
+FROM roles r
+ORDER BY r.is_builtin DESC, r.name
+        "#
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询角色列表失败: {e}")))?;
+
+    Ok(Json(rows.into_iter().map(RoleResponse::from).collect()))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/permissions",
+    tag = "roles",
+    responses(
+        (status = 200, description = "获取可分配的权限列表", body = [PermissionResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `roles:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_permissions_handler(
+    _: RequirePermission<RolesRead>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<PermissionResponse>>, AppError> {
+    let permissions = sqlx::query_as!(
+        PermissionResponse,
+        r#"
+SELECT name, description
+FROM permissions
+ORDER BY name
+        "#
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询权限列表失败: {e}")))?;
+
+    Ok(Json(permissions))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/roles",
+    tag = "roles",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, description = "创建自定义角色", body = RoleResponse),
+        (status = 400, description = "请求参数错误（角色已存在、权限不存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `roles:write`，且不能授予超出自身的权限）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_role_handler(
+    RequirePermission(current_user, _): RequirePermission<RolesWrite>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        CreateRoleRequest,
+    >,
+) -> Result<(StatusCode, Json<RoleResponse>), AppError> {
+    let permissions = rbac::normalize_permissions(payload.permissions);
+    rbac::ensure_permissions_exist(&state.db, &permissions).await?;
+    rbac::ensure_within_own_permissions(&current_user, &permissions)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启创建角色事务失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO roles (name, description)
+VALUES ($1, $2)
+        "#,
+        payload.name,
+        payload.description.trim(),
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| map_role_db_error("创建角色失败", e))?;
+
+    replace_role_permissions(&mut tx, &payload.name, &permissions).await?;
+    record_role_event(
+        &mut tx,
+        &current_user,
+        &client,
+        security_events::ROLE_CREATED,
+        &payload.name,
+        &permissions,
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交创建角色事务失败: {e}")))?;
+
+    let role = get_role(&state.db, &payload.name).await?;
+    Ok((StatusCode::CREATED, Json(role)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/roles/{role}",
+    tag = "roles",
+    params(("role" = String, Path, description = "角色名")),
+    request_body = PatchRoleRequest,
+    responses(
+        (status = 200, description = "更新自定义角色（权限变更即时生效）", body = RoleResponse),
+        (status = 400, description = "请求参数错误（内置角色不可修改、权限不存在等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `roles:write`，且不能授予超出自身的权限）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "角色不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn patch_role_handler(
+    RequirePermission(current_user, _): RequirePermission<RolesWrite>,
+    Path(role): Path<String>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        PatchRoleRequest,
+    >,
+) -> Result<Json<RoleResponse>, AppError> {
+    if payload.description.is_none() && payload.permissions.is_none() {
+        return Err(AppError::validation("至少需要提供一个可更新字段"));
+    }
+
+    let existing = get_role(&state.db, &role).await?;
+    ensure_custom_role(&existing, "修改")?;
+    rbac::ensure_within_own_permissions(&current_user, &existing.permissions)?;
+
+    let permissions = payload.permissions.map(rbac::normalize_permissions);
+    if let Some(permissions) = permissions.as_deref() {
+        rbac::ensure_permissions_exist(&state.db, permissions).await?;
+        rbac::ensure_within_own_permissions(&current_user, permissions)?;
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启更新角色事务失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+UPDATE roles
+SET description = COALESCE($2, description),
+    updated_at = NOW()
+WHERE name = $1
+        "#,
+        role,
+        payload.description.as_deref().map(str::trim),
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| map_role_db_error("更新角色失败", e))?;
+
+    if let Some(permissions) = permissions.as_deref() {
+        replace_role_permissions(&mut tx, &role, permissions).await?;
+        record_role_event(
+            &mut tx,
+            &current_user,
+            &client,
+            security_events::ROLE_UPDATED,
+            &role,
+            permissions,
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交更新角色事务失败: {e}")))?;
+
+    Ok(Json(get_role(&state.db, &role).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/roles/{role}",
+    tag = "roles",
+    params(("role" = String, Path, description = "角色名")),
+    responses(
+        (status = 204, description = "删除自定义角色"),
+        (status = 400, description = "内置角色或仍有用户使用的角色不可删除", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `roles:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "角色不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_role_handler(
+    RequirePermission(current_user, _): RequirePermission<RolesWrite>,
+    Path(role): Path<String>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let existing = get_role(&state.db, &role).await?;
+    ensure_custom_role(&existing, "删除")?;
+    rbac::ensure_within_own_permissions(&current_user, &existing.permissions)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启删除角色事务失败: {e}")))?;
+
+    // 已逻辑删除的用户仍保留角色，恢复后需要继续使用，因此同样阻止删除。
+    let in_use = sqlx::query_scalar!(
+        r#"
+SELECT (
+    EXISTS (SELECT 1 FROM users WHERE role = $1)
+    OR EXISTS (SELECT 1 FROM organization_members WHERE role = $1)
+) AS "exists!"
+        "#,
+        role,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询角色使用情况失败: {e}")))?;
+    if in_use {
+        return Err(AppError::validation(
+            "仍有用户（含已删除用户）或组织成员使用该角色，无法删除",
+        ));
+    }
+
+    sqlx::query!("DELETE FROM roles WHERE name = $1", role)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| map_role_db_error("删除角色失败", e))?;
+
+    record_role_event(
+        &mut tx,
+        &current_user,
+        &client,
+        security_events::ROLE_DELETED,
+        &role,
+        &existing.permissions,
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交删除角色事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(sqlx::FromRow)]
+struct RoleRow {
+    name: String,
+    description: String,
+    is_builtin: bool,
+    permissions: Vec<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<RoleRow> for RoleResponse {
+    fn from(row: RoleRow) -> Self {
+        Self {
+            name: row.name,
+            description: row.description,
+            is_builtin: row.is_builtin,
+            permissions: row.permissions,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+async fn get_role(db: &DbPool, role: &str) -> Result<RoleResponse, AppError> {
+    let row = sqlx::query_as!(
+        RoleRow,
+        r#"
+SELECT
+    r.name,
+    r.description,
+    r.is_builtin,
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = r.name
+        ORDER BY rp.permission
+    ) AS "permissions!",
+    r.created_at,
+    r.updated_at
+FROM roles r
+WHERE r.name = $1
+        "#,
+        role,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询角色失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("角色不存在: {role}")))?;
+
+    Ok(row.into())
+}
+
+fn ensure_custom_role(role: &RoleResponse, action: &str) -> Result<(), AppError> {
+    if role.is_builtin {
+        return Err(AppError::validation(format!(
+            "内置角色不可{action}: {}",
+            role.name
+        )));
+    }
+    Ok(())
+}
+
+async fn replace_role_permissions(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    role: &str,
+    permissions: &[String],
+) -> Result<(), AppError> {
+    sqlx::query!("DELETE FROM role_permissions WHERE role = $1", role)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("清除角色权限失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO role_permissions (role, permission)
+SELECT $1, permission
+FROM UNNEST($2::text[]) AS permission
+        "#,
+        role,
+        permissions,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| map_role_db_error("写入角色权限失败", e))?;
+
+    Ok(())
+}
+
+async fn record_role_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    current_user: &CurrentUser,
+    client: &ClientInfo,
+    event_type: &str,
+    role: &str,
+    permissions: &[String],
+) -> Result<(), AppError> {
+    security_events::record(
+        &mut **tx,
+        SecurityEvent {
+            event_type,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client,
+            details: serde_json::json!({ "role": role, "permissions": permissions }),
+        },
+    )
+    .await
+}
+
+fn map_role_db_error(prefix: &str, err: sqlx::Error) -> AppError {
+    if let sqlx::Error::Database(db_err) = &err {
+        if let Some(code) = db_err.code().as_deref() {
+            return match code {
+                "23505" => AppError::validation(format!("{prefix}: 角色已存在")),
+                "23503" => AppError::validation(format!("{prefix}: 关联数据不存在或仍被引用")),
+                _ => AppError::InternalError(format!("{prefix}: {err}")),
+            };
+        }
+    }
+
+    AppError::InternalError(format!("{prefix}: {err}"))
+}
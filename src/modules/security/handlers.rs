@@ -1,12 +1,22 @@
-use axum::extract::{Extension, State};
+use axum::extract::{Extension, Path, State};
 use axum::http::StatusCode;
+use axum::Json;
 use garde::Validate;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::permissions::{JwtKeysRead, JwtKeysWrite, RequirePermission};
 use crate::error::AppError;
 use crate::http::router::AppState;
+use crate::services::api_tokens::{self, ApiTokenScope};
+use crate::services::organizations;
+use crate::services::security_events::{self, SecurityEvent};
+
+const TOTP_ISSUER: &str = "PROJECT_NAME";
+const MAX_JWT_KEY_GRACE_PERIOD_SECS: u64 = 30 * 24 * 60 * 60;
+const MAX_API_TOKEN_EXPIRES_IN_DAYS: u32 = 365;
 
 #[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct PatchCurrentUserPasswordRequest {
@@ -37,6 +47,7 @@ pub struct PatchCurrentUserPasswordRequest {
 pub async fn patch_current_user_password_handler(
     Extension(current_user): Extension<CurrentUser>,
     State(state): State<AppState>,
+    client: ClientInfo,
     crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
         PatchCurrentUserPasswordRequest,
     >,
@@ -59,49 +70,1107 @@ pub async fn patch_current_user_password_handler(
         .await
         .map_err(|e| AppError::InternalError(format!("开启改密事务失败: {e}")))?;
 
-    let updated = sqlx::query!(
+    crate::services::user_passwords::set_password_and_revoke_sessions(
+        &mut tx,
+        current_user.user_id,
+        &hash,
+        false,
+        "password_changed",
+        &client,
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交改密事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct RequestPasswordResetRequest {
+    /// 账号绑定的邮箱（不区分大小写）。
+    #[schema(format = "email", min_length = 1, max_length = 320)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 320))]
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct ConfirmPasswordResetRequest {
+    /// 邮件链接中的 `token` 参数。
+    #[schema(min_length = 1, max_length = 256)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 256))]
+    pub token: String,
+
+    #[schema(format = "password", min_length = 8, max_length = 256)]
+    #[garde(custom(crate::api::garde_helpers::string_trim_min_len_8))]
+    #[garde(length(max = 256))]
+    pub new_password: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/password-reset",
+    tag = "security",
+    request_body = RequestPasswordResetRequest,
+    responses(
+        (status = 202, description = "已受理（无 body）；无论邮箱是否存在均返回 202，邮件异步发送"),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    )
+)]
+pub async fn request_password_reset_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        RequestPasswordResetRequest,
+    >,
+) -> Result<StatusCode, AppError> {
+    // 查库、哈希与发信都放到后台执行，响应耗时不随邮箱是否存在而变化。
+    tokio::spawn(async move {
+        if let Err(e) = issue_password_reset(&state, &payload.email, &client).await {
+            tracing::error!(error = %e, "签发重置密码邮件失败");
+        }
+    });
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn issue_password_reset(
+    state: &AppState,
+    email: &str,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let cfg = state.config.load_full();
+
+    let users = sqlx::query!(
         r#"
-UPDATE users
-SET password_hash = $2,
-    auth_version = auth_version + 1,
-    updated_at = NOW()
+SELECT id, email, username IS NOT NULL AS "has_username!"
+FROM users
+WHERE lower(email) = lower($1)
+  AND deleted_at IS NULL
+  AND is_active = TRUE
+LIMIT 2
+        "#,
+        email,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询重置密码用户失败: {e}")))?;
+    // 仅大小写不同的多个邮箱无法判断归属，宁可不发。
+    let [user] = users.as_slice() else {
+        return Ok(());
+    };
+    // 密码只能配合用户名登录，没有用户名的账号重置了也无法使用。
+    if !user.has_username {
+        return Ok(());
+    }
+
+    let generated = crate::services::password_reset::generate()?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启重置密码事务失败: {e}")))?;
+
+    // 锁住用户行，串行化同一用户的并发请求，保证冷却期判断有效。
+    sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user.id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("锁定重置密码用户失败: {e}")))?;
+
+    let recently_issued = sqlx::query_scalar!(
+        r#"
+SELECT EXISTS (
+    SELECT 1
+    FROM password_reset_tokens
+    WHERE user_id = $1
+      AND created_at > NOW() - make_interval(secs => $2)
+) AS "exists!"
+        "#,
+        user.id,
+        crate::services::password_reset::REQUEST_COOLDOWN_SECS as f64,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询重置令牌失败: {e}")))?;
+    if recently_issued {
+        return Ok(());
+    }
+
+    // 新链接签发后旧链接全部作废。
+    sqlx::query!(
+        r#"
+UPDATE password_reset_tokens
+SET consumed_at = NOW()
+WHERE user_id = $1
+  AND consumed_at IS NULL
+        "#,
+        user.id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("作废旧重置令牌失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO password_reset_tokens (id, user_id, secret_hash, expires_at, created_ip, created_user_agent)
+VALUES ($1, $2, $3, NOW() + make_interval(mins => $4), $5, $6)
+        "#,
+        generated.id,
+        user.id,
+        generated.secret_hash,
+        crate::services::password_reset::TOKEN_TTL_MINUTES as i32,
+        client.ip,
+        client.user_agent,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("保存重置令牌失败: {e}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::PASSWORD_RESET_REQUESTED,
+            user_id: Some(user.id),
+            session_id: None,
+            client,
+            details: serde_json::json!({ "reset_token_id": generated.id }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交重置密码事务失败: {e}")))?;
+
+    let link =
+        crate::services::password_reset::reset_link(&cfg.app.public_base_url, &generated.token);
+    let body = format!(
+        "您正在重置 PROJECT_NAME 账号的密码，请在 {} 分钟内打开以下链接设置新密码：\n\n{link}\n\n该链接只能使用一次。如果这不是您本人的操作，请忽略本邮件。\n",
+        crate::services::password_reset::TOKEN_TTL_MINUTES
+    );
+    crate::services::mail::send(&cfg.mail, &user.email, "重置密码", &body)
+        .await
+        .map_err(|e| AppError::InternalError(format!("发送重置密码邮件失败: {e}")))?;
+
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/password-reset/confirm",
+    tag = "security",
+    request_body = ConfirmPasswordResetRequest,
+    responses(
+        (status = 204, description = "密码已重置（无 body），该用户的全部会话已撤销"),
+        (status = 400, description = "请求参数错误 / 重置链接无效或已过期", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    )
+)]
+pub async fn confirm_password_reset_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        ConfirmPasswordResetRequest,
+    >,
+) -> Result<StatusCode, AppError> {
+    let invalid = || AppError::validation("重置链接无效或已过期");
+    let (token_id, secret) =
+        crate::services::password_reset::parse_token(&payload.token).ok_or_else(invalid)?;
+
+    let new_password = payload.new_password.trim();
+    let hash = crate::password::hash_password_argon2id(new_password)
+        .map_err(|e| AppError::validation(format!("新密码不合法: {e}")))?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启重置密码事务失败: {e}")))?;
+
+    let row = sqlx::query!(
+        r#"
+SELECT t.user_id, t.secret_hash
+FROM password_reset_tokens t
+INNER JOIN users u ON u.id = t.user_id
+WHERE t.id = $1
+  AND t.consumed_at IS NULL
+  AND t.expires_at > NOW()
+  AND u.deleted_at IS NULL
+  AND u.is_active = TRUE
+FOR UPDATE OF t
+        "#,
+        token_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询重置令牌失败: {e}")))?
+    .ok_or_else(invalid)?;
+
+    let ok = crate::password::verify_password(secret, &row.secret_hash)
+        .map_err(|e| AppError::InternalError(format!("重置令牌校验失败: {e}")))?;
+    if !ok {
+        return Err(invalid());
+    }
+
+    sqlx::query!(
+        "UPDATE password_reset_tokens SET consumed_at = NOW() WHERE id = $1",
+        token_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("标记重置令牌已使用失败: {e}")))?;
+
+    crate::services::user_passwords::set_password_and_revoke_sessions(
+        &mut tx,
+        row.user_id,
+        &hash,
+        false,
+        "password_reset",
+        &client,
+    )
+    .await?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::PASSWORD_RESET_COMPLETED,
+            user_id: Some(row.user_id),
+            session_id: None,
+            client: &client,
+            details: serde_json::json!({ "reset_token_id": token_id }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交重置密码事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpStatusResponse {
+    /// 是否已启用（完成绑定确认）。
+    pub enabled: bool,
+    /// 是否存在尚未确认的待绑定密钥。
+    pub pending: bool,
+    /// 剩余可用的恢复码数量。
+    pub recovery_codes_remaining: i64,
+    /// 当前策略是否要求该用户必须启用两步验证。
+    pub required: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct StartTotpEnrollmentRequest {
+    #[schema(format = "password", min_length = 1, max_length = 256)]
+    #[garde(length(min = 1, max = 256))]
+    pub current_password: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 编码的 TOTP 密钥，供无法扫码时手动输入。
+    pub secret: String,
+    /// `otpauth://` URI，前端可渲染为二维码。
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct TotpCodeRequest {
+    /// 6 位 TOTP 动态码。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct DisableTotpRequest {
+    #[schema(format = "password", min_length = 1, max_length = 256)]
+    #[garde(length(min = 1, max = 256))]
+    pub current_password: String,
+
+    /// 6 位 TOTP 动态码，或一次性恢复码。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TotpRecoveryCodesResponse {
+    /// 一次性恢复码，仅在本次响应中返回明文。
+    pub recovery_codes: Vec<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/totp",
+    tag = "security",
+    responses(
+        (status = 200, description = "获取当前用户两步验证状态", body = TotpStatusResponse),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_totp_status_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+) -> Result<Json<TotpStatusResponse>, AppError> {
+    let cfg = state.config.load_full();
+
+    let row = sqlx::query!(
+        r#"
+SELECT
+    EXISTS (
+        SELECT 1 FROM user_totp WHERE user_id = $1 AND confirmed_at IS NOT NULL
+    ) AS "enabled!",
+    EXISTS (
+        SELECT 1 FROM user_totp WHERE user_id = $1 AND confirmed_at IS NULL
+    ) AS "pending!",
+    (
+        SELECT COUNT(*) FROM user_totp_recovery_codes WHERE user_id = $1 AND used_at IS NULL
+    ) AS "recovery_codes_remaining!"
+        "#,
+        current_user.user_id,
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询两步验证状态失败: {e}")))?;
+
+    Ok(Json(TotpStatusResponse {
+        enabled: row.enabled,
+        pending: row.pending,
+        recovery_codes_remaining: row.recovery_codes_remaining,
+        required: cfg.security.mfa_required_for_admin && current_user.role == "admin",
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/totp",
+    tag = "security",
+    request_body = StartTotpEnrollmentRequest,
+    responses(
+        (status = 200, description = "生成待确认的 TOTP 密钥", body = TotpEnrollmentResponse),
+        (status = 400, description = "请求参数错误 / 已启用两步验证", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效 / 当前密码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn start_totp_enrollment_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        StartTotpEnrollmentRequest,
+    >,
+) -> Result<Json<TotpEnrollmentResponse>, AppError> {
+    let cfg = state.config.load_full();
+    verify_current_password(&state, current_user.user_id, &payload.current_password).await?;
+
+    let account = sqlx::query_scalar!(
+        r#"
+SELECT COALESCE(username, email) AS "account!"
+FROM users
 WHERE id = $1
+LIMIT 1
+        "#,
+        current_user.user_id,
+    )
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询当前用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("当前用户不存在: {}", current_user.user_id)))?;
+
+    let secret = crate::totp::generate_secret();
+    let secret_ciphertext =
+        crate::crypto::encrypt_to_hex(&cfg.security.data_encryption_key, &secret)
+            .map_err(|e| AppError::InternalError(format!("加密 TOTP 密钥失败: {e}")))?;
+
+    // 已启用时不覆盖，必须先停用；未确认的旧密钥直接替换。
+    let upserted = sqlx::query!(
+        r#"
+INSERT INTO user_totp (user_id, secret_ciphertext)
+VALUES ($1, $2)
+ON CONFLICT (user_id) DO UPDATE
+SET secret_ciphertext = EXCLUDED.secret_ciphertext,
+    last_used_step = NULL,
+    created_at = NOW(),
+    updated_at = NOW()
+WHERE user_totp.confirmed_at IS NULL
+        "#,
+        current_user.user_id,
+        secret_ciphertext,
+    )
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("保存 TOTP 密钥失败: {e}")))?;
+    if upserted.rows_affected() == 0 {
+        return Err(AppError::validation("已启用两步验证，如需更换请先停用"));
+    }
+
+    Ok(Json(TotpEnrollmentResponse {
+        secret: crate::totp::base32_encode(&secret),
+        otpauth_uri: crate::totp::otpauth_uri(TOTP_ISSUER, &account, &secret),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/totp/confirm",
+    tag = "security",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "确认绑定并启用两步验证，返回恢复码", body = TotpRecoveryCodesResponse),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效 / 动态码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "不存在待确认的 TOTP 密钥", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_totp_enrollment_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        TotpCodeRequest,
+    >,
+) -> Result<Json<TotpRecoveryCodesResponse>, AppError> {
+    let cfg = state.config.load_full();
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启两步验证绑定事务失败: {e}")))?;
+
+    let pending = sqlx::query_scalar!(
+        r#"
+SELECT user_id
+FROM user_totp
+WHERE user_id = $1
+  AND confirmed_at IS NULL
+FOR UPDATE
+        "#,
+        current_user.user_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询待确认 TOTP 密钥失败: {e}")))?;
+    if pending.is_none() {
+        return Err(AppError::NotFound("不存在待确认的 TOTP 密钥".to_string()));
+    }
+
+    let ok = crate::services::mfa::verify_totp_code(
+        &mut tx,
+        &cfg.security.data_encryption_key,
+        current_user.user_id,
+        &payload.code,
+        false,
+    )
+    .await?;
+    if !ok {
+        return Err(AppError::auth_credential("动态码错误"));
+    }
+
+    sqlx::query!(
+        r#"
+UPDATE user_totp
+SET confirmed_at = NOW(),
+    updated_at = NOW()
+WHERE user_id = $1
         "#,
         current_user.user_id,
-        hash,
     )
     .execute(&mut *tx)
     .await
-    .map_err(|e| AppError::InternalError(format!("更新当前用户密码失败: {e}")))?;
-    if updated.rows_affected() == 0 {
-        return Err(AppError::NotFound(format!(
-            "当前用户不存在: {}",
-            current_user.user_id
-        )));
+    .map_err(|e| AppError::InternalError(format!("启用两步验证失败: {e}")))?;
+
+    let recovery_codes =
+        crate::services::mfa::regenerate_recovery_codes(&mut tx, current_user.user_id).await?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::MFA_ENABLED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({ "method": "totp" }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交两步验证绑定事务失败: {e}")))?;
+
+    Ok(Json(TotpRecoveryCodesResponse { recovery_codes }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/security/totp",
+    tag = "security",
+    request_body = DisableTotpRequest,
+    responses(
+        (status = 204, description = "已停用两步验证（无 body）"),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效 / 当前密码或动态码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "策略要求 admin 必须启用两步验证", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "未启用两步验证", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_totp_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        DisableTotpRequest,
+    >,
+) -> Result<StatusCode, AppError> {
+    let cfg = state.config.load_full();
+    if cfg.security.mfa_required_for_admin && current_user.role == "admin" {
+        return Err(AppError::PermissionDenied(
+            "当前策略要求管理员必须启用两步验证".to_string(),
+        ));
+    }
+    if !crate::services::mfa::is_totp_enabled(&state.db, current_user.user_id).await? {
+        return Err(AppError::NotFound("未启用两步验证".to_string()));
+    }
+
+    verify_current_password(&state, current_user.user_id, &payload.current_password).await?;
+    let factor = crate::services::mfa::verify_second_factor(
+        &state.db,
+        &cfg.security.data_encryption_key,
+        current_user.user_id,
+        &payload.code,
+    )
+    .await?;
+    if factor.is_none() {
+        return Err(AppError::auth_credential("动态码错误"));
     }
 
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启停用两步验证事务失败: {e}")))?;
+
     sqlx::query!(
+        "DELETE FROM user_totp_recovery_codes WHERE user_id = $1",
+        current_user.user_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("删除恢复码失败: {e}")))?;
+
+    sqlx::query!(
+        "DELETE FROM user_totp WHERE user_id = $1",
+        current_user.user_id
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("删除 TOTP 密钥失败: {e}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::MFA_DISABLED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({ "method": "totp" }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交停用两步验证事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/totp/recovery-codes",
+    tag = "security",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "重新生成恢复码（旧恢复码全部作废）", body = TotpRecoveryCodesResponse),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效 / 动态码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn regenerate_totp_recovery_codes_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        TotpCodeRequest,
+    >,
+) -> Result<Json<TotpRecoveryCodesResponse>, AppError> {
+    let cfg = state.config.load_full();
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启恢复码事务失败: {e}")))?;
+
+    let ok = crate::services::mfa::verify_totp_code(
+        &mut tx,
+        &cfg.security.data_encryption_key,
+        current_user.user_id,
+        &payload.code,
+        true,
+    )
+    .await?;
+    if !ok {
+        return Err(AppError::auth_credential("动态码错误"));
+    }
+
+    let recovery_codes =
+        crate::services::mfa::regenerate_recovery_codes(&mut tx, current_user.user_id).await?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::MFA_RECOVERY_CODES_REGENERATED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({ "count": recovery_codes.len() }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交恢复码事务失败: {e}")))?;
+
+    Ok(Json(TotpRecoveryCodesResponse { recovery_codes }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JwtKeyResponse {
+    pub kid: String,
+    pub algorithm: String,
+    /// 是否为当前签名密钥。
+    pub is_active: bool,
+    /// 退役密钥的校验截止时间；过期后使用该密钥签发的 token 全部失效。
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct RotateJwtKeyRequest {
+    /// 旧签名密钥的校验宽限期（秒），默认 3600；传 0 表示立即作废旧密钥签发的全部 access token。
+    #[schema(minimum = 0, maximum = 2592000)]
+    #[garde(inner(range(max = MAX_JWT_KEY_GRACE_PERIOD_SECS)))]
+    pub grace_period_secs: Option<u64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/jwt-keys",
+    tag = "security",
+    responses(
+        (status = 200, description = "获取 JWT 签名密钥列表（不含密钥材料）", body = [JwtKeyResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `jwt_keys:read`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_jwt_keys_handler(
+    _: RequirePermission<JwtKeysRead>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<JwtKeyResponse>>, AppError> {
+    Ok(Json(list_jwt_keys(&state.db).await?))
+}
+
+async fn list_jwt_keys(db: &crate::db::DbPool) -> Result<Vec<JwtKeyResponse>, AppError> {
+    let rows = sqlx::query!(
         r#"
-UPDATE auth_sessions
+SELECT kid, algorithm, is_active, expires_at, created_at
+FROM jwt_signing_keys
+ORDER BY created_at DESC
+        "#
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询 JWT 签名密钥失败: {e}")))?;
+
+    if rows.is_empty() {
+        return Ok(vec![JwtKeyResponse {
+            kid: crate::services::jwt_keys::LEGACY_KID.to_string(),
+            algorithm: "HS256".to_string(),
+            is_active: true,
+            expires_at: None,
+            created_at: None,
+        }]);
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| JwtKeyResponse {
+            kid: row.kid,
+            algorithm: row.algorithm,
+            is_active: row.is_active,
+            expires_at: row.expires_at,
+            created_at: Some(row.created_at),
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/jwt-keys/rotate",
+    tag = "security",
+    request_body = RotateJwtKeyRequest,
+    responses(
+        (status = 200, description = "轮换完成，返回最新密钥列表", body = [JwtKeyResponse]),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `jwt_keys:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn rotate_jwt_key_handler(
+    RequirePermission(current_user, _): RequirePermission<JwtKeysWrite>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        RotateJwtKeyRequest,
+    >,
+) -> Result<Json<Vec<JwtKeyResponse>>, AppError> {
+    let cfg = state.config.load_full();
+    let grace_period_secs = payload
+        .grace_period_secs
+        .unwrap_or(crate::services::jwt_keys::DEFAULT_GRACE_PERIOD_SECS);
+
+    let kid = crate::services::jwt_keys::rotate(
+        &state.db,
+        &cfg.security.jwt_secret,
+        &cfg.security.data_encryption_key,
+        cfg.security.jwt_algorithm,
+        grace_period_secs,
+    )
+    .await?;
+
+    security_events::record(
+        &state.db,
+        SecurityEvent {
+            event_type: security_events::JWT_KEY_ROTATED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "kid": kid,
+                "algorithm": cfg.security.jwt_algorithm.as_str(),
+                "grace_period_secs": grace_period_secs,
+            }),
+        },
+    )
+    .await?;
+
+    state.reload_runtime().await?;
+    Ok(Json(list_jwt_keys(&state.db).await?))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiTokenResponse {
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub scopes: Vec<ApiTokenScope>,
+    /// 绑定的组织；绑定后该 token 始终在此组织上下文中生效。
+    pub organization_id: Option<uuid::Uuid>,
+    /// 为空表示长期有效。
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_used_ip: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiTokenResponse {
+    /// 令牌明文，仅在创建时返回一次；使用方式：`Authorization: Bearer <token>`。
+    pub token: String,
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CreateApiTokenRequest {
+    /// 令牌名称，同一用户下未撤销的令牌不能重名。
+    #[schema(min_length = 1, max_length = 64)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 64))]
+    pub name: String,
+
+    #[schema(min_items = 1)]
+    #[garde(length(min = 1))]
+    pub scopes: Vec<ApiTokenScope>,
+
+    /// 有效期（天），1 ~ 365；不传表示长期有效。
+    #[schema(minimum = 1, maximum = 365)]
+    #[garde(inner(range(min = 1, max = MAX_API_TOKEN_EXPIRES_IN_DAYS)))]
+    pub expires_in_days: Option<u32>,
+
+    /// 绑定到当前用户所属的组织；不传表示平台上下文（可用请求头选择组织）。
+    #[garde(skip)]
+    pub organization_id: Option<uuid::Uuid>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/tokens",
+    tag = "security",
+    responses(
+        (status = 200, description = "获取当前用户未撤销的 API token 列表（不含令牌明文）", body = [ApiTokenResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "API token 不能管理 API token", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_api_tokens_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApiTokenResponse>>, AppError> {
+    let rows = sqlx::query!(
+        r#"
+SELECT id, name, scopes, organization_id, expires_at, last_used_at, last_used_ip, created_at
+FROM api_tokens
+WHERE user_id = $1
+  AND revoked_at IS NULL
+ORDER BY created_at DESC
+        "#,
+        current_user.user_id,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询 API token 失败: {e}")))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| ApiTokenResponse {
+                id: row.id,
+                name: row.name,
+                scopes: api_tokens::parse_scopes(&row.scopes),
+                organization_id: row.organization_id,
+                expires_at: row.expires_at,
+                last_used_at: row.last_used_at,
+                last_used_ip: row.last_used_ip,
+                created_at: row.created_at,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/tokens",
+    tag = "security",
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 201, description = "创建成功，返回令牌明文（仅此一次）", body = CreatedApiTokenResponse),
+        (status = 400, description = "请求参数错误 / 名称重复", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "API token 不能管理 API token / 不是绑定组织的成员", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn create_api_token_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        CreateApiTokenRequest,
+    >,
+) -> Result<(StatusCode, Json<CreatedApiTokenResponse>), AppError> {
+    let mut scopes = payload.scopes;
+    scopes.sort_by_key(|scope| scope.as_str());
+    scopes.dedup();
+    let scope_names: Vec<String> = scopes.iter().map(|s| s.as_str().to_string()).collect();
+    let expires_at = payload
+        .expires_in_days
+        .map(|days| chrono::Utc::now() + chrono::Duration::days(i64::from(days)));
+
+    if let Some(organization_id) = payload.organization_id {
+        if organizations::membership(&state.db, organization_id, current_user.user_id)
+            .await?
+            .is_none()
+        {
+            return Err(AppError::PermissionDenied("不是该组织的成员".to_string()));
+        }
+    }
+
+    let generated = api_tokens::generate()?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启创建 API token 事务失败: {e}")))?;
+
+    let created_at = sqlx::query_scalar!(
+        r#"
+INSERT INTO api_tokens (id, user_id, name, secret_hash, scopes, organization_id, expires_at)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+RETURNING created_at
+        "#,
+        generated.id,
+        current_user.user_id,
+        payload.name,
+        generated.secret_hash,
+        &scope_names,
+        payload.organization_id,
+        expires_at,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+            AppError::validation(format!("API token 名称已存在: {}", payload.name))
+        }
+        _ => AppError::InternalError(format!("创建 API token 失败: {e}")),
+    })?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::API_TOKEN_CREATED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "token_id": generated.id,
+                "name": payload.name,
+                "scopes": scope_names,
+                "organization_id": payload.organization_id,
+                "expires_at": expires_at,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交创建 API token 事务失败: {e}")))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiTokenResponse {
+            token: generated.token,
+            api_token: ApiTokenResponse {
+                id: generated.id,
+                name: payload.name,
+                scopes,
+                organization_id: payload.organization_id,
+                expires_at,
+                last_used_at: None,
+                last_used_ip: None,
+                created_at,
+            },
+        }),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/security/tokens/{token_id}",
+    tag = "security",
+    params(("token_id" = Uuid, Path, description = "API token ID")),
+    responses(
+        (status = 204, description = "撤销成功（无 body），该令牌立即失效"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "API token 不能管理 API token", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "令牌不存在或已撤销", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_api_token_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(token_id): Path<uuid::Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启撤销 API token 事务失败: {e}")))?;
+
+    let name = sqlx::query_scalar!(
+        r#"
+UPDATE api_tokens
 SET revoked_at = NOW(),
-    revoked_reason = 'password_changed',
     updated_at = NOW()
-WHERE user_id = $1
+WHERE id = $1
+  AND user_id = $2
   AND revoked_at IS NULL
+RETURNING name
         "#,
+        token_id,
         current_user.user_id,
     )
-    .execute(&mut *tx)
+    .fetch_optional(&mut *tx)
     .await
-    .map_err(|e| AppError::InternalError(format!("撤销用户会话失败: {e}")))?;
+    .map_err(|e| AppError::InternalError(format!("撤销 API token 失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("API token 不存在或已撤销: {token_id}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::API_TOKEN_REVOKED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({ "token_id": token_id, "name": name }),
+        },
+    )
+    .await?;
 
     tx.commit()
         .await
-        .map_err(|e| AppError::InternalError(format!("提交改密事务失败: {e}")))?;
+        .map_err(|e| AppError::InternalError(format!("提交撤销 API token 事务失败: {e}")))?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+async fn verify_current_password(
+    state: &AppState,
+    user_id: uuid::Uuid,
+    password: &str,
+) -> Result<(), AppError> {
+    let current_hash = load_current_user_password_hash(state, user_id).await?;
+    let ok = crate::password::verify_password(password, &current_hash)
+        .map_err(|e| AppError::InternalError(format!("当前用户密码校验失败: {e}")))?;
+    if !ok {
+        return Err(AppError::auth_credential("密码错误"));
+    }
+    Ok(())
+}
+
 async fn load_current_user_password_hash(
     state: &AppState,
     user_id: uuid::Uuid,
@@ -1,24 +1,38 @@
-use axum::extract::State;
+use axum::extract::{Extension, Path, State};
 use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
 use axum::response::{AppendHeaders, IntoResponse};
 use axum::Json;
 use chrono::{DateTime, Duration, Utc};
 use garde::Validate;
-use jsonwebtoken::{encode, EncodingKey, Header};
+use jsonwebtoken::encode;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::api::auth::Claims;
 use crate::api::auth::CurrentUser;
+use crate::api::auth::SessionRestriction;
+use crate::api::auth::{ActorClaim, Claims};
+use crate::api::client_info::ClientInfo;
+use crate::api::permissions::{RequirePermission, UsersImpersonate};
+use crate::config::runtime::{RuntimeConfig, SessionTokenConfig};
 use crate::error::AppError;
 use crate::http::router::AppState;
+use crate::services::impersonation::{self, RevokedSession};
+use crate::services::jwt_keys::{JwtKey, PublicJwk};
+use crate::services::login_throttle;
+use crate::services::mfa::SecondFactor;
+use crate::services::rbac;
+use crate::services::security_events::{self, SecurityEvent};
 
-const ACCESS_TOKEN_EXPIRES_IN_SECS: u64 = 15 * 60;
-const REFRESH_TOKEN_EXPIRES_IN_SECS: i64 = 30 * 24 * 60 * 60;
 const REFRESH_TOKEN_COOKIE_NAME: &str = "refresh_token";
-const REFRESH_TOKEN_COOKIE_PATH: &str = "/api/v1/sessions";
+const MFA_CHALLENGE_EXPIRES_IN_SECS: u64 = 5 * 60;
+/// 签发 token 前，本地 JWT 密钥环的最大缓存时长（多实例下感知其他实例的密钥轮换）。
+const JWT_KEYRING_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(60);
+const JWKS_CACHE_CONTROL: &str = "public, max-age=300";
+const MFA_CHALLENGE_MAX_FAILED_ATTEMPTS: i32 = 5;
+/// 模拟登录会话的固定有效期，不受 access token 有效期配置影响，且不可刷新。
+const IMPERSONATION_TTL_SECS: u64 = 15 * 60;
 
 type SessionIssueResponse = (
     AppendHeaders<[(header::HeaderName, HeaderValue); 1]>,
@@ -26,7 +40,7 @@ type SessionIssueResponse = (
 );
 
 struct SessionIssueContext {
-    jwt_secret: String,
+    signing_key: JwtKey,
     user_id: Uuid,
     auth_version: i32,
     session_id: Uuid,
@@ -34,7 +48,20 @@ struct SessionIssueContext {
     role: String,
     display_name: String,
     email: String,
+    refresh_generation: i32,
     refresh_secret: String,
+    restriction: Option<SessionRestriction>,
+}
+
+/// 即将签发会话的用户信息（密码登录与 MFA 挑战完成共用）。
+struct SessionSubject {
+    user_id: Uuid,
+    auth_version: i32,
+    username: Option<String>,
+    role: String,
+    display_name: String,
+    email: String,
+    password_must_change: bool,
 }
 
 #[derive(Debug, Deserialize, ToSchema, Validate)]
@@ -56,6 +83,70 @@ pub struct CreateSessionResponse {
     pub expires_in: u64,
 }
 
+/// 账号已启用两步验证时，密码校验通过后返回的 MFA 挑战。
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MfaChallengeResponse {
+    /// 调用 `POST /api/v1/sessions/mfa` 时原样提交。
+    pub mfa_token: String,
+    pub expires_in: u64,
+    /// 可用的第二因素：`totp`、`recovery_code`。
+    pub methods: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct CompleteMfaChallengeRequest {
+    #[schema(min_length = 1, max_length = 256)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 256))]
+    pub mfa_token: String,
+
+    /// 6 位 TOTP 动态码，或一次性恢复码。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 32))]
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    /// 是否为当前 access token 所属会话。
+    pub is_current: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// 登录时的客户端 IP。
+    pub created_ip: Option<String>,
+    /// 登录时的 User-Agent。
+    pub created_user_agent: Option<String>,
+    /// 最近一次登录/刷新时的客户端 IP。
+    pub last_ip: Option<String>,
+    /// 最近一次登录/刷新时的 User-Agent。
+    pub last_user_agent: Option<String>,
+    /// 最近一次刷新时间；从未刷新过时为 `null`。
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    /// 模拟登录会话的操作者；用户本人登录时为 `null`。
+    pub impersonator_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct StartImpersonationRequest {
+    #[garde(skip)]
+    pub user_id: Uuid,
+
+    /// 模拟登录的原因（如工单号），写入审计事件。
+    #[schema(min_length = 1, max_length = 256)]
+    #[serde(deserialize_with = "crate::api::serde_helpers::deserialize_trimmed_string")]
+    #[garde(length(min = 1, max = 256))]
+    pub reason: String,
+}
+
+/// JWK Set（RFC 7517），供其他服务校验本服务签发的 access token。
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JwksResponse {
+    pub keys: Vec<PublicJwk>,
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/sessions",
@@ -63,84 +154,210 @@ pub struct CreateSessionResponse {
     request_body = CreateSessionRequest,
     responses(
         (status = 200, description = "登录成功", body = CreateSessionResponse),
+        (status = 202, description = "密码正确，但账号已启用两步验证，需完成 MFA 挑战", body = MfaChallengeResponse),
         (status = 401, description = "未授权（用户名或密码错误）", body = crate::api::openapi::ErrorResponseBody),
         (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 429, description = "登录失败次数过多或账号已临时锁定（见 Retry-After）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     )
 )]
 pub async fn create_session_handler(
     State(state): State<AppState>,
+    client: ClientInfo,
     crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
         CreateSessionRequest,
     >,
 ) -> Result<impl IntoResponse, AppError> {
     let cfg = state.config.load_full();
+    let identifier = login_throttle::normalize_identifier(&payload.identifier);
+    login_throttle::ensure_not_throttled(
+        &state.db,
+        &cfg.security.login_throttle,
+        &identifier,
+        &client,
+    )
+    .await?;
 
-    let user = load_login_user(&state, &payload.identifier).await?;
-    if !user.is_active {
-        return Err(AppError::auth_credential("用户名或密码错误"));
-    }
-    let Some(password_hash) = user.password_hash.as_deref() else {
+    let Some(user) = load_login_user(&state, &payload.identifier).await? else {
+        login_throttle::record_failure(
+            &state.db,
+            &cfg.security.login_throttle,
+            &identifier,
+            None,
+            &client,
+        )
+        .await?;
         return Err(AppError::auth_credential("用户名或密码错误"));
     };
-    if password_hash.trim().is_empty() {
+    login_throttle::ensure_account_not_locked(user.locked_until)?;
+
+    if !verify_login_password(&user, &payload.password)? {
+        login_throttle::record_failure(
+            &state.db,
+            &cfg.security.login_throttle,
+            &identifier,
+            Some(user.id),
+            &client,
+        )
+        .await?;
         return Err(AppError::auth_credential("用户名或密码错误"));
     }
 
-    let ok = crate::password::verify_password(&payload.password, password_hash)
-        .map_err(|e| AppError::InternalError(format!("用户密码校验失败: {e}")))?;
+    let mfa_enabled = crate::services::mfa::is_totp_enabled(&state.db, user.id).await?;
+    if mfa_enabled {
+        let challenge = create_mfa_challenge(&state, user.id, &identifier, &client).await?;
+        return Ok((StatusCode::ACCEPTED, Json(challenge)).into_response());
+    }
+
+    login_throttle::clear_identifier(&state.db, &identifier).await?;
+
+    let response = issue_session(
+        &state,
+        SessionSubject {
+            user_id: user.id,
+            auth_version: user.auth_version,
+            username: user.username,
+            role: user.role,
+            display_name: user.display_name,
+            email: user.email,
+            password_must_change: user.password_must_change,
+        },
+        mfa_enabled,
+        &client,
+    )
+    .await?;
+
+    Ok(response.into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions/mfa",
+    tag = "sessions",
+    request_body = CompleteMfaChallengeRequest,
+    responses(
+        (status = 200, description = "两步验证通过，登录成功", body = CreateSessionResponse),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "MFA 挑战无效或已过期 / 动态码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 429, description = "登录失败次数过多或账号已临时锁定（见 Retry-After）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    )
+)]
+pub async fn complete_mfa_challenge_handler(
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        CompleteMfaChallengeRequest,
+    >,
+) -> Result<impl IntoResponse, AppError> {
+    let cfg = state.config.load_full();
+    let (challenge_id, challenge_secret) = parse_mfa_token(&payload.mfa_token)?;
+
+    let challenge = load_mfa_challenge(&state, challenge_id).await?;
+    if challenge.consumed_at.is_some()
+        || challenge.expires_at <= Utc::now()
+        || challenge.failed_attempts >= MFA_CHALLENGE_MAX_FAILED_ATTEMPTS
+        || !challenge.user_is_active
+    {
+        return Err(AppError::auth_token("MFA 挑战无效或已过期"));
+    }
+
+    let ok = crate::password::verify_password(&challenge_secret, &challenge.secret_hash)
+        .map_err(|e| AppError::InternalError(format!("MFA 挑战校验失败: {e}")))?;
     if !ok {
-        return Err(AppError::auth_credential("用户名或密码错误"));
+        return Err(AppError::auth_token("MFA 挑战无效或已过期"));
     }
 
-    let mut tx = state
-        .db
-        .begin()
-        .await
-        .map_err(|e| AppError::InternalError(format!("开启登录事务失败: {e}")))?;
+    let identifier = challenge
+        .login_identifier
+        .clone()
+        .unwrap_or_else(|| login_throttle::normalize_identifier(&challenge.email));
+    login_throttle::ensure_not_throttled(
+        &state.db,
+        &cfg.security.login_throttle,
+        &identifier,
+        &client,
+    )
+    .await?;
+    login_throttle::ensure_account_not_locked(challenge.locked_until)?;
 
-    let session_id = Uuid::new_v4();
-    let refresh_secret = generate_random_hex(32)?;
-    let refresh_secret_hash = crate::password::hash_password_argon2id(&refresh_secret)
-        .map_err(|e| AppError::InternalError(format!("refresh token 哈希失败: {e}")))?;
-    let refresh_expires_at = Utc::now() + Duration::seconds(REFRESH_TOKEN_EXPIRES_IN_SECS);
+    let factor = crate::services::mfa::verify_second_factor(
+        &state.db,
+        &cfg.security.data_encryption_key,
+        challenge.user_id,
+        &payload.code,
+    )
+    .await?;
+    let Some(factor) = factor else {
+        sqlx::query!(
+            r#"
+UPDATE auth_mfa_challenges
+SET failed_attempts = failed_attempts + 1
+WHERE id = $1
+            "#,
+            challenge_id,
+        )
+        .execute(&state.db)
+        .await
+        .map_err(|e| AppError::InternalError(format!("记录 MFA 失败次数失败: {e}")))?;
+        login_throttle::record_failure(
+            &state.db,
+            &cfg.security.login_throttle,
+            &identifier,
+            Some(challenge.user_id),
+            &client,
+        )
+        .await?;
+        return Err(AppError::auth_credential("动态码错误"));
+    };
 
-    sqlx::query!(
+    let consumed = sqlx::query!(
         r#"
-INSERT INTO auth_sessions (
-    id,
-    user_id,
-    refresh_secret_hash,
-    expires_at,
-    revoked_at,
-    revoked_reason
-)
-VALUES ($1, $2, $3, $4, NULL, NULL)
+UPDATE auth_mfa_challenges
+SET consumed_at = NOW()
+WHERE id = $1
+  AND consumed_at IS NULL
         "#,
-        session_id,
-        user.id,
-        refresh_secret_hash,
-        refresh_expires_at,
+        challenge_id,
     )
-    .execute(&mut *tx)
+    .execute(&state.db)
     .await
-    .map_err(|e| AppError::InternalError(format!("创建会话失败: {e}")))?;
+    .map_err(|e| AppError::InternalError(format!("消费 MFA 挑战失败: {e}")))?;
+    if consumed.rows_affected() == 0 {
+        return Err(AppError::auth_token("MFA 挑战无效或已过期"));
+    }
 
-    tx.commit()
-        .await
-        .map_err(|e| AppError::InternalError(format!("提交登录事务失败: {e}")))?;
+    login_throttle::clear_identifier(&state.db, &identifier).await?;
 
-    let response = build_login_or_refresh_response(SessionIssueContext {
-        jwt_secret: cfg.security.jwt_secret.clone(),
-        user_id: user.id,
-        auth_version: user.auth_version,
-        session_id,
-        username: user.username,
-        role: user.role,
-        display_name: user.display_name,
-        email: user.email,
-        refresh_secret,
-    })?;
+    if factor == SecondFactor::RecoveryCode {
+        security_events::record(
+            &state.db,
+            SecurityEvent {
+                event_type: security_events::MFA_RECOVERY_CODE_USED,
+                user_id: Some(challenge.user_id),
+                session_id: None,
+                client: &client,
+                details: serde_json::json!({ "challenge_id": challenge_id }),
+            },
+        )
+        .await?;
+    }
+
+    let response = issue_session(
+        &state,
+        SessionSubject {
+            user_id: challenge.user_id,
+            auth_version: challenge.auth_version,
+            username: challenge.username,
+            role: challenge.role,
+            display_name: challenge.display_name,
+            email: challenge.email,
+            password_must_change: challenge.password_must_change,
+        },
+        true,
+        &client,
+    )
+    .await?;
 
     Ok(response)
 }
@@ -157,11 +374,15 @@ VALUES ($1, $2, $3, $4, NULL, NULL)
 )]
 pub async fn refresh_session_handler(
     State(state): State<AppState>,
+    client: ClientInfo,
     headers: HeaderMap,
 ) -> Result<impl IntoResponse, AppError> {
-    let cfg = state.config.load_full();
+    let cfg = state
+        .runtime_with_fresh_jwt_keys(JWT_KEYRING_MAX_AGE)
+        .await?;
     let refresh_token = extract_refresh_token_from_headers(&headers)?;
-    let (session_id, refresh_secret) = parse_refresh_token(&refresh_token)?;
+    let refresh_token = parse_refresh_token(&refresh_token)?;
+    let session_id = refresh_token.session_id;
 
     let session = load_auth_session(&state, session_id).await?;
     let now = Utc::now();
@@ -169,12 +390,18 @@ pub async fn refresh_session_handler(
         return Err(AppError::auth_token("Token 无效或已过期"));
     }
 
-    let ok = crate::password::verify_password(&refresh_secret, &session.refresh_secret_hash)
-        .map_err(|e| AppError::InternalError(format!("refresh token 校验失败: {e}")))?;
+    let ok = refresh_token.generation == session.refresh_generation
+        && crate::password::verify_password(&refresh_token.secret, &session.refresh_secret_hash)
+            .map_err(|e| AppError::InternalError(format!("refresh token 校验失败: {e}")))?;
     if !ok {
+        if is_rotated_refresh_secret(&state, &refresh_token).await? {
+            revoke_session_for_refresh_reuse(&state, session_id, &session, &client).await?;
+        }
         return Err(AppError::auth_token("Token 无效或已过期"));
     }
 
+    let mfa_enabled = crate::services::mfa::is_totp_enabled(&state.db, session.user_id).await?;
+
     let mut tx = state
         .db
         .begin()
@@ -184,48 +411,99 @@ pub async fn refresh_session_handler(
     let next_refresh_secret = generate_random_hex(32)?;
     let next_refresh_secret_hash = crate::password::hash_password_argon2id(&next_refresh_secret)
         .map_err(|e| AppError::InternalError(format!("refresh token 哈希失败: {e}")))?;
-    let next_refresh_expires_at = now + Duration::seconds(REFRESH_TOKEN_EXPIRES_IN_SECS);
+    let next_refresh_expires_at =
+        now + Duration::seconds(cfg.security.session.refresh_token_ttl_secs as i64);
 
-    let updated = sqlx::query!(
+    let next_refresh_generation = sqlx::query_scalar!(
         r#"
 UPDATE auth_sessions
 SET refresh_secret_hash = $2,
+    refresh_generation = refresh_generation + 1,
     expires_at = $3,
+    last_ip = $5,
+    last_user_agent = $6,
+    last_refreshed_at = NOW(),
     updated_at = NOW()
 WHERE id = $1
   AND revoked_at IS NULL
-  AND refresh_secret_hash = $4
+  AND refresh_generation = $4
+RETURNING refresh_generation
         "#,
         session_id,
         next_refresh_secret_hash,
         next_refresh_expires_at,
+        session.refresh_generation,
+        client.ip,
+        client.user_agent,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("轮换会话失败: {e}")))?
+    .ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO auth_session_rotated_secrets (session_id, generation, secret_hash)
+VALUES ($1, $2, $3)
+        "#,
+        session_id,
+        session.refresh_generation,
         session.refresh_secret_hash,
     )
     .execute(&mut *tx)
     .await
-    .map_err(|e| AppError::InternalError(format!("轮换会话失败: {e}")))?;
-    if updated.rows_affected() == 0 {
-        return Err(AppError::auth_token("Token 无效或已过期"));
-    }
+    .map_err(|e| AppError::InternalError(format!("记录已轮换 refresh secret 失败: {e}")))?;
 
     tx.commit()
         .await
         .map_err(|e| AppError::InternalError(format!("提交刷新事务失败: {e}")))?;
 
-    let response = build_login_or_refresh_response(SessionIssueContext {
-        jwt_secret: cfg.security.jwt_secret.clone(),
-        user_id: session.user_id,
-        auth_version: session.auth_version,
-        session_id,
-        username: session.username,
-        role: session.role,
-        display_name: session.display_name,
-        email: session.email,
-        refresh_secret: next_refresh_secret,
-    })?;
+    let restriction = session_restriction_for(
+        &cfg,
+        &session.role,
+        mfa_enabled,
+        session.password_must_change,
+    );
+    let response = build_login_or_refresh_response(
+        &cfg.security.session,
+        SessionIssueContext {
+            signing_key: cfg.security.jwt_keys.signing_key().clone(),
+            restriction,
+            user_id: session.user_id,
+            auth_version: session.auth_version,
+            session_id,
+            username: session.username,
+            role: session.role,
+            display_name: session.display_name,
+            email: session.email,
+            refresh_generation: next_refresh_generation,
+            refresh_secret: next_refresh_secret,
+        },
+    )?;
     Ok(response)
 }
 
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "sessions",
+    responses(
+        (status = 200, description = "当前仍可用于校验 access token 的非对称公钥（HS256 密钥不公开，使用 HS256 时为空集合）", body = JwksResponse),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    )
+)]
+pub async fn jwks_handler(State(state): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let cfg = state
+        .runtime_with_fresh_jwt_keys(JWT_KEYRING_MAX_AGE)
+        .await?;
+    let keys = cfg.security.jwt_keys.public_jwks();
+
+    Ok((
+        [(header::CACHE_CONTROL, JWKS_CACHE_CONTROL)],
+        Json(JwksResponse { keys }),
+    ))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/v1/sessions/current",
@@ -238,10 +516,18 @@ WHERE id = $1
     security(("bearer_auth" = []))
 )]
 pub async fn delete_current_session_handler(
-    axum::extract::Extension(current_user): axum::extract::Extension<CurrentUser>,
+    Extension(current_user): Extension<CurrentUser>,
     State(state): State<AppState>,
+    client: ClientInfo,
 ) -> Result<impl IntoResponse, AppError> {
-    sqlx::query!(
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启退出会话事务失败: {e}")))?;
+
+    let revoked = sqlx::query_as!(
+        RevokedSession,
         r#"
 UPDATE auth_sessions
 SET revoked_at = NOW(),
@@ -250,24 +536,586 @@ SET revoked_at = NOW(),
 WHERE id = $1
   AND user_id = $2
   AND revoked_at IS NULL
+RETURNING id, user_id, impersonator_id
         "#,
         current_user.session_id,
         current_user.user_id,
     )
-    .execute(&state.db)
+    .fetch_all(&mut *tx)
     .await
     .map_err(|e| AppError::InternalError(format!("退出当前会话失败: {e}")))?;
+    impersonation::record_ended(&mut tx, &revoked, &client, "manual_logout").await?;
 
-    let clear_cookie = build_clear_refresh_cookie_value();
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交退出会话事务失败: {e}")))?;
+
+    // 模拟登录会话没有 refresh cookie，浏览器中的 cookie 属于操作者本人的会话，不能清除。
+    if current_user.impersonator_id.is_some() {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+    let cfg = state.config.load_full();
+    let clear_cookie = build_clear_refresh_cookie_value(&cfg.security.session);
     let headers = build_set_cookie_headers(clear_cookie)?;
-    Ok((StatusCode::NO_CONTENT, headers))
+    Ok((StatusCode::NO_CONTENT, headers).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/sessions/impersonation",
+    tag = "sessions",
+    request_body = StartImpersonationRequest,
+    responses(
+        (status = 200, description = "以目标用户身份签发短期 access token（不下发 refresh cookie，不可刷新）", body = CreateSessionResponse),
+        (status = 400, description = "请求参数错误（不能模拟自己、用户已停用等）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:impersonate`，且目标用户的权限不能超出自身）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn start_impersonation_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersImpersonate>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        StartImpersonationRequest,
+    >,
+) -> Result<Json<CreateSessionResponse>, AppError> {
+    if payload.user_id == current_user.user_id {
+        return Err(AppError::validation("不能模拟登录自己"));
+    }
+    rbac::ensure_user_manageable(&state.db, &current_user, payload.user_id).await?;
+
+    let target = sqlx::query_as!(
+        ImpersonationTargetRow,
+        r#"
+SELECT
+    u.id AS user_id,
+    u.username,
+    u.role,
+    u.display_name,
+    u.email,
+    u.is_active,
+    u.auth_version,
+    a.username AS "actor_username?"
+FROM users u
+LEFT JOIN users a ON a.id = $2
+WHERE u.id = $1
+  AND u.deleted_at IS NULL
+        "#,
+        payload.user_id,
+        current_user.user_id,
+    )
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询模拟登录用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {}", payload.user_id)))?;
+    if !target.is_active {
+        return Err(AppError::validation("用户已停用，无法模拟登录"));
+    }
+
+    let cfg = state
+        .runtime_with_fresh_jwt_keys(JWT_KEYRING_MAX_AGE)
+        .await?;
+    let session_id = Uuid::new_v4();
+    // refresh secret 不下发给任何客户端，会话只能凭短期 access token 使用。
+    let refresh_secret_hash = crate::password::hash_password_argon2id(&generate_random_hex(32)?)
+        .map_err(|e| AppError::InternalError(format!("refresh token 哈希失败: {e}")))?;
+    let now = Utc::now();
+    let expires_at = now + Duration::seconds(IMPERSONATION_TTL_SECS as i64);
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启模拟登录事务失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+INSERT INTO auth_sessions (
+    id,
+    user_id,
+    refresh_secret_hash,
+    expires_at,
+    created_ip,
+    created_user_agent,
+    last_ip,
+    last_user_agent,
+    impersonator_id
+)
+VALUES ($1, $2, $3, $4, $5, $6, $5, $6, $7)
+        "#,
+        session_id,
+        target.user_id,
+        refresh_secret_hash,
+        expires_at,
+        client.ip,
+        client.user_agent,
+        current_user.user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("创建模拟登录会话失败: {e}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::IMPERSONATION_STARTED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "target_user_id": target.user_id,
+                "impersonation_session_id": session_id,
+                "reason": payload.reason,
+                "expires_at": expires_at,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交模拟登录事务失败: {e}")))?;
+
+    let claims = Claims {
+        exp: expires_at.timestamp() as usize,
+        iat: now.timestamp() as usize,
+        sub: target.user_id.to_string(),
+        sid: session_id.to_string(),
+        ver: target.auth_version,
+        username: target.username,
+        display_name: Some(target.display_name),
+        email: Some(target.email),
+        role: target.role,
+        rst: None,
+        act: Some(ActorClaim {
+            sub: current_user.user_id.to_string(),
+            username: target.actor_username,
+        }),
+    };
+    Ok(Json(CreateSessionResponse {
+        token: sign_access_token(cfg.security.jwt_keys.signing_key(), &claims)?,
+        expires_in: IMPERSONATION_TTL_SECS,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions",
+    tag = "sessions",
+    responses(
+        (status = 200, description = "获取当前用户的有效会话列表", body = [SessionResponse]),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_sessions_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SessionResponse>>, AppError> {
+    let rows = sqlx::query_as!(
+        SessionListRow,
+        r#"
+SELECT
+    id,
+    created_at,
+    updated_at,
+    expires_at,
+    created_ip,
+    created_user_agent,
+    last_ip,
+    last_user_agent,
+    last_refreshed_at,
+    impersonator_id
+FROM auth_sessions
+WHERE user_id = $1
+  AND revoked_at IS NULL
+  AND expires_at > NOW()
+ORDER BY created_at DESC
+        "#,
+        current_user.user_id,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询会话列表失败: {e}")))?;
+
+    Ok(Json(
+        rows.into_iter()
+            .map(|row| SessionResponse {
+                id: row.id,
+                is_current: Some(row.id) == current_user.session_id,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                expires_at: row.expires_at,
+                created_ip: row.created_ip,
+                created_user_agent: row.created_user_agent,
+                last_ip: row.last_ip,
+                last_user_agent: row.last_user_agent,
+                last_refreshed_at: row.last_refreshed_at,
+                impersonator_id: row.impersonator_id,
+            })
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions/{session_id}",
+    tag = "sessions",
+    params(("session_id" = Uuid, Path, description = "会话 ID")),
+    responses(
+        (status = 204, description = "会话已撤销（无 body）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "会话不存在或已撤销", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_session_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    Path(session_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启撤销会话事务失败: {e}")))?;
+
+    let revoked = sqlx::query_as!(
+        RevokedSession,
+        r#"
+UPDATE auth_sessions
+SET revoked_at = NOW(),
+    revoked_reason = COALESCE(revoked_reason, 'manual_revoke'),
+    updated_at = NOW()
+WHERE id = $1
+  AND user_id = $2
+  AND revoked_at IS NULL
+RETURNING id, user_id, impersonator_id
+        "#,
+        session_id,
+        current_user.user_id,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("撤销会话失败: {e}")))?;
+
+    if revoked.is_empty() {
+        return Err(AppError::NotFound(format!(
+            "会话不存在或已撤销: {session_id}"
+        )));
+    }
+    impersonation::record_ended(&mut tx, &revoked, &client, "manual_revoke").await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交撤销会话事务失败: {e}")))?;
+
+    // 撤销的是当前会话时，顺带清理浏览器中的 refresh cookie。
+    let response = if Some(session_id) == current_user.session_id {
+        let cfg = state.config.load_full();
+        let headers =
+            build_set_cookie_headers(build_clear_refresh_cookie_value(&cfg.security.session))?;
+        (StatusCode::NO_CONTENT, headers).into_response()
+    } else {
+        StatusCode::NO_CONTENT.into_response()
+    };
+    Ok(response)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions/others",
+    tag = "sessions",
+    responses(
+        (status = 204, description = "除当前会话外的其他会话均已撤销（无 body）"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_other_sessions_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启撤销其他会话事务失败: {e}")))?;
+
+    let revoked = sqlx::query_as!(
+        RevokedSession,
+        r#"
+UPDATE auth_sessions
+SET revoked_at = NOW(),
+    revoked_reason = COALESCE(revoked_reason, 'logout_other_sessions'),
+    updated_at = NOW()
+WHERE user_id = $1
+  AND id <> $2
+  AND revoked_at IS NULL
+RETURNING id, user_id, impersonator_id
+        "#,
+        current_user.user_id,
+        current_user.session_id,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("撤销其他会话失败: {e}")))?;
+    impersonation::record_ended(&mut tx, &revoked, &client, "logout_other_sessions").await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交撤销其他会话事务失败: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 判断提交的 refresh token 是否为该会话任意一代已被轮换掉的 token。
+///
+/// 只比对 token 所声明代次的哈希（外加升级前遗留的第 -1 代），
+/// 伪造代次但 secret 不匹配的 token 不会触发会话撤销。
+async fn is_rotated_refresh_secret(
+    state: &AppState,
+    refresh_token: &RefreshToken,
+) -> Result<bool, AppError> {
+    let hashes = sqlx::query_scalar!(
+        r#"
+SELECT secret_hash
+FROM auth_session_rotated_secrets
+WHERE session_id = $1
+  AND (generation = $2 OR generation < 0)
+        "#,
+        refresh_token.session_id,
+        refresh_token.generation,
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询已轮换 refresh secret 失败: {e}")))?;
+
+    for hash in hashes {
+        let matched = crate::password::verify_password(&refresh_token.secret, &hash)
+            .map_err(|e| AppError::InternalError(format!("refresh token 校验失败: {e}")))?;
+        if matched {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// 已轮换的 refresh token 被再次使用，视为凭证被盗：撤销整个会话并记录安全事件。
+async fn revoke_session_for_refresh_reuse(
+    state: &AppState,
+    session_id: Uuid,
+    session: &AuthSessionRow,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启会话撤销事务失败: {e}")))?;
+
+    let revoked = sqlx::query!(
+        r#"
+UPDATE auth_sessions
+SET revoked_at = NOW(),
+    revoked_reason = 'refresh_reuse_detected',
+    updated_at = NOW()
+WHERE id = $1
+  AND revoked_at IS NULL
+        "#,
+        session_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("撤销重放会话失败: {e}")))?;
+
+    if revoked.rows_affected() > 0 {
+        security_events::record(
+            &mut *tx,
+            SecurityEvent {
+                event_type: security_events::REFRESH_REUSE_DETECTED,
+                user_id: Some(session.user_id),
+                session_id: Some(session_id),
+                client,
+                details: serde_json::json!({ "action": "session_revoked" }),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交会话撤销事务失败: {e}")))?;
+
+    Ok(())
+}
+
+/// 创建新会话并签发 access token 与 refresh cookie。
+async fn issue_session(
+    state: &AppState,
+    subject: SessionSubject,
+    mfa_enabled: bool,
+    client: &ClientInfo,
+) -> Result<SessionIssueResponse, AppError> {
+    let cfg = state
+        .runtime_with_fresh_jwt_keys(JWT_KEYRING_MAX_AGE)
+        .await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启登录事务失败: {e}")))?;
+
+    let session_id = Uuid::new_v4();
+    let refresh_secret = generate_random_hex(32)?;
+    let refresh_secret_hash = crate::password::hash_password_argon2id(&refresh_secret)
+        .map_err(|e| AppError::InternalError(format!("refresh token 哈希失败: {e}")))?;
+    let refresh_expires_at =
+        Utc::now() + Duration::seconds(cfg.security.session.refresh_token_ttl_secs as i64);
+
+    sqlx::query!(
+        r#"
+INSERT INTO auth_sessions (
+    id,
+    user_id,
+    refresh_secret_hash,
+    expires_at,
+    revoked_at,
+    revoked_reason,
+    created_ip,
+    created_user_agent,
+    last_ip,
+    last_user_agent
+)
+VALUES ($1, $2, $3, $4, NULL, NULL, $5, $6, $5, $6)
+        "#,
+        session_id,
+        subject.user_id,
+        refresh_secret_hash,
+        refresh_expires_at,
+        client.ip,
+        client.user_agent,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("创建会话失败: {e}")))?;
+
+    sqlx::query!(
+        "UPDATE users SET last_login_at = NOW() WHERE id = $1",
+        subject.user_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("记录最近登录时间失败: {e}")))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交登录事务失败: {e}")))?;
+
+    build_login_or_refresh_response(
+        &cfg.security.session,
+        SessionIssueContext {
+            signing_key: cfg.security.jwt_keys.signing_key().clone(),
+            restriction: session_restriction_for(
+                &cfg,
+                &subject.role,
+                mfa_enabled,
+                subject.password_must_change,
+            ),
+            user_id: subject.user_id,
+            auth_version: subject.auth_version,
+            session_id,
+            username: subject.username,
+            role: subject.role,
+            display_name: subject.display_name,
+            email: subject.email,
+            refresh_generation: 0,
+            refresh_secret,
+        },
+    )
+}
+
+/// 须修改密码时签发只允许改密的受限会话；策略要求 admin 启用两步验证但尚未绑定时，
+/// 签发受限会话，只允许完成 TOTP 绑定。
+fn session_restriction_for(
+    cfg: &RuntimeConfig,
+    role: &str,
+    mfa_enabled: bool,
+    password_must_change: bool,
+) -> Option<SessionRestriction> {
+    if password_must_change {
+        return Some(SessionRestriction::PasswordChangeRequired);
+    }
+    if cfg.security.mfa_required_for_admin && role == "admin" && !mfa_enabled {
+        return Some(SessionRestriction::MfaEnrollmentRequired);
+    }
+    None
+}
+
+async fn create_mfa_challenge(
+    state: &AppState,
+    user_id: Uuid,
+    login_identifier: &str,
+    client: &ClientInfo,
+) -> Result<MfaChallengeResponse, AppError> {
+    let challenge_id = Uuid::new_v4();
+    let secret = generate_random_hex(32)?;
+    let secret_hash = crate::password::hash_password_argon2id(&secret)
+        .map_err(|e| AppError::InternalError(format!("MFA 挑战哈希失败: {e}")))?;
+    let expires_at = Utc::now() + Duration::seconds(MFA_CHALLENGE_EXPIRES_IN_SECS as i64);
+
+    sqlx::query!(
+        r#"
+INSERT INTO auth_mfa_challenges (
+    id,
+    user_id,
+    secret_hash,
+    expires_at,
+    created_ip,
+    created_user_agent,
+    login_identifier
+)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        challenge_id,
+        user_id,
+        secret_hash,
+        expires_at,
+        client.ip,
+        client.user_agent,
+        login_identifier,
+    )
+    .execute(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("创建 MFA 挑战失败: {e}")))?;
+
+    Ok(MfaChallengeResponse {
+        mfa_token: format!("{challenge_id}.{secret}"),
+        expires_in: MFA_CHALLENGE_EXPIRES_IN_SECS,
+        methods: vec![
+            SecondFactor::Totp.as_str().to_string(),
+            SecondFactor::RecoveryCode.as_str().to_string(),
+        ],
+    })
 }
 
 fn build_login_or_refresh_response(
+    session_cfg: &SessionTokenConfig,
     ctx: SessionIssueContext,
 ) -> Result<SessionIssueResponse, AppError> {
     let now = Utc::now();
-    let exp = (now + Duration::seconds(ACCESS_TOKEN_EXPIRES_IN_SECS as i64)).timestamp() as usize;
+    let exp =
+        (now + Duration::seconds(session_cfg.access_token_ttl_secs as i64)).timestamp() as usize;
     let iat = now.timestamp() as usize;
 
     let claims = Claims {
@@ -280,29 +1128,31 @@ fn build_login_or_refresh_response(
         display_name: Some(ctx.display_name),
         email: Some(ctx.email),
         role: ctx.role,
+        rst: ctx.restriction,
+        act: None,
     };
+    let token = sign_access_token(&ctx.signing_key, &claims)?;
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(ctx.jwt_secret.as_bytes()),
-    )
-    .map_err(|e| AppError::InternalError(format!("JWT 生成失败: {e}")))?;
-
-    let refresh_token = build_refresh_token(ctx.session_id, &ctx.refresh_secret);
-    let set_cookie = build_refresh_set_cookie_value(&refresh_token);
+    let refresh_token =
+        build_refresh_token(ctx.session_id, ctx.refresh_generation, &ctx.refresh_secret);
+    let set_cookie = build_refresh_set_cookie_value(session_cfg, &refresh_token);
     let headers = build_set_cookie_headers(set_cookie)?;
 
     Ok((
         headers,
         Json(CreateSessionResponse {
             token,
-            expires_in: ACCESS_TOKEN_EXPIRES_IN_SECS,
+            expires_in: session_cfg.access_token_ttl_secs,
         }),
     ))
 }
 
-fn build_set_cookie_headers(
+fn sign_access_token(signing_key: &JwtKey, claims: &Claims) -> Result<String, AppError> {
+    encode(&signing_key.header(), claims, signing_key.encoding_key())
+        .map_err(|e| AppError::InternalError(format!("JWT 生成失败: {e}")))
+}
+
+pub(crate) fn build_set_cookie_headers(
     set_cookie_value: String,
 ) -> Result<AppendHeaders<[(header::HeaderName, HeaderValue); 1]>, AppError> {
     let value = HeaderValue::from_str(&set_cookie_value)
@@ -310,29 +1160,34 @@ fn build_set_cookie_headers(
     Ok(AppendHeaders([(header::SET_COOKIE, value)]))
 }
 
-fn build_refresh_set_cookie_value(refresh_token: &str) -> String {
-    let mut segments = vec![
-        format!("{REFRESH_TOKEN_COOKIE_NAME}={refresh_token}"),
-        format!("Path={REFRESH_TOKEN_COOKIE_PATH}"),
-        "HttpOnly".to_string(),
-        "SameSite=Lax".to_string(),
-        format!("Max-Age={REFRESH_TOKEN_EXPIRES_IN_SECS}"),
-    ];
-    if !cfg!(debug_assertions) {
-        segments.push("Secure".to_string());
-    }
-    segments.join("; ")
+fn build_refresh_set_cookie_value(session_cfg: &SessionTokenConfig, refresh_token: &str) -> String {
+    build_refresh_cookie_value(
+        session_cfg,
+        refresh_token,
+        session_cfg.refresh_token_ttl_secs,
+    )
 }
 
-fn build_clear_refresh_cookie_value() -> String {
+pub(crate) fn build_clear_refresh_cookie_value(session_cfg: &SessionTokenConfig) -> String {
+    build_refresh_cookie_value(session_cfg, "", 0)
+}
+
+fn build_refresh_cookie_value(
+    session_cfg: &SessionTokenConfig,
+    value: &str,
+    max_age_secs: u64,
+) -> String {
     let mut segments = vec![
-        format!("{REFRESH_TOKEN_COOKIE_NAME}="),
-        format!("Path={REFRESH_TOKEN_COOKIE_PATH}"),
+        format!("{REFRESH_TOKEN_COOKIE_NAME}={value}"),
+        format!("Path={}", session_cfg.refresh_cookie_path),
         "HttpOnly".to_string(),
-        "SameSite=Lax".to_string(),
-        "Max-Age=0".to_string(),
+        format!("SameSite={}", session_cfg.refresh_cookie_same_site.as_str()),
+        format!("Max-Age={max_age_secs}"),
     ];
-    if !cfg!(debug_assertions) {
+    if let Some(domain) = &session_cfg.refresh_cookie_domain {
+        segments.push(format!("Domain={domain}"));
+    }
+    if session_cfg.refresh_cookie_secure.enabled() {
         segments.push("Secure".to_string());
     }
     segments.join("; ")
@@ -346,22 +1201,50 @@ fn generate_random_hex(byte_len: usize) -> Result<String, AppError> {
     Ok(crate::config::seed::hex_encode(&bytes))
 }
 
-fn build_refresh_token(session_id: Uuid, refresh_secret: &str) -> String {
-    format!("{session_id}.{refresh_secret}")
+/// 解析后的 refresh token：`{session_id}.{generation}.{secret}`。
+struct RefreshToken {
+    session_id: Uuid,
+    generation: i32,
+    secret: String,
+}
+
+fn build_refresh_token(session_id: Uuid, generation: i32, refresh_secret: &str) -> String {
+    format!("{session_id}.{generation}.{refresh_secret}")
+}
+
+fn parse_mfa_token(token: &str) -> Result<(Uuid, String), AppError> {
+    split_id_and_secret(token).ok_or_else(|| AppError::auth_token("MFA 挑战无效或已过期"))
 }
 
-fn parse_refresh_token(token: &str) -> Result<(Uuid, String), AppError> {
-    let token = token.trim();
-    let (session_id, secret) = token
-        .split_once('.')
-        .ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
-    let session_id =
-        Uuid::parse_str(session_id).map_err(|_| AppError::auth_token("Token 无效或已过期"))?;
+/// 升级前签发的 `{session_id}.{secret}` 视为第 0 代。
+fn parse_refresh_token(token: &str) -> Result<RefreshToken, AppError> {
+    let invalid = || AppError::auth_token("Token 无效或已过期");
+    let (session_id, rest) = split_id_and_secret(token).ok_or_else(invalid)?;
+    let (generation, secret) = match rest.split_once('.') {
+        Some((generation, secret)) => {
+            let generation = generation.parse::<i32>().map_err(|_| invalid())?;
+            (generation, secret.trim().to_string())
+        }
+        None => (0, rest),
+    };
+    if generation < 0 || secret.is_empty() {
+        return Err(invalid());
+    }
+    Ok(RefreshToken {
+        session_id,
+        generation,
+        secret,
+    })
+}
+
+fn split_id_and_secret(token: &str) -> Option<(Uuid, String)> {
+    let (id, secret) = token.trim().split_once('.')?;
+    let id = Uuid::parse_str(id).ok()?;
     let secret = secret.trim();
     if secret.is_empty() {
-        return Err(AppError::auth_token("Token 无效或已过期"));
+        return None;
     }
-    Ok((session_id, secret.to_string()))
+    Some((id, secret.to_string()))
 }
 
 fn extract_refresh_token_from_headers(headers: &HeaderMap) -> Result<String, AppError> {
@@ -399,13 +1282,34 @@ struct LoginUserRow {
     password_hash: Option<String>,
     is_active: bool,
     auth_version: i32,
+    locked_until: Option<DateTime<Utc>>,
+    password_must_change: bool,
 }
 
-async fn load_login_user(state: &AppState, identifier: &str) -> Result<LoginUserRow, AppError> {
+fn verify_login_password(user: &LoginUserRow, password: &str) -> Result<bool, AppError> {
+    if !user.is_active {
+        return Ok(false);
+    }
+    let Some(password_hash) = user.password_hash.as_deref() else {
+        return Ok(false);
+    };
+    if password_hash.trim().is_empty() {
+        return Ok(false);
+    }
+
+    crate::password::verify_password(password, password_hash)
+        .map_err(|e| AppError::InternalError(format!("用户密码校验失败: {e}")))
+}
+
+async fn load_login_user(
+    state: &AppState,
+    identifier: &str,
+) -> Result<Option<LoginUserRow>, AppError> {
     let mut users = sqlx::query_as!(
         LoginUserRow,
         r#"
-SELECT id, username, role, display_name, email, password_hash, is_active, auth_version
+SELECT id, username, role, display_name, email, password_hash, is_active, auth_version, locked_until,
+       password_must_change
 FROM users
 WHERE deleted_at IS NULL
   AND (
@@ -422,10 +1326,90 @@ LIMIT 2
     .map_err(|e| AppError::InternalError(format!("查询登录用户失败: {e}")))?;
 
     if users.len() != 1 {
-        return Err(AppError::auth_credential("用户名或密码错误"));
+        return Ok(None);
     }
 
-    Ok(users.remove(0))
+    Ok(Some(users.remove(0)))
+}
+
+#[derive(Debug, FromRow)]
+struct SessionListRow {
+    id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    created_ip: Option<String>,
+    created_user_agent: Option<String>,
+    last_ip: Option<String>,
+    last_user_agent: Option<String>,
+    last_refreshed_at: Option<DateTime<Utc>>,
+    impersonator_id: Option<Uuid>,
+}
+
+#[derive(Debug, FromRow)]
+struct ImpersonationTargetRow {
+    user_id: Uuid,
+    username: Option<String>,
+    role: String,
+    display_name: String,
+    email: String,
+    is_active: bool,
+    auth_version: i32,
+    actor_username: Option<String>,
+}
+
+#[derive(Debug, FromRow)]
+struct MfaChallengeRow {
+    user_id: Uuid,
+    username: Option<String>,
+    role: String,
+    display_name: String,
+    email: String,
+    user_is_active: bool,
+    auth_version: i32,
+    secret_hash: String,
+    expires_at: DateTime<Utc>,
+    consumed_at: Option<DateTime<Utc>>,
+    failed_attempts: i32,
+    login_identifier: Option<String>,
+    locked_until: Option<DateTime<Utc>>,
+    password_must_change: bool,
+}
+
+async fn load_mfa_challenge(
+    state: &AppState,
+    challenge_id: Uuid,
+) -> Result<MfaChallengeRow, AppError> {
+    let row = sqlx::query_as!(
+        MfaChallengeRow,
+        r#"
+SELECT
+    c.user_id,
+    u.username,
+    u.role,
+    u.display_name,
+    u.email,
+    (u.is_active AND u.deleted_at IS NULL) AS "user_is_active!",
+    u.auth_version,
+    c.secret_hash,
+    c.expires_at,
+    c.consumed_at,
+    c.failed_attempts,
+    c.login_identifier,
+    u.locked_until,
+    u.password_must_change
+FROM auth_mfa_challenges c
+INNER JOIN users u ON u.id = c.user_id
+WHERE c.id = $1
+LIMIT 1
+        "#,
+        challenge_id,
+    )
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询 MFA 挑战失败: {e}")))?;
+
+    row.ok_or_else(|| AppError::auth_token("MFA 挑战无效或已过期"))
 }
 
 #[derive(Debug, FromRow)]
@@ -438,8 +1422,10 @@ struct AuthSessionRow {
     user_is_active: bool,
     auth_version: i32,
     refresh_secret_hash: String,
+    refresh_generation: i32,
     expires_at: DateTime<Utc>,
     revoked_at: Option<DateTime<Utc>>,
+    password_must_change: bool,
 }
 
 async fn load_auth_session(state: &AppState, session_id: Uuid) -> Result<AuthSessionRow, AppError> {
@@ -455,8 +1441,10 @@ SELECT
     u.is_active AS user_is_active,
     u.auth_version,
     s.refresh_secret_hash,
+    s.refresh_generation,
     s.expires_at,
-    s.revoked_at
+    s.revoked_at,
+    u.password_must_change
 FROM auth_sessions s
 INNER JOIN users u ON u.id = s.user_id
 WHERE s.id = $1
@@ -482,13 +1470,49 @@ mod tests {
         assert_eq!(actual.as_deref(), Some("abc.def"));
     }
 
+    #[test]
+    fn refresh_cookie_should_follow_configured_attributes() {
+        let mut session_cfg = SessionTokenConfig {
+            access_token_ttl_secs: 600,
+            refresh_token_ttl_secs: 86400,
+            refresh_cookie_path: "/api/v1/sessions".to_string(),
+            refresh_cookie_domain: Some(".example.com".to_string()),
+            refresh_cookie_same_site: crate::config::runtime::CookieSameSite::None,
+            refresh_cookie_secure: crate::config::runtime::CookieSecure::Always,
+        };
+        assert_eq!(
+            build_refresh_set_cookie_value(&session_cfg, "abc.def"),
+            "refresh_token=abc.def; Path=/api/v1/sessions; HttpOnly; SameSite=None; Max-Age=86400; Domain=.example.com; Secure"
+        );
+
+        session_cfg.refresh_cookie_domain = None;
+        session_cfg.refresh_cookie_same_site = crate::config::runtime::CookieSameSite::Strict;
+        session_cfg.refresh_cookie_secure = crate::config::runtime::CookieSecure::Never;
+        assert_eq!(
+            build_clear_refresh_cookie_value(&session_cfg),
+            "refresh_token=; Path=/api/v1/sessions; HttpOnly; SameSite=Strict; Max-Age=0"
+        );
+    }
+
     #[test]
     fn should_parse_refresh_token_payload() {
         let session_id = Uuid::new_v4();
-        let token = format!("{session_id}.deadbeef");
+        let token = build_refresh_token(session_id, 3, "deadbeef");
         let parsed = parse_refresh_token(&token).expect("应成功解析 refresh token");
 
-        assert_eq!(parsed.0, session_id);
-        assert_eq!(parsed.1, "deadbeef");
+        assert_eq!(parsed.session_id, session_id);
+        assert_eq!(parsed.generation, 3);
+        assert_eq!(parsed.secret, "deadbeef");
+    }
+
+    #[test]
+    fn should_parse_legacy_refresh_token_as_generation_zero() {
+        let session_id = Uuid::new_v4();
+        let parsed = parse_refresh_token(&format!("{session_id}.deadbeef"))
+            .expect("应成功解析旧格式 refresh token");
+
+        assert_eq!(parsed.generation, 0);
+        assert_eq!(parsed.secret, "deadbeef");
+        assert!(parse_refresh_token(&format!("{session_id}.-1.deadbeef")).is_err());
     }
 }
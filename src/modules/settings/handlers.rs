@@ -1,27 +1,31 @@
-use axum::extract::{Extension, State};
+use axum::extract::State;
+use axum::response::IntoResponse;
 use axum::Json;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use uuid::Uuid;
 
-use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::etag::{self, IfMatch};
+use crate::api::permissions::{Permission, RequirePermission, SettingsRead, SettingsWrite};
+use crate::config::runtime::{
+    CookieSameSite, CookieSecure, RuntimeConfig, ORGANIZATION_OVERRIDABLE_KEYS,
+};
 use crate::error::AppError;
 use crate::http::router::AppState;
+use crate::services::jwt_keys::{self, JwtAlgorithm};
+use crate::services::security_events::{self, SecurityEvent};
 use crate::services::system_config;
 
-fn ensure_admin(current_user: &CurrentUser) -> Result<(), AppError> {
-    if current_user.role != "admin" {
-        return Err(AppError::PermissionDenied(
-            "仅管理员可执行该操作".to_string(),
-        ));
-    }
-    Ok(())
-}
-
 #[derive(Debug, Serialize, ToSchema)]
 pub struct SettingsResponse {
     pub app: AppSettings,
     pub integrations: IntegrationsSettings,
+    /// 仅对具备 `settings:write` 的调用方返回。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<SecuritySettings>,
+    pub users: UsersSettings,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -36,23 +40,82 @@ pub struct IntegrationsSettings {
     pub example_api_key_is_set: bool,
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UsersSettings {
+    /// 逻辑删除的用户保留天数，到期后物理删除；`0` 表示不自动清除。
+    pub purge_after_days: u64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SecuritySettings {
+    /// 是否要求 admin 角色必须启用两步验证（TOTP）。
+    pub mfa_required_for_admin: bool,
+    /// JWT 签名算法；修改后会自动轮换签名密钥。
+    pub jwt_algorithm: JwtAlgorithm,
+    pub login_failure_window_secs: u64,
+    pub login_backoff_after_failures: u64,
+    pub login_backoff_base_secs: u64,
+    pub login_backoff_max_secs: u64,
+    pub login_lockout_threshold: u64,
+    pub login_lockout_secs: u64,
+    pub login_ip_failure_threshold: u64,
+    pub access_token_ttl_secs: u64,
+    pub refresh_token_ttl_secs: u64,
+    pub refresh_cookie_path: String,
+    /// 为空表示 host-only cookie。
+    pub refresh_cookie_domain: Option<String>,
+    pub refresh_cookie_same_site: CookieSameSite,
+    /// `auto` 时仅 release 构建设置 `Secure`。
+    pub refresh_cookie_secure: CookieSecure,
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/settings",
     tag = "settings",
     responses(
-        (status = 200, description = "获取运行期配置", body = SettingsResponse),
+        (status = 200, description = "获取运行期配置（`security` 仅对具备 `settings:write` 的调用方返回）", body = SettingsResponse, headers(
+            ("ETag" = String, description = "配置版本，更新时放入 `If-Match` 做并发校验")
+        )),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `settings:read`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_settings_handler(
+    RequirePermission(current_user, _): RequirePermission<SettingsRead>,
     State(state): State<AppState>,
-) -> Result<Json<SettingsResponse>, AppError> {
-    let cfg = state.config.load_full();
+) -> Result<impl IntoResponse, AppError> {
+    let version = system_config::version(&state.db, current_user.organization_id).await?;
+    let include_security = current_user.has_permission(SettingsWrite::NAME);
+    let settings = match current_user.organization_id {
+        Some(organization_id) => {
+            organization_settings(&state, organization_id, include_security).await?
+        }
+        None => settings_response(&state.config.load_full(), include_security),
+    };
+    Ok((etag::header(version), Json(settings)))
+}
+
+/// 组织上下文：全局配置叠加该组织的覆盖项。
+async fn organization_settings(
+    state: &AppState,
+    organization_id: Uuid,
+    include_security: bool,
+) -> Result<SettingsResponse, AppError> {
+    let cfg = state
+        .config
+        .load_full()
+        .with_organization_overrides(&state.db, organization_id)
+        .await
+        .map_err(|e| AppError::InternalError(format!("加载组织配置失败: {e}")))?;
+    Ok(settings_response(&cfg, include_security))
+}
 
-    Ok(Json(SettingsResponse {
+/// `include_security` 为 `false` 时不返回安全配置（登录防爆破阈值、会话与 Cookie 参数等）。
+fn settings_response(cfg: &RuntimeConfig, include_security: bool) -> SettingsResponse {
+    SettingsResponse {
         app: AppSettings {
             check_interval_secs: cfg.app.check_interval_secs,
             welcome_message: cfg.app.welcome_message.clone(),
@@ -61,7 +124,27 @@ pub async fn get_settings_handler(
             example_api_base: cfg.integrations.example_api_base.clone(),
             example_api_key_is_set: !cfg.integrations.example_api_key.trim().is_empty(),
         },
-    }))
+        security: include_security.then(|| SecuritySettings {
+            mfa_required_for_admin: cfg.security.mfa_required_for_admin,
+            jwt_algorithm: cfg.security.jwt_algorithm,
+            login_failure_window_secs: cfg.security.login_throttle.failure_window_secs,
+            login_backoff_after_failures: cfg.security.login_throttle.backoff_after_failures,
+            login_backoff_base_secs: cfg.security.login_throttle.backoff_base_secs,
+            login_backoff_max_secs: cfg.security.login_throttle.backoff_max_secs,
+            login_lockout_threshold: cfg.security.login_throttle.lockout_threshold,
+            login_lockout_secs: cfg.security.login_throttle.lockout_secs,
+            login_ip_failure_threshold: cfg.security.login_throttle.ip_failure_threshold,
+            access_token_ttl_secs: cfg.security.session.access_token_ttl_secs,
+            refresh_token_ttl_secs: cfg.security.session.refresh_token_ttl_secs,
+            refresh_cookie_path: cfg.security.session.refresh_cookie_path.clone(),
+            refresh_cookie_domain: cfg.security.session.refresh_cookie_domain.clone(),
+            refresh_cookie_same_site: cfg.security.session.refresh_cookie_same_site,
+            refresh_cookie_secure: cfg.security.session.refresh_cookie_secure,
+        }),
+        users: UsersSettings {
+            purge_after_days: cfg.users.purge_after_days,
+        },
+    }
 }
 
 #[derive(Debug, Deserialize, ToSchema, Validate)]
@@ -70,6 +153,10 @@ pub struct PatchSettingsRequest {
     pub app: Option<PatchAppSettings>,
     #[garde(dive)]
     pub integrations: Option<PatchIntegrationsSettings>,
+    #[garde(dive)]
+    pub security: Option<PatchSecuritySettings>,
+    #[garde(dive)]
+    pub users: Option<PatchUsersSettings>,
 }
 
 #[derive(Debug, Deserialize, ToSchema, Validate)]
@@ -110,29 +197,118 @@ pub struct PatchIntegrationsSettings {
     pub example_api_key: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PatchUsersSettings {
+    /// 0 ~ 3650 天；`0` 表示不自动清除。
+    #[schema(minimum = 0, maximum = 3650)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_max_3650))]
+    pub purge_after_days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct PatchSecuritySettings {
+    #[garde(skip)]
+    pub mfa_required_for_admin: Option<bool>,
+
+    /// 与当前签名密钥算法不同时，以新算法自动轮换签名密钥（旧密钥保留默认宽限期）。
+    #[garde(skip)]
+    pub jwt_algorithm: Option<JwtAlgorithm>,
+
+    /// 1 ~ 2592000 秒（30 天）。
+    #[schema(minimum = 1, maximum = 2592000)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_failure_window_secs: Option<u64>,
+
+    #[schema(minimum = 1)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_backoff_after_failures: Option<u64>,
+
+    /// 1 ~ 2592000 秒（30 天）。
+    #[schema(minimum = 1, maximum = 2592000)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_backoff_base_secs: Option<u64>,
+
+    /// 1 ~ 2592000 秒（30 天）。
+    #[schema(minimum = 1, maximum = 2592000)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_backoff_max_secs: Option<u64>,
+
+    #[schema(minimum = 1)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_lockout_threshold: Option<u64>,
+
+    /// 1 ~ 2592000 秒（30 天）。
+    #[schema(minimum = 1, maximum = 2592000)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_lockout_secs: Option<u64>,
+
+    #[schema(minimum = 1)]
+    #[garde(custom(crate::api::garde_helpers::opt_u64_min_1))]
+    pub login_ip_failure_threshold: Option<u64>,
+
+    /// 60 ~ 86400 秒。
+    #[schema(minimum = 60, maximum = 86400)]
+    #[garde(skip)]
+    pub access_token_ttl_secs: Option<u64>,
+
+    /// 必须大于 `access_token_ttl_secs`，最长 365 天。
+    #[schema(minimum = 61, maximum = 31536000)]
+    #[garde(skip)]
+    pub refresh_token_ttl_secs: Option<u64>,
+
+    /// 必须以 `/` 开头并覆盖 `/api/v1/sessions/refresh`。
+    #[schema(min_length = 1, max_length = 256)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(length(max = 256))]
+    pub refresh_cookie_path: Option<String>,
+
+    /// 传空字符串表示清除 `Domain`（host-only cookie）。
+    #[schema(max_length = 253)]
+    #[garde(length(max = 253))]
+    pub refresh_cookie_domain: Option<String>,
+
+    /// `None` 要求 `refresh_cookie_secure = always`。
+    #[garde(skip)]
+    pub refresh_cookie_same_site: Option<CookieSameSite>,
+
+    #[garde(skip)]
+    pub refresh_cookie_secure: Option<CookieSecure>,
+}
+
 #[utoipa::path(
     patch,
     path = "/api/v1/settings",
     tag = "settings",
+    params(
+        ("If-Match" = Option<String>, Header, description = "上次读取到的 `ETag`；不一致时返回 412")
+    ),
     request_body = PatchSettingsRequest,
     responses(
-        (status = 200, description = "更新并返回运行期配置", body = SettingsResponse),
+        (status = 200, description = "更新并返回运行期配置（修改 jwt_algorithm 会同时轮换签名密钥）", body = SettingsResponse, headers(
+            ("ETag" = String, description = "配置版本，更新时放入 `If-Match` 做并发校验")
+        )),
         (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
-        (status = 403, description = "权限不足（仅管理员可写）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `settings:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 412, description = "`If-Match` 与当前版本不一致", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn patch_settings_handler(
-    Extension(current_user): Extension<CurrentUser>,
+    RequirePermission(current_user, _): RequirePermission<SettingsWrite>,
     State(state): State<AppState>,
+    client: ClientInfo,
+    if_match: IfMatch,
     crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
         PatchSettingsRequest,
     >,
-) -> Result<Json<SettingsResponse>, AppError> {
-    ensure_admin(&current_user)?;
+) -> Result<impl IntoResponse, AppError> {
     let mut changes: Vec<(String, serde_json::Value)> = Vec::new();
+    let mut rotate_to: Option<JwtAlgorithm> = None;
 
     if let Some(app) = payload.app {
         if let Some(v) = app.check_interval_secs {
@@ -164,7 +340,211 @@ pub async fn patch_settings_handler(
         }
     }
 
-    system_config::upsert_many(&state.db, changes).await?;
+    if let Some(security) = payload.security {
+        if let Some(v) = security.mfa_required_for_admin {
+            changes.push((
+                "security.mfa_required_for_admin".to_string(),
+                serde_json::Value::Bool(v),
+            ));
+        }
+        if let Some(v) = security.jwt_algorithm {
+            changes.push((
+                "security.jwt_algorithm".to_string(),
+                serde_json::Value::String(v.as_str().to_string()),
+            ));
+            let cfg = state.config.load_full();
+            if cfg.security.jwt_keys.signing_key().algorithm != v.algorithm() {
+                rotate_to = Some(v);
+            }
+        }
+        let login_throttle_changes = [
+            (
+                "security.login_failure_window_secs",
+                security.login_failure_window_secs,
+            ),
+            (
+                "security.login_backoff_after_failures",
+                security.login_backoff_after_failures,
+            ),
+            (
+                "security.login_backoff_base_secs",
+                security.login_backoff_base_secs,
+            ),
+            (
+                "security.login_backoff_max_secs",
+                security.login_backoff_max_secs,
+            ),
+            (
+                "security.login_lockout_threshold",
+                security.login_lockout_threshold,
+            ),
+            ("security.login_lockout_secs", security.login_lockout_secs),
+            (
+                "security.login_ip_failure_threshold",
+                security.login_ip_failure_threshold,
+            ),
+        ];
+        for (key, value) in login_throttle_changes {
+            if let Some(v) = value {
+                changes.push((
+                    key.to_string(),
+                    serde_json::Value::Number(serde_json::Number::from(v)),
+                ));
+            }
+        }
+        let mut login_throttle = state.config.load_full().security.login_throttle.clone();
+        let durations = [
+            (
+                &mut login_throttle.failure_window_secs,
+                security.login_failure_window_secs,
+            ),
+            (
+                &mut login_throttle.backoff_base_secs,
+                security.login_backoff_base_secs,
+            ),
+            (
+                &mut login_throttle.backoff_max_secs,
+                security.login_backoff_max_secs,
+            ),
+            (
+                &mut login_throttle.lockout_secs,
+                security.login_lockout_secs,
+            ),
+        ];
+        for (field, value) in durations {
+            if let Some(v) = value {
+                *field = v;
+            }
+        }
+        login_throttle.validate().map_err(AppError::validation)?;
+
+        // token 有效期与 cookie 属性存在跨字段约束，按合并后的最终配置校验。
+        let mut session = state.config.load_full().security.session.clone();
+        if let Some(v) = security.access_token_ttl_secs {
+            session.access_token_ttl_secs = v;
+            changes.push((
+                "security.access_token_ttl_secs".to_string(),
+                serde_json::Value::Number(serde_json::Number::from(v)),
+            ));
+        }
+        if let Some(v) = security.refresh_token_ttl_secs {
+            session.refresh_token_ttl_secs = v;
+            changes.push((
+                "security.refresh_token_ttl_secs".to_string(),
+                serde_json::Value::Number(serde_json::Number::from(v)),
+            ));
+        }
+        if let Some(v) = security.refresh_cookie_path {
+            session.refresh_cookie_path = v.clone();
+            changes.push((
+                "security.refresh_cookie_path".to_string(),
+                serde_json::Value::String(v),
+            ));
+        }
+        if let Some(v) = security.refresh_cookie_domain {
+            let v = v.trim().to_ascii_lowercase();
+            session.refresh_cookie_domain = Some(v.clone()).filter(|v| !v.is_empty());
+            changes.push((
+                "security.refresh_cookie_domain".to_string(),
+                serde_json::Value::String(v),
+            ));
+        }
+        if let Some(v) = security.refresh_cookie_same_site {
+            session.refresh_cookie_same_site = v;
+            changes.push((
+                "security.refresh_cookie_same_site".to_string(),
+                serde_json::Value::String(v.as_str().to_string()),
+            ));
+        }
+        if let Some(v) = security.refresh_cookie_secure {
+            session.refresh_cookie_secure = v;
+            changes.push((
+                "security.refresh_cookie_secure".to_string(),
+                serde_json::Value::String(v.as_str().to_string()),
+            ));
+        }
+        session.validate().map_err(AppError::validation)?;
+    }
+
+    if let Some(users) = payload.users {
+        if let Some(v) = users.purge_after_days {
+            changes.push((
+                "users.purge_after_days".to_string(),
+                serde_json::Value::Number(serde_json::Number::from(v)),
+            ));
+        }
+    }
+
+    if let Some(organization_id) = current_user.organization_id {
+        if let Some((key, _)) = changes
+            .iter()
+            .find(|(key, _)| !ORGANIZATION_OVERRIDABLE_KEYS.contains(&key.as_str()))
+        {
+            return Err(AppError::validation(format!(
+                "组织上下文不能修改全局配置: {key}"
+            )));
+        }
+        system_config::upsert_organization_many(&state.db, organization_id, changes, &if_match)
+            .await?;
+        let version = system_config::version(&state.db, Some(organization_id)).await?;
+        return Ok((
+            etag::header(version),
+            Json(organization_settings(&state, organization_id, true).await?),
+        ));
+    }
+
+    // 配置写入与签名密钥轮换在同一事务中提交，轮换失败时配置也不落库。
+    let cfg = state.config.load_full();
+    let grace_period_secs = jwt_keys::DEFAULT_GRACE_PERIOD_SECS;
+    let prepared = match rotate_to {
+        Some(algorithm) => Some((
+            algorithm,
+            jwt_keys::prepare(&cfg.security.data_encryption_key, algorithm).await?,
+        )),
+        None => None,
+    };
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启配置更新事务失败: {e}")))?;
+    system_config::upsert_many(&mut tx, changes, &if_match).await?;
+
+    if let Some((algorithm, prepared)) = prepared {
+        let kid = jwt_keys::rotate_in_tx(
+            &mut tx,
+            &cfg.security.jwt_secret,
+            &cfg.security.data_encryption_key,
+            prepared,
+            grace_period_secs,
+        )
+        .await?;
+        security_events::record(
+            &mut *tx,
+            SecurityEvent {
+                event_type: security_events::JWT_KEY_ROTATED,
+                user_id: Some(current_user.user_id),
+                session_id: current_user.session_id,
+                client: &client,
+                details: serde_json::json!({
+                    "kid": kid,
+                    "algorithm": algorithm.as_str(),
+                    "grace_period_secs": grace_period_secs,
+                }),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交配置更新事务失败: {e}")))?;
+
     state.reload_runtime().await?;
-    get_settings_handler(State(state)).await
+    let version = system_config::version(&state.db, None).await?;
+    Ok((
+        etag::header(version),
+        Json(settings_response(&state.config.load_full(), true)),
+    ))
 }
@@ -1,16 +1,24 @@
-use axum::extract::{Extension, Path, Query, State};
-use axum::http::StatusCode;
+use axum::extract::{Extension, Path, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::IntoResponse;
 use axum::Json;
 use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use garde::Validate;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use sqlx::{Connection, PgConnection, PgExecutor};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::api::auth::CurrentUser;
+use crate::api::client_info::ClientInfo;
+use crate::api::etag::{self, IfMatch};
+use crate::api::permissions::{RequirePermission, UsersRead, UsersWrite};
 use crate::db::DbPool;
 use crate::error::AppError;
 use crate::http::router::AppState;
+use crate::services::impersonation::{self, RevokedSession};
+use crate::services::{login_throttle, rbac};
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct UserResponse {
@@ -20,12 +28,27 @@ pub struct UserResponse {
     pub email: String,
     pub phone: Option<String>,
     pub avatar_url: Option<String>,
+    /// 角色名（内置 `admin` | `user` | `auditor` 或自定义角色）。
+    pub role: String,
     pub is_active: bool,
     pub metadata: serde_json::Value,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// 管理端用户详情：在 [`UserResponse`] 基础上附带删除状态与登录信息。
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserDetailResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    /// 逻辑删除时间；未删除时为 `null`。
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// 最近一次登录时间（模拟登录不计入）；从未登录时为 `null`。
+    pub last_login_at: Option<DateTime<Utc>>,
+    /// 未吊销且未过期的会话数。
+    pub active_session_count: i64,
+}
+
 #[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct CreateUserRequest {
     #[schema(min_length = 1, max_length = 64)]
@@ -67,27 +90,161 @@ pub struct CreateUserRequest {
     #[garde(length(max = 2048))]
     pub avatar_url: Option<String>,
 
+    /// 角色名（内置 `admin` | `user` | `auditor` 或自定义角色），默认 `user`；不能超出操作者自身的权限。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(length(max = 32))]
+    pub role: Option<String>,
+
     #[serde(default)]
     #[garde(skip)]
     pub metadata: Option<serde_json::Value>,
 }
 
+/// 单次导入的最大数据行数。
+const MAX_IMPORT_ROWS: usize = 1000;
+/// CSV 导入允许的列，与 [`CreateUserRequest`] 字段一致；`metadata` 列为 JSON 对象文本。
+const IMPORT_CSV_COLUMNS: [&str; 7] = [
+    "username",
+    "display_name",
+    "email",
+    "phone",
+    "avatar_url",
+    "role",
+    "metadata",
+];
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportUsersQuery {
+    /// 只校验不写入，默认 `false`。
+    #[serde(default)]
+    #[garde(skip)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportUsersResponse {
+    pub dry_run: bool,
+    /// 数据行总数（不含 CSV 表头与空行）。
+    pub total: usize,
+    /// 校验通过的行数；非 dry-run 时即实际创建的用户数。
+    pub succeeded: usize,
+    pub failed: usize,
+    pub rows: Vec<ImportUserRowResult>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportUserRowResult {
+    /// 该行在文件中的起始行号（从 1 开始）。
+    pub line: usize,
+    pub email: Option<String>,
+    /// 新建用户 ID；dry-run 或该行失败时为 `null`。
+    pub user_id: Option<Uuid>,
+    /// 失败原因；成功时为 `null`。
+    pub error: Option<String>,
+    /// 字段级校验错误，结构同错误体的 `details`。
+    pub details: Option<serde_json::Value>,
+}
+
+/// 导出可选列，默认按此顺序导出全部列。
+const EXPORT_COLUMNS: [&str; 13] = [
+    "id",
+    "username",
+    "display_name",
+    "email",
+    "phone",
+    "avatar_url",
+    "role",
+    "is_active",
+    "metadata",
+    "created_at",
+    "updated_at",
+    "deleted_at",
+    "last_login_at",
+];
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportUsersQuery {
+    /// 导出格式，默认 `csv`。
+    #[param(inline)]
+    #[serde(default)]
+    #[garde(skip)]
+    pub format: ExportFormat,
+    /// 逗号分隔的导出列，默认全部：`id`、`username`、`display_name`、`email`、`phone`、`avatar_url`、
+    /// `role`、`is_active`、`metadata`、`created_at`、`updated_at`、`deleted_at`、`last_login_at`。
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(custom(export_columns_format))]
+    pub columns: Option<String>,
+    /// 是否包含已逻辑删除用户，默认 `false`。
+    #[serde(default)]
+    #[garde(skip)]
+    pub include_deleted: bool,
+    /// 同用户列表的 `q`。
+    #[param(max_length = 128)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(length(chars, max = 128))]
+    pub q: Option<String>,
+    #[garde(skip)]
+    pub is_active: Option<bool>,
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(skip)]
+    pub role: Option<String>,
+    #[garde(skip)]
+    pub created_from: Option<DateTime<Utc>>,
+    #[garde(skip)]
+    pub created_to: Option<DateTime<Utc>>,
+    #[param(inline)]
+    #[garde(skip)]
+    pub sort: Option<UserSort>,
+}
+
+fn export_columns_format(v: &Option<String>, _ctx: &()) -> garde::Result {
+    for column in v.iter().flat_map(|v| v.split(',')) {
+        if !EXPORT_COLUMNS.contains(&column.trim()) {
+            return Err(garde::Error::new(format!("未知的导出列: {column}")));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Deserialize, ToSchema, Validate)]
 pub struct PatchUserRequest {
+    /// 传 `null` 或空字符串清空用户名。
     #[schema(min_length = 1, max_length = 64)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_nullable_trimmed_string"
     )]
-    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
-    #[garde(custom(crate::api::garde_helpers::opt_username_format))]
+    #[garde(custom(crate::api::garde_helpers::nullable_username_format))]
     #[garde(length(max = 64))]
-    pub username: Option<String>,
+    pub username: Option<Option<String>>,
 
     #[schema(min_length = 1, max_length = 128)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_non_null_trimmed_string"
     )]
     #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 128))]
@@ -96,33 +253,52 @@ pub struct PatchUserRequest {
     #[schema(min_length = 3, max_length = 320)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_non_null_trimmed_string"
     )]
     #[garde(custom(crate::api::garde_helpers::opt_string_basic_email))]
     #[garde(length(max = 320))]
     pub email: Option<String>,
 
+    /// 传 `null` 或空字符串清空手机号。
     #[schema(min_length = 1, max_length = 32)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_nullable_trimmed_string"
     )]
-    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 32))]
-    pub phone: Option<String>,
+    pub phone: Option<Option<String>>,
 
+    /// 传 `null` 或空字符串清空头像。
     #[schema(min_length = 1, max_length = 2048)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_nullable_trimmed_string"
     )]
-    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 2048))]
-    pub avatar_url: Option<String>,
+    pub avatar_url: Option<Option<String>>,
+
+    /// 角色名；不能超出操作者自身的权限，变更后该用户已签发的 access token 立即失效。
+    #[schema(min_length = 1, max_length = 32)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_non_null_trimmed_string"
+    )]
+    #[garde(length(max = 32))]
+    pub role: Option<String>,
 
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_some"
+    )]
     #[garde(skip)]
     pub is_active: Option<bool>,
 
+    /// 按 RFC 7396 与现有 metadata 深度合并：值为 `null` 的键被删除，传 `null` 清空为 `{}`。
+    #[schema(value_type = Option<Object>)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_some"
+    )]
     #[garde(skip)]
     pub metadata: Option<serde_json::Value>,
 }
@@ -133,7 +309,7 @@ pub struct PatchCurrentUserRequest {
     #[schema(min_length = 1, max_length = 128)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_non_null_trimmed_string"
     )]
     #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 128))]
@@ -142,44 +318,213 @@ pub struct PatchCurrentUserRequest {
     #[schema(min_length = 3, max_length = 320)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_non_null_trimmed_string"
     )]
     #[garde(custom(crate::api::garde_helpers::opt_string_basic_email))]
     #[garde(length(max = 320))]
     pub email: Option<String>,
 
+    /// 传 `null` 或空字符串清空手机号。
     #[schema(min_length = 1, max_length = 32)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_nullable_trimmed_string"
     )]
-    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 32))]
-    pub phone: Option<String>,
+    pub phone: Option<Option<String>>,
 
+    /// 传 `null` 或空字符串清空头像。
     #[schema(min_length = 1, max_length = 2048)]
     #[serde(
         default,
-        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+        deserialize_with = "crate::api::serde_helpers::deserialize_nullable_trimmed_string"
     )]
-    #[garde(custom(crate::api::garde_helpers::opt_string_trim_non_empty))]
     #[garde(length(max = 2048))]
-    pub avatar_url: Option<String>,
+    pub avatar_url: Option<Option<String>>,
 }
 
-#[derive(Debug, Deserialize, ToSchema)]
-pub struct ListUsersQuery {
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct DeleteCurrentUserRequest {
+    /// 当前密码，用于确认注销。
+    #[schema(format = "password", min_length = 1, max_length = 256)]
+    #[garde(length(min = 1, max = 256))]
+    pub password: String,
+}
+
+/// 个人数据归档：我们保存的与当前用户相关的全部数据。
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataExport {
+    pub exported_at: DateTime<Utc>,
+    pub profile: UserDetailResponse,
+    pub mfa_enabled: bool,
+    pub organizations: Vec<PersonalDataOrganization>,
+    pub groups: Vec<PersonalDataGroup>,
+    /// 全部会话（含已吊销、已过期及模拟登录会话）。
+    pub sessions: Vec<PersonalDataSession>,
+    /// API token 元数据，不含密钥。
+    pub api_tokens: Vec<PersonalDataApiToken>,
+    pub security_events: Vec<PersonalDataSecurityEvent>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataOrganization {
+    pub organization_id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub role: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataGroup {
+    pub group_id: Uuid,
+    pub name: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataSession {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_reason: Option<String>,
+    pub created_ip: Option<String>,
+    pub created_user_agent: Option<String>,
+    pub last_ip: Option<String>,
+    pub last_user_agent: Option<String>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    /// 模拟登录会话的操作者；用户本人登录时为 `null`。
+    pub impersonator_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataApiToken {
+    pub id: Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub last_used_ip: Option<String>,
+    pub last_used_user_agent: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PersonalDataSecurityEvent {
+    pub event_type: String,
+    pub session_id: Option<Uuid>,
+    pub client_ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, Validate)]
+pub struct SetUserPasswordRequest {
+    #[schema(format = "password", min_length = 8, max_length = 256)]
+    #[garde(custom(crate::api::garde_helpers::string_trim_min_len_8))]
+    #[garde(length(max = 256))]
+    pub password: String,
+
+    /// 是否要求用户下次登录时修改密码，默认 `false`。
     #[serde(default)]
-    pub include_deleted: bool,
+    #[garde(skip)]
+    pub must_change: bool,
 }
 
-fn ensure_admin(current_user: &CurrentUser) -> Result<(), AppError> {
-    if current_user.role != "admin" {
-        return Err(AppError::PermissionDenied(
-            "仅管理员可执行该操作".to_string(),
-        ));
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TemporaryPasswordResponse {
+    /// 临时密码明文，仅在本次响应中返回；用户使用后必须修改密码。
+    pub temporary_password: String,
+}
+
+/// 用户列表默认每页条数。
+const DEFAULT_USERS_PER_PAGE: u32 = 50;
+/// 用户列表单页上限，避免一次拉取全表。
+const MAX_USERS_PER_PAGE: u32 = 200;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+pub enum UserSort {
+    #[serde(rename = "created_at")]
+    CreatedAt,
+    #[default]
+    #[serde(rename = "-created_at")]
+    CreatedAtDesc,
+    #[serde(rename = "username")]
+    Username,
+    #[serde(rename = "-username")]
+    UsernameDesc,
+    #[serde(rename = "display_name")]
+    DisplayName,
+    #[serde(rename = "-display_name")]
+    DisplayNameDesc,
+    #[serde(rename = "email")]
+    Email,
+    #[serde(rename = "-email")]
+    EmailDesc,
+}
+
+impl UserSort {
+    /// 对应的 `ORDER BY` 片段，以 `u.id` 兜底保证分页稳定。
+    fn order_by(self) -> &'static str {
+        match self {
+            Self::CreatedAt => "u.created_at ASC, u.id",
+            Self::CreatedAtDesc => "u.created_at DESC, u.id",
+            Self::Username => "u.username ASC NULLS LAST, u.id",
+            Self::UsernameDesc => "u.username DESC NULLS LAST, u.id",
+            Self::DisplayName => "u.display_name ASC, u.id",
+            Self::DisplayNameDesc => "u.display_name DESC, u.id",
+            Self::Email => "u.email ASC, u.id",
+            Self::EmailDesc => "u.email DESC, u.id",
+        }
     }
-    Ok(())
+}
+
+#[derive(Debug, Deserialize, Validate, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListUsersQuery {
+    /// 是否包含已逻辑删除用户，默认 `false`。
+    #[serde(default)]
+    #[garde(skip)]
+    pub include_deleted: bool,
+    /// 页码，从 1 开始，默认 1。
+    #[param(minimum = 1)]
+    #[garde(range(min = 1))]
+    pub page: Option<u32>,
+    /// 每页条数，默认 50，最大 200。
+    #[param(minimum = 1, maximum = 200)]
+    #[garde(range(min = 1, max = MAX_USERS_PER_PAGE))]
+    pub per_page: Option<u32>,
+    /// 模糊搜索用户名、显示名、邮箱和手机号（不区分大小写）。
+    #[param(max_length = 128)]
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(length(chars, max = 128))]
+    pub q: Option<String>,
+    /// 按启用状态过滤。
+    #[garde(skip)]
+    pub is_active: Option<bool>,
+    /// 按角色过滤；组织上下文中匹配组织内角色。
+    #[serde(
+        default,
+        deserialize_with = "crate::api::serde_helpers::deserialize_opt_trimmed_string"
+    )]
+    #[garde(skip)]
+    pub role: Option<String>,
+    /// 只返回该时间及之后创建的用户（RFC 3339）。
+    #[garde(skip)]
+    pub created_from: Option<DateTime<Utc>>,
+    /// 只返回该时间之前创建的用户（RFC 3339）。
+    #[garde(skip)]
+    pub created_to: Option<DateTime<Utc>>,
+    /// 排序字段，`-` 前缀表示倒序，默认 `-created_at`。
+    #[param(inline)]
+    #[garde(skip)]
+    pub sort: Option<UserSort>,
 }
 
 #[utoipa::path(
@@ -187,7 +532,9 @@ fn ensure_admin(current_user: &CurrentUser) -> Result<(), AppError> {
     path = "/api/v1/users/me",
     tag = "users",
     responses(
-        (status = 200, description = "获取当前用户", body = UserResponse),
+        (status = 200, description = "获取当前用户", body = UserResponse, headers(
+            ("ETag" = String, description = "资源版本，更新时放入 `If-Match` 做并发校验")
+        )),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
         (status = 404, description = "当前用户不存在", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
@@ -197,21 +544,31 @@ fn ensure_admin(current_user: &CurrentUser) -> Result<(), AppError> {
 pub async fn get_current_user_handler(
     Extension(current_user): Extension<CurrentUser>,
     State(state): State<AppState>,
-) -> Result<Json<UserResponse>, AppError> {
-    let user = get_user_by_id(&state.db, current_user.user_id).await?;
-    Ok(Json(user))
+) -> Result<impl IntoResponse, AppError> {
+    let user = get_user_by_id(&state.db, current_user.user_id, None).await?;
+    Ok((etag::header(user.updated_at), Json(user)))
 }
 
 #[utoipa::path(
     patch,
     path = "/api/v1/users/me",
     tag = "users",
-    request_body = PatchCurrentUserRequest,
+    params(("If-Match" = Option<String>, Header, description = "上次读取到的 `ETag`；不一致时返回 412")),
+    request_body(
+        content(
+            (PatchCurrentUserRequest = "application/merge-patch+json"),
+            (PatchCurrentUserRequest = "application/json")
+        ),
+        description = "RFC 7396 JSON Merge Patch：缺省字段不修改，`null` 清空可置空字段"
+    ),
     responses(
-        (status = 200, description = "更新当前用户", body = UserResponse),
+        (status = 200, description = "更新当前用户", body = UserResponse, headers(
+            ("ETag" = String, description = "资源版本，更新时放入 `If-Match` 做并发校验")
+        )),
         (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
         (status = 404, description = "当前用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 412, description = "`If-Match` 与当前版本不一致", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
@@ -219,36 +576,227 @@ pub async fn get_current_user_handler(
 pub async fn patch_current_user_handler(
     Extension(current_user): Extension<CurrentUser>,
     State(state): State<AppState>,
+    if_match: IfMatch,
     crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
         PatchCurrentUserRequest,
     >,
-) -> Result<Json<UserResponse>, AppError> {
-    let user = patch_current_user(&state.db, current_user.user_id, payload).await?;
-    Ok(Json(user))
+) -> Result<impl IntoResponse, AppError> {
+    let user = patch_current_user(&state.db, current_user.user_id, payload, &if_match).await?;
+    Ok((etag::header(user.updated_at), Json(user)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me",
+    tag = "users",
+    request_body = DeleteCurrentUserRequest,
+    responses(
+        (status = 204, description = "注销当前账号（逻辑删除，无 body）；全部会话已撤销，保留期内可由管理员恢复"),
+        (status = 400, description = "请求参数错误 / 最后一个启用中的管理员不能注销", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效 / 密码错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "模拟登录会话或 API token 不能注销账号", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "当前用户不存在或未设置密码", body = crate::api::openapi::ErrorResponseBody),
+        (status = 429, description = "密码错误次数过多或账号已临时锁定（见 Retry-After）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_current_user_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        DeleteCurrentUserRequest,
+    >,
+) -> Result<impl IntoResponse, AppError> {
+    let cfg = state.config.load_full();
+    let user = sqlx::query!(
+        "SELECT username, email, password_hash, locked_until FROM users WHERE id = $1",
+        current_user.user_id
+    )
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("读取当前用户密码失败: {e}")))?;
+    let not_found = || {
+        AppError::NotFound(format!(
+            "当前用户不存在或未设置密码: {}",
+            current_user.user_id
+        ))
+    };
+    let user = user.ok_or_else(not_found)?;
+    let password_hash = user
+        .password_hash
+        .as_deref()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(not_found)?;
+
+    // 与登录共用失败计数与退避，避免借注销接口暴力猜测密码
+    let identifier =
+        login_throttle::normalize_identifier(user.username.as_deref().unwrap_or(&user.email));
+    login_throttle::ensure_not_throttled(
+        &state.db,
+        &cfg.security.login_throttle,
+        &identifier,
+        &client,
+    )
+    .await?;
+    login_throttle::ensure_account_not_locked(user.locked_until)?;
+
+    let ok = crate::password::verify_password(&payload.password, password_hash)
+        .map_err(|e| AppError::InternalError(format!("当前用户密码校验失败: {e}")))?;
+    if !ok {
+        login_throttle::record_failure(
+            &state.db,
+            &cfg.security.login_throttle,
+            &identifier,
+            Some(current_user.user_id),
+            &client,
+        )
+        .await?;
+        return Err(AppError::auth_credential("密码错误"));
+    }
+    login_throttle::clear_identifier(&state.db, &identifier).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启注销账号事务失败: {e}")))?;
+    soft_delete_user_in_tx(&mut tx, current_user.user_id, &client).await?;
+    crate::services::security_events::record(
+        &mut *tx,
+        crate::services::security_events::SecurityEvent {
+            event_type: crate::services::security_events::ACCOUNT_DELETION_REQUESTED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "purge_after_days": cfg.users.purge_after_days,
+            }),
+        },
+    )
+    .await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交注销账号事务失败: {e}")))?;
+
+    let headers = crate::modules::sessions::handlers::build_set_cookie_headers(
+        crate::modules::sessions::handlers::build_clear_refresh_cookie_value(&cfg.security.session),
+    )?;
+    Ok((headers, StatusCode::NO_CONTENT))
 }
 
 #[utoipa::path(
     get,
-    path = "/api/v1/users",
+    path = "/api/v1/users/me/export",
     tag = "users",
-    params(
-        ("include_deleted" = Option<bool>, Query, description = "是否包含已逻辑删除用户")
+    responses(
+        (status = 200, description = "下载当前用户的个人数据归档（JSON）", body = PersonalDataExport, headers(
+            ("Content-Disposition" = String, description = "`attachment; filename=\"personal-data-<时间戳>.json\"`")
+        )),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "模拟登录会话或 API token 不能导出个人数据", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "当前用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
+    security(("bearer_auth" = []))
+)]
+pub async fn export_current_user_handler(
+    Extension(current_user): Extension<CurrentUser>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<impl IntoResponse, AppError> {
+    let archive = personal_data_export(&state.db, current_user.user_id).await?;
+
+    crate::services::security_events::record(
+        &state.db,
+        crate::services::security_events::SecurityEvent {
+            event_type: crate::services::security_events::PERSONAL_DATA_EXPORTED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({}),
+        },
+    )
+    .await?;
+
+    let filename = format!(
+        "personal-data-{}.json",
+        archive.exported_at.format("%Y%m%dT%H%M%SZ")
+    );
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        )],
+        Json(archive),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    params(ListUsersQuery),
     responses(
-        (status = 200, description = "获取用户列表", body = [UserResponse]),
+        (status = 200, description = "获取用户列表（分页）", body = [UserResponse], headers(
+            ("X-Total-Count" = i64, description = "符合条件的用户总数"),
+            ("Link" = String, description = "RFC 8288 分页链接（first / prev / next / last）")
+        )),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:read`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn get_users_handler(
-    Extension(current_user): Extension<CurrentUser>,
-    Query(query): Query<ListUsersQuery>,
+    RequirePermission(current_user, _): RequirePermission<UsersRead>,
+    uri: Uri,
     State(state): State<AppState>,
-) -> Result<Json<Vec<UserResponse>>, AppError> {
-    ensure_admin(&current_user)?;
-    let users = list_users(&state.db, query.include_deleted).await?;
-    Ok(Json(users))
+    crate::api::validation::ValidatedQuery(query): crate::api::validation::ValidatedQuery<
+        ListUsersQuery,
+    >,
+) -> Result<impl IntoResponse, AppError> {
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_USERS_PER_PAGE);
+    let (users, total) = list_users(&state.db, &query, current_user.organization_id).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-total-count", HeaderValue::from(total));
+    let link = HeaderValue::from_str(&pagination_links(&uri, page, per_page, total))
+        .map_err(|e| AppError::InternalError(format!("生成分页链接失败: {e}")))?;
+    headers.insert(header::LINK, link);
+    Ok((headers, Json(users)))
+}
+
+/// 生成 RFC 8288 `Link` 头；保留原查询参数，仅替换 `page`。
+fn pagination_links(uri: &Uri, page: u32, per_page: u32, total: i64) -> String {
+    let last_page = u32::try_from((total + i64::from(per_page) - 1) / i64::from(per_page))
+        .unwrap_or(u32::MAX)
+        .max(1);
+    let base_query: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some("page"))
+        .collect();
+    let link = |target: u32, rel: &str| {
+        let mut query = base_query.clone();
+        let page_pair = format!("page={target}");
+        query.push(&page_pair);
+        format!("<{}?{}>; rel=\"{rel}\"", uri.path(), query.join("&"))
+    };
+
+    let mut links = vec![link(1, "first")];
+    if page > 1 {
+        links.push(link((page - 1).min(last_page), "prev"));
+    }
+    if page < last_page {
+        links.push(link(page + 1, "next"));
+    }
+    links.push(link(last_page, "last"));
+    links.join(", ")
 }
 
 #[utoipa::path(
@@ -260,164 +808,669 @@ pub async fn get_users_handler(
         (status = 201, description = "创建用户", body = UserResponse),
         (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
 pub async fn create_user_handler(
-    Extension(current_user): Extension<CurrentUser>,
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
     State(state): State<AppState>,
     crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
         CreateUserRequest,
     >,
 ) -> Result<(StatusCode, Json<UserResponse>), AppError> {
-    ensure_admin(&current_user)?;
-    let user = create_user(&state.db, payload).await?;
+    rbac::ensure_role_assignable(
+        &state.db,
+        &current_user,
+        payload.role.as_deref().unwrap_or(rbac::DEFAULT_ROLE),
+    )
+    .await?;
+    let user = create_user(&state.db, payload, current_user.organization_id).await?;
     Ok((StatusCode::CREATED, Json(user)))
 }
 
 #[utoipa::path(
-    patch,
-    path = "/api/v1/users/{user_id}",
+    get,
+    path = "/api/v1/users/export",
     tag = "users",
-    params(("user_id" = Uuid, Path, description = "用户 ID")),
-    request_body = PatchUserRequest,
+    params(ExportUsersQuery),
     responses(
-        (status = 200, description = "更新用户", body = UserResponse),
+        (
+            status = 200,
+            description = "流式导出用户（时间字段为 ISO 8601 / RFC 3339）",
+            content((String = "text/csv"), (String = "application/x-ndjson")),
+            headers(("Content-Disposition" = String, description = "attachment; filename=\"users-<时间>.csv\""))
+        ),
         (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
-        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:read`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
-pub async fn patch_user_handler(
-    Extension(current_user): Extension<CurrentUser>,
-    Path(user_id): Path<Uuid>,
+pub async fn export_users_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersRead>,
     State(state): State<AppState>,
-    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
-        PatchUserRequest,
+    client: ClientInfo,
+    crate::api::validation::ValidatedQuery(query): crate::api::validation::ValidatedQuery<
+        ExportUsersQuery,
     >,
-) -> Result<Json<UserResponse>, AppError> {
-    ensure_admin(&current_user)?;
-    if current_user.user_id == user_id && payload.is_active == Some(false) {
-        return Err(AppError::validation("管理员不能停用自己的账号"));
-    }
-    let user = patch_user(&state.db, user_id, payload).await?;
-    Ok(Json(user))
+) -> Result<impl IntoResponse, AppError> {
+    let columns: Vec<&'static str> = match query.columns.as_deref() {
+        Some(columns) => EXPORT_COLUMNS
+            .into_iter()
+            .filter(|column| columns.split(',').any(|c| c.trim() == *column))
+            .collect(),
+        None => EXPORT_COLUMNS.to_vec(),
+    };
+
+    crate::services::security_events::record(
+        &state.db,
+        crate::services::security_events::SecurityEvent {
+            event_type: crate::services::security_events::USERS_EXPORTED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client: &client,
+            details: serde_json::json!({
+                "format": query.format,
+                "columns": columns,
+                "include_deleted": query.include_deleted,
+                "organization_id": current_user.organization_id,
+            }),
+        },
+    )
+    .await?;
+
+    let (content_type, extension) = match query.format {
+        ExportFormat::Csv => ("text/csv; charset=utf-8", "csv"),
+        ExportFormat::Ndjson => ("application/x-ndjson", "ndjson"),
+    };
+    let disposition = format!(
+        "attachment; filename=\"users-{}.{extension}\"",
+        Utc::now().format("%Y%m%dT%H%M%SZ")
+    );
+    let body = axum::body::Body::from_stream(stream_users_export(
+        state.db.clone(),
+        query,
+        columns,
+        current_user.organization_id,
+    ));
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
 }
 
 #[utoipa::path(
-    delete,
-    path = "/api/v1/users/{user_id}",
+    post,
+    path = "/api/v1/users/import",
     tag = "users",
-    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    params(ImportUsersQuery),
+    request_body(
+        content(
+            (String = "text/csv"),
+            (String = "application/x-ndjson")
+        ),
+        description = "CSV（首行为表头）或 NDJSON（每行一个 `CreateUserRequest`），最多 1000 行"
+    ),
     responses(
-        (status = 204, description = "逻辑删除用户"),
+        (status = 200, description = "逐行导入结果；校验通过的行在同一事务中创建", body = ImportUsersResponse),
+        (status = 400, description = "文件格式错误或超出行数上限", body = crate::api::openapi::ErrorResponseBody),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
-        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
-pub async fn delete_user_handler(
-    Extension(current_user): Extension<CurrentUser>,
-    Path(user_id): Path<Uuid>,
+pub async fn import_users_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
     State(state): State<AppState>,
-) -> Result<StatusCode, AppError> {
-    ensure_admin(&current_user)?;
-    if current_user.user_id == user_id {
-        return Err(AppError::validation("管理员不能删除自己的账号"));
+    headers: HeaderMap,
+    crate::api::validation::ValidatedQuery(query): crate::api::validation::ValidatedQuery<
+        ImportUsersQuery,
+    >,
+    body: axum::body::Bytes,
+) -> Result<Json<ImportUsersResponse>, AppError> {
+    let body = std::str::from_utf8(&body)
+        .map_err(|_| AppError::validation("导入文件必须为 UTF-8 编码"))?;
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let rows = match content_type.as_str() {
+        "text/csv" => parse_import_csv(body)?,
+        "application/x-ndjson" | "application/ndjson" => parse_import_ndjson(body),
+        _ => {
+            return Err(AppError::validation(
+                "导入文件的 Content-Type 须为 text/csv 或 application/x-ndjson",
+            ))
+        }
+    };
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(AppError::validation(format!(
+            "单次最多导入 {MAX_IMPORT_ROWS} 行"
+        )));
     }
-    soft_delete_user(&state.db, user_id).await?;
-    Ok(StatusCode::NO_CONTENT)
+
+    let report = import_users(&state.db, &current_user, rows, query.dry_run).await?;
+    Ok(Json(report))
 }
 
 #[utoipa::path(
-    post,
-    path = "/api/v1/users/{user_id}/restore",
+    get,
+    path = "/api/v1/users/{user_id}",
     tag = "users",
     params(("user_id" = Uuid, Path, description = "用户 ID")),
     responses(
-        (status = 200, description = "恢复逻辑删除用户", body = UserResponse),
+        (status = 200, description = "获取用户详情（包含已逻辑删除用户）", body = UserDetailResponse, headers(
+            ("ETag" = String, description = "资源版本，更新时放入 `If-Match` 做并发校验")
+        )),
         (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:read`）", body = crate::api::openapi::ErrorResponseBody),
         (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
         (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
     ),
     security(("bearer_auth" = []))
 )]
-pub async fn restore_user_handler(
-    Extension(current_user): Extension<CurrentUser>,
+pub async fn get_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersRead>,
     Path(user_id): Path<Uuid>,
     State(state): State<AppState>,
-) -> Result<Json<UserResponse>, AppError> {
-    ensure_admin(&current_user)?;
-    let user = restore_user(&state.db, user_id).await?;
-    Ok(Json(user))
+) -> Result<impl IntoResponse, AppError> {
+    let user = get_user_detail(&state.db, user_id, current_user.organization_id).await?;
+    Ok((etag::header(user.user.updated_at), Json(user)))
 }
 
-#[derive(sqlx::FromRow)]
-struct UserRow {
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/{user_id}",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "用户 ID"),
+        ("If-Match" = Option<String>, Header, description = "上次读取到的 `ETag`；不一致时返回 412")
+    ),
+    request_body(
+        content(
+            (PatchUserRequest = "application/merge-patch+json"),
+            (PatchUserRequest = "application/json")
+        ),
+        description = "RFC 7396 JSON Merge Patch：缺省字段不修改，`null` 清空可置空字段"
+    ),
+    responses(
+        (status = 200, description = "更新用户", body = UserResponse, headers(
+            ("ETag" = String, description = "资源版本，更新时放入 `If-Match` 做并发校验")
+        )),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 412, description = "`If-Match` 与当前版本不一致", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn patch_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    if_match: IfMatch,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        PatchUserRequest,
+    >,
+) -> Result<impl IntoResponse, AppError> {
+    if current_user.user_id == user_id && payload.is_active == Some(false) {
+        return Err(AppError::validation("管理员不能停用自己的账号"));
+    }
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    if let Some(role) = payload.role.as_deref() {
+        rbac::ensure_role_assignable(&state.db, &current_user, role).await?;
+    }
+    let user = patch_user(
+        &state.db,
+        user_id,
+        payload,
+        &current_user,
+        &client,
+        &if_match,
+    )
+    .await?;
+    Ok((etag::header(user.updated_at), Json(user)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/{user_id}",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    responses(
+        (status = 204, description = "逻辑删除用户"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    if current_user.user_id == user_id {
+        return Err(AppError::validation("管理员不能删除自己的账号"));
+    }
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    soft_delete_user(&state.db, user_id, &client).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/restore",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "恢复逻辑删除用户", body = UserResponse),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn restore_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> Result<Json<UserResponse>, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    let user = restore_user(&state.db, user_id, current_user.organization_id).await?;
+    Ok(Json(user))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/purge",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    responses(
+        (status = 204, description = "立即物理删除已逻辑删除的用户（不可恢复）"),
+        (status = 400, description = "用户未被逻辑删除", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn purge_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    purge_user(&state.db, user_id, &current_user, &client).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/unlock",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    responses(
+        (status = 204, description = "解除账号临时锁定并清零登录失败计数"),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn unlock_user_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<StatusCode, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    crate::services::login_throttle::unlock_account(
+        &state.db,
+        user_id,
+        current_user.user_id,
+        &client,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/{user_id}/password",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    request_body = SetUserPasswordRequest,
+    responses(
+        (status = 204, description = "已设置密码（无 body），该用户的全部会话已撤销"),
+        (status = 400, description = "请求参数错误", body = crate::api::openapi::ErrorResponseBody),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn set_user_password_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+    crate::api::validation::ValidatedJson(payload): crate::api::validation::ValidatedJson<
+        SetUserPasswordRequest,
+    >,
+) -> Result<StatusCode, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    set_user_password(
+        &state.db,
+        user_id,
+        payload.password.trim(),
+        payload.must_change,
+        current_user.user_id,
+        &client,
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/{user_id}/temporary-password",
+    tag = "users",
+    params(("user_id" = Uuid, Path, description = "用户 ID")),
+    responses(
+        (status = 200, description = "签发临时密码，用户下次登录必须修改密码；该用户的全部会话已撤销", body = TemporaryPasswordResponse),
+        (status = 401, description = "未登录或 Token 无效", body = crate::api::openapi::ErrorResponseBody),
+        (status = 403, description = "权限不足（需要 `users:write`）", body = crate::api::openapi::ErrorResponseBody),
+        (status = 404, description = "用户不存在", body = crate::api::openapi::ErrorResponseBody),
+        (status = 500, description = "服务器内部错误", body = crate::api::openapi::ErrorResponseBody)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn issue_temporary_password_handler(
+    RequirePermission(current_user, _): RequirePermission<UsersWrite>,
+    Path(user_id): Path<Uuid>,
+    State(state): State<AppState>,
+    client: ClientInfo,
+) -> Result<Json<TemporaryPasswordResponse>, AppError> {
+    rbac::ensure_user_manageable(&state.db, &current_user, user_id).await?;
+    let temporary_password = crate::password::generate_random_password();
+    set_user_password(
+        &state.db,
+        user_id,
+        &temporary_password,
+        true,
+        current_user.user_id,
+        &client,
+    )
+    .await?;
+    Ok(Json(TemporaryPasswordResponse { temporary_password }))
+}
+
+async fn set_user_password(
+    db: &DbPool,
+    user_id: Uuid,
+    password: &str,
+    must_change: bool,
+    set_by: Uuid,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let hash = crate::password::hash_password_argon2id(password)
+        .map_err(|e| AppError::validation(format!("密码不合法: {e}")))?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启设置密码事务失败: {e}")))?;
+
+    crate::services::user_passwords::set_password_and_revoke_sessions(
+        &mut tx,
+        user_id,
+        &hash,
+        must_change,
+        "password_set_by_admin",
+        client,
+    )
+    .await?;
+
+    crate::services::security_events::record(
+        &mut *tx,
+        crate::services::security_events::SecurityEvent {
+            event_type: crate::services::security_events::PASSWORD_SET_BY_ADMIN,
+            user_id: Some(user_id),
+            session_id: None,
+            client,
+            details: serde_json::json!({ "set_by": set_by, "must_change": must_change }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交设置密码事务失败: {e}")))?;
+
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct UserRow {
     id: Uuid,
     username: Option<String>,
     display_name: String,
     email: String,
     phone: Option<String>,
     avatar_url: Option<String>,
+    role: String,
     is_active: bool,
     metadata: serde_json::Value,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
 
-async fn list_users(db: &DbPool, include_deleted: bool) -> Result<Vec<UserResponse>, AppError> {
-    let users: Vec<UserRow> = sqlx::query_as!(
-        UserRow,
-        r#"
-SELECT
-    id,
-    username,
-    display_name,
-    email,
-    phone,
-    avatar_url,
-    is_active,
-    metadata,
-    created_at,
-    updated_at
-FROM users
-WHERE ($1::bool = TRUE OR deleted_at IS NULL)
-ORDER BY created_at DESC
-        "#,
-        include_deleted,
-    )
-    .fetch_all(db)
-    .await
-    .map_err(|e| AppError::InternalError(format!("查询用户列表失败: {e}")))?;
-
-    Ok(users
-        .into_iter()
-        .map(|row| UserResponse {
+impl From<UserRow> for UserResponse {
+    fn from(row: UserRow) -> Self {
+        Self {
             id: row.id,
             username: row.username,
             display_name: row.display_name,
             email: row.email,
             phone: row.phone,
             avatar_url: row.avatar_url,
+            role: row.role,
             is_active: row.is_active,
             metadata: row.metadata,
             created_at: row.created_at,
             updated_at: row.updated_at,
-        })
-        .collect())
+        }
+    }
+}
+
+/// 组织上下文中只返回该组织的成员，`role` 为组织内角色。
+///
+/// 返回当前页数据和符合条件的总数。
+async fn list_users(
+    db: &DbPool,
+    query: &ListUsersQuery,
+    organization_id: Option<Uuid>,
+) -> Result<(Vec<UserResponse>, i64), AppError> {
+    let page = query.page.unwrap_or(1);
+    let per_page = query.per_page.unwrap_or(DEFAULT_USERS_PER_PAGE);
+    let offset = i64::from(page - 1) * i64::from(per_page);
+    let filter = UserFilter {
+        include_deleted: query.include_deleted,
+        organization_id,
+        is_active: query.is_active,
+        role: query.role.as_deref(),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        q: query.q.as_deref(),
+    };
+
+    let total = filtered_users_query("SELECT COUNT(*)", &filter)
+        .build_query_scalar::<i64>()
+        .fetch_one(db)
+        .await
+        .map_err(|e| AppError::InternalError(format!("统计用户数量失败: {e}")))?;
+
+    let mut builder = filtered_users_query(USER_COLUMNS_SELECT, &filter);
+    builder
+        .push("\nORDER BY ")
+        .push(query.sort.unwrap_or_default().order_by())
+        .push("\nLIMIT ")
+        .push_bind(i64::from(per_page))
+        .push("\nOFFSET ")
+        .push_bind(offset);
+    let users: Vec<UserRow> = builder
+        .build_query_as()
+        .fetch_all(db)
+        .await
+        .map_err(|e| AppError::InternalError(format!("查询用户列表失败: {e}")))?;
+
+    Ok((users.into_iter().map(UserResponse::from).collect(), total))
+}
+
+const USER_COLUMNS_SELECT: &str = r#"
+SELECT
+    u.id,
+    u.username,
+    u.display_name,
+    u.email,
+    u.phone,
+    u.avatar_url,
+    COALESCE(m.role, u.role) AS role,
+    u.is_active,
+    u.metadata,
+    u.created_at,
+    u.updated_at"#;
+
+/// 用户列表、计数与导出共用的筛选条件。
+struct UserFilter<'a> {
+    include_deleted: bool,
+    organization_id: Option<Uuid>,
+    is_active: Option<bool>,
+    role: Option<&'a str>,
+    created_from: Option<DateTime<Utc>>,
+    created_to: Option<DateTime<Utc>>,
+    q: Option<&'a str>,
+}
+
+/// 在 `select` 之后拼接 `FROM` 与按筛选条件生成的 `WHERE`，调用方再按需追加排序与分页。
+///
+/// 组织上下文中只匹配该组织成员，`m.role` 为组织内角色。
+fn filtered_users_query<'a>(
+    select: &str,
+    filter: &UserFilter<'_>,
+) -> sqlx::QueryBuilder<'a, sqlx::Postgres> {
+    let mut builder = sqlx::QueryBuilder::new(select);
+    builder
+        .push(
+            r#"
+FROM users u
+LEFT JOIN organization_members m
+       ON m.user_id = u.id
+      AND m.organization_id = "#,
+        )
+        .push_bind(filter.organization_id)
+        .push("\nWHERE TRUE");
+    if !filter.include_deleted {
+        builder.push("\n  AND u.deleted_at IS NULL");
+    }
+    if filter.organization_id.is_some() {
+        builder.push("\n  AND m.user_id IS NOT NULL");
+    }
+    if let Some(is_active) = filter.is_active {
+        builder.push("\n  AND u.is_active = ").push_bind(is_active);
+    }
+    if let Some(role) = filter.role {
+        builder
+            .push("\n  AND COALESCE(m.role, u.role) = ")
+            .push_bind(role.to_string());
+    }
+    if let Some(created_from) = filter.created_from {
+        builder
+            .push("\n  AND u.created_at >= ")
+            .push_bind(created_from);
+    }
+    if let Some(created_to) = filter.created_to {
+        builder
+            .push("\n  AND u.created_at < ")
+            .push_bind(created_to);
+    }
+    if let Some(q) = filter.q {
+        let pattern = like_pattern(q);
+        builder.push("\n  AND (");
+        for (index, column) in ["u.username", "u.display_name", "u.email", "u.phone"]
+            .into_iter()
+            .enumerate()
+        {
+            if index > 0 {
+                builder.push(" OR ");
+            }
+            builder
+                .push(column)
+                .push(" ILIKE ")
+                .push_bind(pattern.clone());
+        }
+        builder.push(")");
+    }
+    builder
+}
+
+/// 把搜索词转成 `ILIKE` 子串匹配模式，转义其中的通配符。
+fn like_pattern(q: &str) -> String {
+    let mut pattern = String::with_capacity(q.len() + 2);
+    pattern.push('%');
+    for ch in q.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
 }
 
-async fn soft_delete_user(db: &DbPool, user_id: Uuid) -> Result<(), AppError> {
+async fn soft_delete_user(db: &DbPool, user_id: Uuid, client: &ClientInfo) -> Result<(), AppError> {
     let mut tx = db
         .begin()
         .await
         .map_err(|e| AppError::InternalError(format!("开启删除用户事务失败: {e}")))?;
 
+    soft_delete_user_in_tx(&mut tx, user_id, client).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交删除用户事务失败: {e}")))?;
+
+    Ok(())
+}
+
+/// 逻辑删除用户并吊销其全部会话，由调用方提交事务。
+async fn soft_delete_user_in_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    ensure_not_last_active_admin(tx, user_id, "删除").await?;
+
     let result = sqlx::query!(
         r#"
 UPDATE users
@@ -431,7 +1484,7 @@ WHERE id = $1
         "#,
         user_id,
     )
-    .execute(&mut *tx)
+    .execute(&mut **tx)
     .await
     .map_err(|e| AppError::InternalError(format!("逻辑删除用户失败: {e}")))?;
 
@@ -439,7 +1492,8 @@ WHERE id = $1
         return Err(AppError::NotFound(format!("用户不存在或已删除: {user_id}")));
     }
 
-    sqlx::query!(
+    let revoked = sqlx::query_as!(
+        RevokedSession,
         r#"
 UPDATE auth_sessions
 SET revoked_at = NOW(),
@@ -447,21 +1501,23 @@ SET revoked_at = NOW(),
     updated_at = NOW()
 WHERE user_id = $1
   AND revoked_at IS NULL
+RETURNING id, user_id, impersonator_id
         "#,
         user_id,
     )
-    .execute(&mut *tx)
+    .fetch_all(&mut **tx)
     .await
     .map_err(|e| AppError::InternalError(format!("删除用户后吊销会话失败: {e}")))?;
-
-    tx.commit()
-        .await
-        .map_err(|e| AppError::InternalError(format!("提交删除用户事务失败: {e}")))?;
+    impersonation::record_ended(tx, &revoked, client, "user_soft_deleted").await?;
 
     Ok(())
 }
 
-async fn restore_user(db: &DbPool, user_id: Uuid) -> Result<UserResponse, AppError> {
+async fn restore_user(
+    db: &DbPool,
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> Result<UserResponse, AppError> {
     let mut tx = db
         .begin()
         .await
@@ -485,12 +1541,22 @@ RETURNING
     email,
     phone,
     avatar_url,
+    COALESCE(
+        (
+            SELECT m.role
+            FROM organization_members m
+            WHERE m.user_id = users.id
+              AND m.organization_id = $2
+        ),
+        role
+    ) AS "role!",
     is_active,
     metadata,
     created_at,
     updated_at
         "#,
         user_id,
+        organization_id,
     )
     .fetch_optional(&mut *tx)
     .await
@@ -516,66 +1582,606 @@ WHERE user_id = $1
         .await
         .map_err(|e| AppError::InternalError(format!("提交恢复用户事务失败: {e}")))?;
 
-    Ok(UserResponse {
-        id: row.id,
-        username: row.username,
-        display_name: row.display_name,
-        email: row.email,
-        phone: row.phone,
-        avatar_url: row.avatar_url,
-        is_active: row.is_active,
-        metadata: row.metadata,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
+    Ok(row.into())
+}
+
+/// 立即物理删除已逻辑删除的用户，不等待保留期；关联数据的处理与后台清除一致。
+async fn purge_user(
+    db: &DbPool,
+    user_id: Uuid,
+    current_user: &CurrentUser,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启清除用户事务失败: {e}")))?;
+
+    let deleted = sqlx::query_scalar!(
+        r#"
+SELECT deleted_at IS NOT NULL AS "deleted!"
+FROM users
+WHERE id = $1
+FOR UPDATE
+        "#,
+        user_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询待清除用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    if !deleted {
+        return Err(AppError::validation("只能清除已删除的用户，请先删除该用户"));
+    }
+
+    crate::services::user_purge::delete_users(&mut tx, &[user_id]).await?;
+
+    crate::services::security_events::record(
+        &mut *tx,
+        crate::services::security_events::SecurityEvent {
+            event_type: crate::services::security_events::USER_PURGED,
+            user_id: Some(current_user.user_id),
+            session_id: current_user.session_id,
+            client,
+            details: serde_json::json!({
+                "purged_user_id": user_id,
+                "reason": "forced",
+                "organization_id": current_user.organization_id,
+            }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交清除用户事务失败: {e}")))?;
+
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct UserExportRow {
+    id: Uuid,
+    username: Option<String>,
+    display_name: String,
+    email: String,
+    phone: Option<String>,
+    avatar_url: Option<String>,
+    role: String,
+    is_active: bool,
+    metadata: serde_json::Value,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    deleted_at: Option<DateTime<Utc>>,
+    last_login_at: Option<DateTime<Utc>>,
+}
+
+impl UserExportRow {
+    fn column(&self, name: &str) -> serde_json::Value {
+        match name {
+            "id" => serde_json::json!(self.id),
+            "username" => serde_json::json!(self.username),
+            "display_name" => serde_json::json!(self.display_name),
+            "email" => serde_json::json!(self.email),
+            "phone" => serde_json::json!(self.phone),
+            "avatar_url" => serde_json::json!(self.avatar_url),
+            "role" => serde_json::json!(self.role),
+            "is_active" => serde_json::json!(self.is_active),
+            "metadata" => self.metadata.clone(),
+            "created_at" => serde_json::json!(self.created_at),
+            "updated_at" => serde_json::json!(self.updated_at),
+            "deleted_at" => serde_json::json!(self.deleted_at),
+            "last_login_at" => serde_json::json!(self.last_login_at),
+            _ => serde_json::Value::Null,
+        }
+    }
+
+    fn encode(&self, format: ExportFormat, columns: &[&str]) -> String {
+        match format {
+            ExportFormat::Csv => {
+                crate::csv::write_record(columns.iter().map(|column| match self.column(column) {
+                    serde_json::Value::Null => String::new(),
+                    serde_json::Value::String(value) => value,
+                    value => value.to_string(),
+                }))
+            }
+            ExportFormat::Ndjson => {
+                let object: serde_json::Map<String, serde_json::Value> = columns
+                    .iter()
+                    .map(|column| (column.to_string(), self.column(column)))
+                    .collect();
+                format!("{}\n", serde_json::Value::Object(object))
+            }
+        }
+    }
+}
+
+/// 后台任务逐行读取并编码，经有界通道交给响应体，不在内存中缓冲整表；
+/// 客户端断开后通道关闭，查询随之停止。
+fn stream_users_export(
+    db: DbPool,
+    query: ExportUsersQuery,
+    columns: Vec<&'static str>,
+    organization_id: Option<Uuid>,
+) -> impl futures_util::Stream<Item = Result<axum::body::Bytes, std::io::Error>> {
+    let (sender, receiver) = tokio::sync::mpsc::channel(64);
+    tokio::spawn(async move {
+        if let Err(e) = write_users_export(&db, &query, &columns, organization_id, &sender).await {
+            tracing::error!(error = %e, "导出用户失败");
+            let _ = sender.send(Err(std::io::Error::other(e.to_string()))).await;
+        }
+    });
+    futures_util::stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
     })
 }
 
-async fn get_user_by_id(db: &DbPool, user_id: Uuid) -> Result<UserResponse, AppError> {
+async fn write_users_export(
+    db: &DbPool,
+    query: &ExportUsersQuery,
+    columns: &[&str],
+    organization_id: Option<Uuid>,
+    sender: &tokio::sync::mpsc::Sender<Result<axum::body::Bytes, std::io::Error>>,
+) -> Result<(), AppError> {
+    if let ExportFormat::Csv = query.format {
+        let header = crate::csv::write_record(columns);
+        if sender.send(Ok(header.into())).await.is_err() {
+            return Ok(());
+        }
+    }
+
+    let filter = UserFilter {
+        include_deleted: query.include_deleted,
+        organization_id,
+        is_active: query.is_active,
+        role: query.role.as_deref(),
+        created_from: query.created_from,
+        created_to: query.created_to,
+        q: query.q.as_deref(),
+    };
+    let select = format!("{USER_COLUMNS_SELECT},\n    u.deleted_at,\n    u.last_login_at");
+    let mut builder = filtered_users_query(&select, &filter);
+    builder
+        .push("\nORDER BY ")
+        .push(query.sort.unwrap_or_default().order_by());
+    let mut rows = builder.build_query_as::<UserExportRow>().fetch(db);
+
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .map_err(|e| AppError::InternalError(format!("读取导出用户失败: {e}")))?
+    {
+        let chunk = row.encode(query.format, columns);
+        if sender.send(Ok(chunk.into())).await.is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+type ImportRow = (usize, Result<CreateUserRequest, AppError>);
+
+fn parse_import_ndjson(body: &str) -> Vec<ImportRow> {
+    body.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let row = serde_json::from_str::<CreateUserRequest>(line)
+                .map_err(|e| AppError::validation(format!("JSON 不合法: {e}")));
+            (index + 1, row)
+        })
+        .collect()
+}
+
+fn parse_import_csv(body: &str) -> Result<Vec<ImportRow>, AppError> {
+    let mut records = crate::csv::parse(body)
+        .map_err(|e| AppError::validation(format!("CSV 不合法: {e}")))?
+        .into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let columns: Vec<String> = header
+        .fields
+        .iter()
+        .map(|column| column.trim().to_ascii_lowercase())
+        .collect();
+    for (index, column) in columns.iter().enumerate() {
+        if !IMPORT_CSV_COLUMNS.contains(&column.as_str()) {
+            return Err(AppError::validation(format!(
+                "CSV 表头包含未知列: {column}"
+            )));
+        }
+        if columns[..index].contains(column) {
+            return Err(AppError::validation(format!("CSV 表头列重复: {column}")));
+        }
+    }
+
+    Ok(records
+        .map(|record| (record.line, csv_record_to_request(&columns, record.fields)))
+        .collect())
+}
+
+fn csv_record_to_request(
+    columns: &[String],
+    fields: Vec<String>,
+) -> Result<CreateUserRequest, AppError> {
+    if fields.len() != columns.len() {
+        return Err(AppError::validation(format!(
+            "列数与表头不一致：期望 {}，实际 {}",
+            columns.len(),
+            fields.len()
+        )));
+    }
+    let mut object = serde_json::Map::new();
+    for (column, value) in columns.iter().zip(fields) {
+        if value.trim().is_empty() {
+            continue;
+        }
+        let value = if column == "metadata" {
+            serde_json::from_str(&value)
+                .map_err(|e| AppError::validation(format!("metadata 不是合法 JSON: {e}")))?
+        } else {
+            serde_json::Value::String(value)
+        };
+        object.insert(column.clone(), value);
+    }
+    serde_json::from_value(serde_json::Value::Object(object))
+        .map_err(|e| AppError::validation(format!("字段不合法: {e}")))
+}
+
+/// 逐行校验并在同一事务中创建用户：每行使用独立 savepoint，失败行回滚后继续处理后续行；
+/// dry-run 时同样执行插入以命中唯一索引，最后整体回滚。
+async fn import_users(
+    db: &DbPool,
+    current_user: &CurrentUser,
+    rows: Vec<ImportRow>,
+    dry_run: bool,
+) -> Result<ImportUsersResponse, AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启导入用户事务失败: {e}")))?;
+
+    let mut seen = std::collections::HashMap::<(&'static str, String), usize>::new();
+    let mut results = Vec::with_capacity(rows.len());
+    for (line, payload) in rows {
+        let email = payload.as_ref().ok().map(|payload| payload.email.clone());
+        let outcome = match payload {
+            Ok(payload) => import_user_row(&mut tx, current_user, &mut seen, line, payload).await,
+            Err(err) => Err(err),
+        };
+        let result = match outcome {
+            Ok(user_id) => ImportUserRowResult {
+                line,
+                email,
+                user_id: Some(user_id).filter(|_| !dry_run),
+                error: None,
+                details: None,
+            },
+            Err(err @ (AppError::InternalError(_) | AppError::Unknown(_))) => return Err(err),
+            Err(AppError::ValidationError { message, details }) => ImportUserRowResult {
+                line,
+                email,
+                user_id: None,
+                error: Some(message),
+                details,
+            },
+            Err(err) => ImportUserRowResult {
+                line,
+                email,
+                user_id: None,
+                error: Some(err.to_string()),
+                details: None,
+            },
+        };
+        results.push(result);
+    }
+
+    if dry_run {
+        tx.rollback()
+            .await
+            .map_err(|e| AppError::InternalError(format!("回滚导入用户事务失败: {e}")))?;
+    } else {
+        tx.commit()
+            .await
+            .map_err(|e| AppError::InternalError(format!("提交导入用户事务失败: {e}")))?;
+    }
+
+    let failed = results.iter().filter(|row| row.error.is_some()).count();
+    Ok(ImportUsersResponse {
+        dry_run,
+        total: results.len(),
+        succeeded: results.len() - failed,
+        failed,
+        rows: results,
+    })
+}
+
+async fn import_user_row(
+    tx: &mut PgConnection,
+    current_user: &CurrentUser,
+    seen: &mut std::collections::HashMap<(&'static str, String), usize>,
+    line: usize,
+    payload: CreateUserRequest,
+) -> Result<Uuid, AppError> {
+    payload
+        .validate()
+        .map_err(|report| AppError::from_garde_report("字段校验失败", report))?;
+
+    let keys = [
+        ("username", payload.username.clone()),
+        ("email", Some(payload.email.clone())),
+        ("phone", payload.phone.clone()),
+    ];
+    for (field, value) in keys.iter() {
+        if let Some(&first_line) = value.as_ref().and_then(|v| seen.get(&(*field, v.clone()))) {
+            return Err(AppError::validation(format!(
+                "{field} 与第 {first_line} 行重复"
+            )));
+        }
+    }
+
+    rbac::ensure_role_assignable(
+        &mut *tx,
+        current_user,
+        payload.role.as_deref().unwrap_or(rbac::DEFAULT_ROLE),
+    )
+    .await?;
+
+    let mut savepoint = tx
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("创建导入 savepoint 失败: {e}")))?;
+    let row = insert_user(&mut savepoint, payload, current_user.organization_id).await?;
+    savepoint
+        .commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("释放导入 savepoint 失败: {e}")))?;
+
+    for (field, value) in keys {
+        if let Some(value) = value {
+            seen.insert((field, value), line);
+        }
+    }
+    Ok(row.id)
+}
+
+/// 组织上下文中只能查到该组织的成员，`role` 为组织内角色；已逻辑删除的用户同样返回。
+async fn get_user_by_id(
+    db: &DbPool,
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> Result<UserResponse, AppError> {
     let row = sqlx::query_as!(
         UserRow,
         r#"
 SELECT
-    id,
-    username,
-    display_name,
-    email,
-    phone,
-    avatar_url,
-    is_active,
-    metadata,
-    created_at,
-    updated_at
-FROM users
-WHERE id = $1
+    u.id,
+    u.username,
+    u.display_name,
+    u.email,
+    u.phone,
+    u.avatar_url,
+    COALESCE(m.role, u.role) AS "role!",
+    u.is_active,
+    u.metadata,
+    u.created_at,
+    u.updated_at
+FROM users u
+LEFT JOIN organization_members m
+       ON m.user_id = u.id
+      AND m.organization_id = $2
+WHERE u.id = $1
+  AND ($2::uuid IS NULL OR m.user_id IS NOT NULL)
 LIMIT 1
         "#,
         user_id,
+        organization_id,
     )
     .fetch_optional(db)
     .await
     .map_err(|e| AppError::InternalError(format!("查询用户失败: {e}")))?
     .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
 
-    Ok(UserResponse {
-        id: row.id,
-        username: row.username,
-        display_name: row.display_name,
-        email: row.email,
-        phone: row.phone,
-        avatar_url: row.avatar_url,
-        is_active: row.is_active,
-        metadata: row.metadata,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
+    Ok(row.into())
+}
+
+async fn personal_data_export(db: &DbPool, user_id: Uuid) -> Result<PersonalDataExport, AppError> {
+    let profile = get_user_detail(db, user_id, None).await?;
+    let mfa_enabled = crate::services::mfa::is_totp_enabled(db, user_id).await?;
+
+    let organizations = sqlx::query_as!(
+        PersonalDataOrganization,
+        r#"
+SELECT
+    o.id AS organization_id,
+    o.slug,
+    o.name,
+    m.role,
+    m.created_at AS joined_at
+FROM organization_members m
+INNER JOIN organizations o ON o.id = m.organization_id
+WHERE m.user_id = $1
+ORDER BY m.created_at
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询个人组织成员关系失败: {e}")))?;
+
+    let groups = sqlx::query_as!(
+        PersonalDataGroup,
+        r#"
+SELECT
+    g.id AS group_id,
+    g.name,
+    m.created_at AS joined_at
+FROM user_group_members m
+INNER JOIN user_groups g ON g.id = m.group_id
+WHERE m.user_id = $1
+ORDER BY m.created_at
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询个人用户组失败: {e}")))?;
+
+    let sessions = sqlx::query_as!(
+        PersonalDataSession,
+        r#"
+SELECT
+    id,
+    created_at,
+    expires_at,
+    revoked_at,
+    revoked_reason,
+    created_ip,
+    created_user_agent,
+    last_ip,
+    last_user_agent,
+    last_refreshed_at,
+    impersonator_id
+FROM auth_sessions
+WHERE user_id = $1
+ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询个人会话失败: {e}")))?;
+
+    let api_tokens = sqlx::query_as!(
+        PersonalDataApiToken,
+        r#"
+SELECT
+    id,
+    name,
+    scopes,
+    expires_at,
+    last_used_at,
+    last_used_ip,
+    last_used_user_agent,
+    revoked_at,
+    created_at
+FROM api_tokens
+WHERE user_id = $1
+ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询个人 API token 失败: {e}")))?;
+
+    let security_events = sqlx::query_as!(
+        PersonalDataSecurityEvent,
+        r#"
+SELECT
+    event_type,
+    session_id,
+    client_ip,
+    user_agent,
+    details,
+    created_at
+FROM security_events
+WHERE user_id = $1
+ORDER BY created_at DESC
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询个人安全事件失败: {e}")))?;
+
+    Ok(PersonalDataExport {
+        exported_at: Utc::now(),
+        profile,
+        mfa_enabled,
+        organizations,
+        groups,
+        sessions,
+        api_tokens,
+        security_events,
     })
 }
 
-async fn create_user(db: &DbPool, payload: CreateUserRequest) -> Result<UserResponse, AppError> {
+async fn get_user_detail(
+    db: &DbPool,
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> Result<UserDetailResponse, AppError> {
+    let user = get_user_by_id(db, user_id, organization_id).await?;
+
+    let row = sqlx::query!(
+        r#"
+SELECT
+    u.deleted_at,
+    u.last_login_at,
+    (
+        SELECT COUNT(*)
+        FROM auth_sessions s
+        WHERE s.user_id = u.id
+          AND s.revoked_at IS NULL
+          AND s.expires_at > NOW()
+    ) AS "active_session_count!"
+FROM users u
+WHERE u.id = $1
+        "#,
+        user_id,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户登录信息失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+
+    Ok(UserDetailResponse {
+        user,
+        deleted_at: row.deleted_at,
+        last_login_at: row.last_login_at,
+        active_session_count: row.active_session_count,
+    })
+}
+
+/// 组织上下文中新用户以默认全局角色创建，并以 `role` 作为组织内角色加入该组织。
+async fn create_user(
+    db: &DbPool,
+    payload: CreateUserRequest,
+    organization_id: Option<Uuid>,
+) -> Result<UserResponse, AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启创建用户事务失败: {e}")))?;
+
+    let row = insert_user(&mut tx, payload, organization_id).await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交创建用户事务失败: {e}")))?;
+
+    Ok(row.into())
+}
+
+async fn insert_user(
+    conn: &mut PgConnection,
+    payload: CreateUserRequest,
+    organization_id: Option<Uuid>,
+) -> Result<UserRow, AppError> {
     if let Some(username) = payload.username.as_deref() {
-        ensure_username_not_conflicts_with_other_user_contacts(db, username, None).await?;
+        ensure_username_not_conflicts_with_other_user_contacts(&mut *conn, username, None).await?;
     }
 
-    let row: UserRow = sqlx::query_as!(
+    let role = payload.role.as_deref().unwrap_or(rbac::DEFAULT_ROLE);
+    let global_role = if organization_id.is_some() {
+        rbac::DEFAULT_ROLE
+    } else {
+        role
+    };
+
+    let mut row: UserRow = sqlx::query_as!(
         UserRow,
         r#"
 INSERT INTO users (
@@ -584,9 +2190,10 @@ INSERT INTO users (
     email,
     phone,
     avatar_url,
+    role,
     metadata
 )
-VALUES ($1, $2, $3, $4, $5, $6)
+VALUES ($1, $2, $3, $4, $5, $6, $7)
 RETURNING
     id,
     username,
@@ -594,6 +2201,7 @@ RETURNING
     email,
     phone,
     avatar_url,
+    role,
     is_active,
     metadata,
     created_at,
@@ -604,58 +2212,112 @@ RETURNING
         payload.email,
         payload.phone,
         payload.avatar_url,
+        global_role,
         payload.metadata.unwrap_or_else(|| serde_json::json!({})),
     )
-    .fetch_one(db)
+    .fetch_one(&mut *conn)
     .await
     .map_err(|e| map_user_db_error("创建用户失败", e))?;
 
-    Ok(UserResponse {
-        id: row.id,
-        username: row.username,
-        display_name: row.display_name,
-        email: row.email,
-        phone: row.phone,
-        avatar_url: row.avatar_url,
-        is_active: row.is_active,
-        metadata: row.metadata,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-    })
+    if let Some(organization_id) = organization_id {
+        sqlx::query!(
+            r#"
+INSERT INTO organization_members (organization_id, user_id, role)
+VALUES ($1, $2, $3)
+            "#,
+            organization_id,
+            row.id,
+            role,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| map_user_db_error("加入组织失败", e))?;
+        row.role = role.to_string();
+    }
+
+    Ok(row)
 }
 
 async fn patch_user(
     db: &DbPool,
     user_id: Uuid,
     payload: PatchUserRequest,
+    current_user: &CurrentUser,
+    client: &ClientInfo,
+    if_match: &IfMatch,
 ) -> Result<UserResponse, AppError> {
     if payload.username.is_none()
         && payload.display_name.is_none()
         && payload.email.is_none()
         && payload.phone.is_none()
         && payload.avatar_url.is_none()
+        && payload.role.is_none()
         && payload.is_active.is_none()
         && payload.metadata.is_none()
     {
         return Err(AppError::validation("至少需要提供一个可更新字段"));
     }
 
-    if let Some(username) = payload.username.as_deref() {
+    if let Some(Some(username)) = &payload.username {
         ensure_username_not_conflicts_with_other_user_contacts(db, username, Some(user_id)).await?;
     }
 
-    let row = sqlx::query_as!(
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启更新用户事务失败: {e}")))?;
+
+    let previous = sqlx::query!(
+        "SELECT role, metadata, updated_at FROM users WHERE id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    if_match.ensure(previous.updated_at, "用户")?;
+    let previous_role = previous.role;
+    let metadata = payload.metadata.map(|patch| {
+        let mut metadata = previous.metadata;
+        crate::api::merge_patch::apply(&mut metadata, patch);
+        match metadata {
+            serde_json::Value::Null => serde_json::json!({}),
+            metadata => metadata,
+        }
+    });
+
+    // 组织上下文中 `role` 修改的是组织内角色，全局角色保持不变。
+    let organization_id = current_user.organization_id;
+    let (global_role, member_role) = match organization_id {
+        Some(_) => (None, payload.role),
+        None => (payload.role, None),
+    };
+    let role_changed = global_role
+        .as_deref()
+        .is_some_and(|role| role != previous_role);
+    if previous_role == rbac::ADMIN_ROLE {
+        if role_changed {
+            ensure_not_last_active_admin(&mut tx, user_id, "降级").await?;
+        }
+        if payload.is_active == Some(false) {
+            ensure_not_last_active_admin(&mut tx, user_id, "停用").await?;
+        }
+    }
+
+    let mut row = sqlx::query_as!(
         UserRow,
         r#"
 UPDATE users
 SET
-    username = COALESCE($2, username),
+    username = CASE WHEN $12 THEN $2 ELSE username END,
     display_name = COALESCE($3, display_name),
     email = COALESCE($4, email),
-    phone = COALESCE($5, phone),
-    avatar_url = COALESCE($6, avatar_url),
+    phone = CASE WHEN $13 THEN $5 ELSE phone END,
+    avatar_url = CASE WHEN $14 THEN $6 ELSE avatar_url END,
     is_active = COALESCE($7, is_active),
     metadata = COALESCE($8, metadata),
+    role = COALESCE($9, role),
+    auth_version = CASE WHEN $10 THEN auth_version + 1 ELSE auth_version END,
     updated_at = NOW()
 WHERE id = $1
 RETURNING
@@ -665,43 +2327,107 @@ RETURNING
     email,
     phone,
     avatar_url,
+    COALESCE(
+        (
+            SELECT m.role
+            FROM organization_members m
+            WHERE m.user_id = users.id
+              AND m.organization_id = $11
+        ),
+        role
+    ) AS "role!",
     is_active,
     metadata,
     created_at,
     updated_at
         "#,
         user_id,
-        payload.username,
+        payload.username.clone().flatten(),
         payload.display_name,
         payload.email,
-        payload.phone,
-        payload.avatar_url,
+        payload.phone.clone().flatten(),
+        payload.avatar_url.clone().flatten(),
         payload.is_active,
-        payload.metadata,
+        metadata,
+        global_role,
+        role_changed,
+        organization_id,
+        payload.username.is_some(),
+        payload.phone.is_some(),
+        payload.avatar_url.is_some(),
     )
-    .fetch_optional(db)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| map_user_db_error("更新用户失败", e))?
-    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    .map_err(|e| map_user_db_error("更新用户失败", e))?;
 
-    Ok(UserResponse {
-        id: row.id,
-        username: row.username,
-        display_name: row.display_name,
-        email: row.email,
-        phone: row.phone,
-        avatar_url: row.avatar_url,
-        is_active: row.is_active,
-        metadata: row.metadata,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-    })
+    if role_changed {
+        crate::services::security_events::record(
+            &mut *tx,
+            crate::services::security_events::SecurityEvent {
+                event_type: crate::services::security_events::USER_ROLE_CHANGED,
+                user_id: Some(user_id),
+                session_id: None,
+                client,
+                details: serde_json::json!({
+                    "from": previous_role,
+                    "to": row.role,
+                    "changed_by": current_user.user_id,
+                }),
+            },
+        )
+        .await?;
+    }
+
+    if let (Some(organization_id), Some(role)) = (organization_id, member_role) {
+        if role != row.role {
+            sqlx::query!(
+                r#"
+UPDATE organization_members
+SET role = $3,
+    updated_at = NOW()
+WHERE organization_id = $1
+  AND user_id = $2
+                "#,
+                organization_id,
+                user_id,
+                role,
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| map_user_db_error("更新组织内角色失败", e))?;
+
+            crate::services::security_events::record(
+                &mut *tx,
+                crate::services::security_events::SecurityEvent {
+                    event_type: crate::services::security_events::USER_ROLE_CHANGED,
+                    user_id: Some(user_id),
+                    session_id: None,
+                    client,
+                    details: serde_json::json!({
+                        "from": row.role,
+                        "to": role,
+                        "changed_by": current_user.user_id,
+                        "organization_id": organization_id,
+                    }),
+                },
+            )
+            .await?;
+            row.role = role;
+        }
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交更新用户事务失败: {e}")))?;
+
+    Ok(row.into())
 }
 
 async fn patch_current_user(
     db: &DbPool,
     user_id: Uuid,
     payload: PatchCurrentUserRequest,
+    if_match: &IfMatch,
 ) -> Result<UserResponse, AppError> {
     if payload.display_name.is_none()
         && payload.email.is_none()
@@ -711,6 +2437,21 @@ async fn patch_current_user(
         return Err(AppError::validation("至少需要提供一个可更新字段"));
     }
 
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启更新当前用户事务失败: {e}")))?;
+
+    let updated_at = sqlx::query_scalar!(
+        "SELECT updated_at FROM users WHERE id = $1 FOR UPDATE",
+        user_id
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询当前用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    if_match.ensure(updated_at, "用户")?;
+
     let row = sqlx::query_as!(
         UserRow,
         r#"
@@ -718,8 +2459,8 @@ UPDATE users
 SET
     display_name = COALESCE($2, display_name),
     email = COALESCE($3, email),
-    phone = COALESCE($4, phone),
-    avatar_url = COALESCE($5, avatar_url),
+    phone = CASE WHEN $6 THEN $4 ELSE phone END,
+    avatar_url = CASE WHEN $7 THEN $5 ELSE avatar_url END,
     updated_at = NOW()
 WHERE id = $1
 RETURNING
@@ -729,6 +2470,7 @@ RETURNING
     email,
     phone,
     avatar_url,
+    role,
     is_active,
     metadata,
     created_at,
@@ -737,33 +2479,61 @@ RETURNING
         user_id,
         payload.display_name,
         payload.email,
-        payload.phone,
-        payload.avatar_url,
+        payload.phone.clone().flatten(),
+        payload.avatar_url.clone().flatten(),
+        payload.phone.is_some(),
+        payload.avatar_url.is_some(),
     )
-    .fetch_optional(db)
+    .fetch_one(&mut *tx)
     .await
-    .map_err(|e| map_user_db_error("更新当前用户失败", e))?
-    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    .map_err(|e| map_user_db_error("更新当前用户失败", e))?;
 
-    Ok(UserResponse {
-        id: row.id,
-        username: row.username,
-        display_name: row.display_name,
-        email: row.email,
-        phone: row.phone,
-        avatar_url: row.avatar_url,
-        is_active: row.is_active,
-        metadata: row.metadata,
-        created_at: row.created_at,
-        updated_at: row.updated_at,
-    })
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交更新当前用户事务失败: {e}")))?;
+
+    Ok(row.into())
 }
 
-async fn ensure_username_not_conflicts_with_other_user_contacts(
-    db: &DbPool,
+/// 目标用户是唯一一个启用中的管理员时拒绝降级/停用/删除，避免系统失去管理员。
+///
+/// 锁住全部启用中的管理员行，保证并发操作两个管理员时不会同时通过检查。
+async fn ensure_not_last_active_admin(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    action: &str,
+) -> Result<(), AppError> {
+    let admin_ids = sqlx::query_scalar!(
+        r#"
+SELECT id
+FROM users
+WHERE role = 'admin'
+  AND is_active = TRUE
+  AND deleted_at IS NULL
+ORDER BY id
+FOR UPDATE
+        "#
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询管理员失败: {e}")))?;
+
+    if admin_ids == [user_id] {
+        return Err(AppError::validation(format!(
+            "不能{action}最后一个启用中的管理员"
+        )));
+    }
+    Ok(())
+}
+
+async fn ensure_username_not_conflicts_with_other_user_contacts<'e, E>(
+    executor: E,
     username: &str,
     exclude_user_id: Option<Uuid>,
-) -> Result<(), AppError> {
+) -> Result<(), AppError>
+where
+    E: PgExecutor<'e>,
+{
     let conflict = sqlx::query!(
         r#"
 SELECT EXISTS (
@@ -777,7 +2547,7 @@ SELECT EXISTS (
         username,
         exclude_user_id,
     )
-    .fetch_one(db)
+    .fetch_one(executor)
     .await
     .map_err(|e| AppError::InternalError(format!("检查用户名冲突失败: {e}")))?;
 
@@ -795,6 +2565,7 @@ fn map_user_db_error(prefix: &str, err: sqlx::Error) -> AppError {
         if let Some(code) = db_err.code().as_deref() {
             return match code {
                 "23505" => AppError::validation(format!("{prefix}: 数据已存在或重复绑定")),
+                "23503" => AppError::validation(format!("{prefix}: 关联数据不存在")),
                 "23514" => AppError::validation(format!("{prefix}: 字段约束校验失败")),
                 _ => AppError::InternalError(format!("{prefix}: {err}")),
             };
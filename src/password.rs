@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::rand_core::{OsRng, RngCore};
 use argon2::password_hash::SaltString;
 use argon2::{Argon2, PasswordHash, PasswordHasher, PasswordVerifier};
 
@@ -18,3 +18,10 @@ pub fn verify_password(password: &str, phc_hash: &str) -> Result<bool> {
     let argon2 = Argon2::default();
     Ok(argon2.verify_password(password.as_bytes(), &parsed).is_ok())
 }
+
+/// 生成随机密码（32 位 hex），用于初始化管理员与管理员签发的临时密码。
+pub fn generate_random_password() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    crate::config::seed::hex_encode(&bytes)
+}
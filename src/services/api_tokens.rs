@@ -0,0 +1,300 @@
+use axum::http::Method;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::db::DbPool;
+use crate::error::AppError;
+
+/// 个人访问令牌前缀；`Authorization: Bearer pat_...` 走 PAT 鉴权，其余按 JWT 处理。
+pub const TOKEN_PREFIX: &str = "pat_";
+
+/// PAT 授权范围：按资源区分读写，`GET`/`HEAD` 需要 `:read`，其余方法需要 `:write`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum ApiTokenScope {
+    #[serde(rename = "users:read")]
+    UsersRead,
+    #[serde(rename = "users:write")]
+    UsersWrite,
+    #[serde(rename = "settings:read")]
+    SettingsRead,
+    #[serde(rename = "settings:write")]
+    SettingsWrite,
+}
+
+impl ApiTokenScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::UsersRead => "users:read",
+            Self::UsersWrite => "users:write",
+            Self::SettingsRead => "settings:read",
+            Self::SettingsWrite => "settings:write",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "users:read" => Some(Self::UsersRead),
+            "users:write" => Some(Self::UsersWrite),
+            "settings:read" => Some(Self::SettingsRead),
+            "settings:write" => Some(Self::SettingsWrite),
+            _ => None,
+        }
+    }
+
+    /// 访问 `method path` 所需的 scope；`None` 表示 PAT 不能访问该接口
+    /// （安全设置、会话管理、注销账号与个人数据导出只接受密码登录签发的会话）。
+    pub fn required_for(method: &Method, path: &str) -> Option<Self> {
+        let read = *method == Method::GET || *method == Method::HEAD;
+        if (*method == Method::DELETE && path == "/api/v1/users/me")
+            || path == "/api/v1/users/me/export"
+        {
+            return None;
+        }
+        if path == "/api/v1/users" || path.starts_with("/api/v1/users/") {
+            return Some(if read {
+                Self::UsersRead
+            } else {
+                Self::UsersWrite
+            });
+        }
+        if path == "/api/v1/settings" {
+            return Some(if read {
+                Self::SettingsRead
+            } else {
+                Self::SettingsWrite
+            });
+        }
+        None
+    }
+}
+
+pub fn parse_scopes(scopes: &[String]) -> Vec<ApiTokenScope> {
+    scopes
+        .iter()
+        .filter_map(|s| ApiTokenScope::parse(s))
+        .collect()
+}
+
+pub fn is_api_token(token: &str) -> bool {
+    token.starts_with(TOKEN_PREFIX)
+}
+
+/// 新生成的令牌：`token` 为明文，只在创建响应中返回一次。
+pub struct GeneratedApiToken {
+    pub id: Uuid,
+    pub token: String,
+    pub secret_hash: String,
+}
+
+pub fn generate() -> Result<GeneratedApiToken, AppError> {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+    let id = Uuid::new_v4();
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let secret = crate::config::seed::hex_encode(&bytes);
+    let secret_hash = secret_digest(&secret);
+
+    Ok(GeneratedApiToken {
+        id,
+        token: format!("{TOKEN_PREFIX}{}_{secret}", id.simple()),
+        secret_hash,
+    })
+}
+
+/// 密钥为 256 位随机数，无需慢哈希；存 SHA-256（hex），每次请求只需一次摘要与常量时间比较。
+fn secret_digest(secret: &str) -> String {
+    crate::config::seed::hex_encode(&Sha256::digest(secret.as_bytes()))
+}
+
+/// 校验密钥；早期令牌以 Argon2id PHC 存储，放到阻塞线程池中校验，避免占用异步 worker。
+async fn verify_secret(secret: &str, secret_hash: &str) -> Result<bool, AppError> {
+    if !secret_hash.starts_with("$argon2") {
+        return Ok(crate::crypto::constant_time_eq(
+            secret_digest(secret).as_bytes(),
+            secret_hash.as_bytes(),
+        ));
+    }
+    let secret = secret.to_string();
+    let secret_hash = secret_hash.to_string();
+    tokio::task::spawn_blocking(move || crate::password::verify_password(&secret, &secret_hash))
+        .await
+        .map_err(|e| AppError::InternalError(format!("API token 校验失败: {e}")))?
+        .map_err(|e| AppError::InternalError(format!("API token 校验失败: {e}")))
+}
+
+fn parse_token(token: &str) -> Option<(Uuid, &str)> {
+    let (id, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('_')?;
+    let id = Uuid::try_parse(id).ok()?;
+    if secret.is_empty() {
+        return None;
+    }
+    Some((id, secret))
+}
+
+/// PAT 鉴权结果。
+#[derive(Debug, Clone)]
+pub struct ApiTokenPrincipal {
+    pub token_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub permissions: Vec<String>,
+    pub scopes: Vec<ApiTokenScope>,
+    /// 令牌绑定的组织；鉴权后自动进入该组织上下文。
+    pub organization_id: Option<Uuid>,
+    pub mfa_enabled: bool,
+    pub password_must_change: bool,
+}
+
+/// 校验 PAT 并记录最近使用时间。
+pub async fn authenticate(
+    db: &DbPool,
+    token: &str,
+    client: &ClientInfo,
+) -> Result<ApiTokenPrincipal, AppError> {
+    let (token_id, secret) =
+        parse_token(token).ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
+
+    let row = sqlx::query!(
+        r#"
+SELECT
+    t.user_id,
+    t.secret_hash,
+    t.scopes,
+    t.expires_at,
+    t.organization_id,
+    u.role,
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = u.role
+        UNION
+        SELECT gp.permission
+        FROM user_group_members gm
+        INNER JOIN user_group_permissions gp ON gp.group_id = gm.group_id
+        WHERE gm.user_id = u.id
+        ORDER BY 1
+    ) AS "permissions!",
+    u.password_must_change,
+    EXISTS (
+        SELECT 1
+        FROM user_totp ut
+        WHERE ut.user_id = t.user_id
+          AND ut.confirmed_at IS NOT NULL
+    ) AS "mfa_enabled!"
+FROM api_tokens t
+INNER JOIN users u ON u.id = t.user_id
+WHERE t.id = $1
+  AND t.revoked_at IS NULL
+  AND u.is_active = TRUE
+LIMIT 1
+        "#,
+        token_id,
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询 API token 失败: {e}")))?
+    .ok_or_else(|| AppError::auth_token("Token 无效或已过期"))?;
+
+    if row
+        .expires_at
+        .is_some_and(|expires_at| expires_at <= Utc::now())
+    {
+        return Err(AppError::auth_token("Token 无效或已过期"));
+    }
+
+    if !verify_secret(secret, &row.secret_hash).await? {
+        return Err(AppError::auth_token("Token 无效或已过期"));
+    }
+
+    touch_last_used(db, token_id, client).await?;
+
+    Ok(ApiTokenPrincipal {
+        token_id,
+        user_id: row.user_id,
+        role: row.role,
+        permissions: row.permissions,
+        scopes: parse_scopes(&row.scopes),
+        organization_id: row.organization_id,
+        mfa_enabled: row.mfa_enabled,
+        password_must_change: row.password_must_change,
+    })
+}
+
+/// 最近使用信息至多每分钟写一次，避免高频脚本放大写压力。
+async fn touch_last_used(db: &DbPool, token_id: Uuid, client: &ClientInfo) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+UPDATE api_tokens
+SET last_used_at = NOW(),
+    last_used_ip = $2,
+    last_used_user_agent = $3
+WHERE id = $1
+  AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')
+        "#,
+        token_id,
+        client.ip,
+        client.user_agent,
+    )
+    .execute(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("更新 API token 使用时间失败: {e}")))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn verify_secret_should_accept_digest_and_legacy_argon2_hashes() {
+        let secret = "0123456789abcdef";
+        assert!(verify_secret(secret, &secret_digest(secret)).await.unwrap());
+        assert!(!verify_secret("wrong", &secret_digest(secret))
+            .await
+            .unwrap());
+
+        let legacy = crate::password::hash_password_argon2id(secret).unwrap();
+        assert!(verify_secret(secret, &legacy).await.unwrap());
+        assert!(!verify_secret("wrong", &legacy).await.unwrap());
+    }
+
+    #[test]
+    fn generated_token_should_round_trip_and_map_routes_to_scopes() {
+        let generated = generate().expect("生成 API token 失败");
+        assert!(is_api_token(&generated.token));
+        let (id, secret) = parse_token(&generated.token).expect("应能解析生成的 token");
+        assert_eq!(id, generated.id);
+        assert_eq!(generated.secret_hash, secret_digest(secret));
+        assert_eq!(generated.secret_hash.len(), 64);
+
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/v1/users/me"),
+            Some(ApiTokenScope::UsersRead)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::PATCH, "/api/v1/settings"),
+            Some(ApiTokenScope::SettingsWrite)
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/v1/security/tokens"),
+            None
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/v1/sessions"),
+            None
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::DELETE, "/api/v1/users/me"),
+            None
+        );
+        assert_eq!(
+            ApiTokenScope::required_for(&Method::GET, "/api/v1/users/me/export"),
+            None
+        );
+    }
+}
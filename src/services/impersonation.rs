@@ -0,0 +1,97 @@
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::services::security_events::{self, SecurityEvent};
+
+/// 被撤销的会话；`impersonator_id` 不为空表示模拟登录会话。
+#[derive(Debug, sqlx::FromRow)]
+pub struct RevokedSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub impersonator_id: Option<Uuid>,
+}
+
+/// 为被撤销的模拟登录会话写入 `impersonation_ended`，普通会话忽略。
+///
+/// `reason` 与会话的 `revoked_reason` 一致，调用方须在撤销会话的同一事务内调用。
+pub async fn record_ended(
+    conn: &mut PgConnection,
+    sessions: &[RevokedSession],
+    client: &ClientInfo,
+    reason: &str,
+) -> Result<(), AppError> {
+    for session in sessions {
+        let Some(impersonator_id) = session.impersonator_id else {
+            continue;
+        };
+        security_events::record(
+            &mut *conn,
+            SecurityEvent {
+                event_type: security_events::IMPERSONATION_ENDED,
+                user_id: Some(impersonator_id),
+                session_id: Some(session.id),
+                client,
+                details: serde_json::json!({
+                    "target_user_id": session.user_id,
+                    "reason": reason,
+                }),
+            },
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// 将已到期但未撤销的模拟登录会话标记为撤销，并补记结束事件，返回处理数量。
+///
+/// 模拟登录会话不可刷新，到期即结束；由后台任务定期调用，事件中的 `expires_at` 为实际结束时间。
+pub async fn end_expired(db: &DbPool) -> Result<u64, AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启结束模拟登录事务失败: {e}")))?;
+
+    let rows = sqlx::query!(
+        r#"
+UPDATE auth_sessions
+SET revoked_at = NOW(),
+    revoked_reason = 'impersonation_expired',
+    updated_at = NOW()
+WHERE impersonator_id IS NOT NULL
+  AND revoked_at IS NULL
+  AND expires_at <= NOW()
+RETURNING id, user_id, impersonator_id AS "impersonator_id!", expires_at
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("结束到期模拟登录会话失败: {e}")))?;
+
+    let client = ClientInfo::default();
+    for row in &rows {
+        security_events::record(
+            &mut *tx,
+            SecurityEvent {
+                event_type: security_events::IMPERSONATION_ENDED,
+                user_id: Some(row.impersonator_id),
+                session_id: Some(row.id),
+                client: &client,
+                details: serde_json::json!({
+                    "target_user_id": row.user_id,
+                    "reason": "impersonation_expired",
+                    "expires_at": row.expires_at,
+                }),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交结束模拟登录事务失败: {e}")))?;
+
+    Ok(rows.len() as u64)
+}
@@ -0,0 +1,586 @@
+use std::time::{Duration as StdDuration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::db::DbPool;
+use crate::error::AppError;
+
+/// `jwt_signing_keys` 为空时，`security.jwt_secret` 作为唯一签名密钥使用的 kid。
+pub const LEGACY_KID: &str = "legacy";
+
+/// 轮换后旧签名密钥默认的校验宽限期（秒）。
+pub const DEFAULT_GRACE_PERIOD_SECS: u64 = 60 * 60;
+
+/// RSA 签名密钥长度（bit）。
+const RSA_KEY_BITS: usize = 2048;
+
+/// 可配置的 JWT 签名算法（`security.jwt_algorithm`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+pub enum JwtAlgorithm {
+    /// HMAC-SHA256 对称签名（默认，兼容旧版本）。
+    #[default]
+    #[serde(rename = "HS256")]
+    Hs256,
+    /// Ed25519 非对称签名。
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+    /// RSA PKCS#1 v1.5 + SHA-256 非对称签名。
+    #[serde(rename = "RS256")]
+    Rs256,
+}
+
+impl JwtAlgorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Hs256 => "HS256",
+            Self::EdDsa => "EdDSA",
+            Self::Rs256 => "RS256",
+        }
+    }
+
+    pub fn algorithm(self) -> Algorithm {
+        match self {
+            Self::Hs256 => Algorithm::HS256,
+            Self::EdDsa => Algorithm::EdDSA,
+            Self::Rs256 => Algorithm::RS256,
+        }
+    }
+}
+
+impl std::str::FromStr for JwtAlgorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "HS256" => Ok(Self::Hs256),
+            "EdDSA" => Ok(Self::EdDsa),
+            "RS256" => Ok(Self::Rs256),
+            other => Err(anyhow!("不支持的 JWT 签名算法: {other}")),
+        }
+    }
+}
+
+/// 非对称签名密钥的公钥（RFC 7517 JWK），通过 `/.well-known/jwks.json` 公开。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicJwk {
+    /// `OKP`（Ed25519）或 `RSA`。
+    pub kty: String,
+    pub kid: String,
+    pub alg: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    /// Ed25519 曲线名（`Ed25519`）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crv: Option<String>,
+    /// Ed25519 公钥（base64url）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub x: Option<String>,
+    /// RSA 模数（base64url）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    /// RSA 公钥指数（base64url）。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub e: Option<String>,
+}
+
+/// 单个 JWT 签名/校验密钥。
+#[derive(Clone)]
+pub struct JwtKey {
+    pub kid: String,
+    pub algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_jwk: Option<PublicJwk>,
+    /// 校验截止时间；为空表示长期有效（当前签名密钥）。
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl std::fmt::Debug for JwtKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtKey")
+            .field("kid", &self.kid)
+            .field("algorithm", &self.algorithm)
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JwtKey {
+    pub fn header(&self) -> Header {
+        let mut header = Header::new(self.algorithm);
+        header.kid = Some(self.kid.clone());
+        header
+    }
+
+    /// 由解密后的密钥材料构造：HS256 为对称密钥，EdDSA 为 PKCS#8 DER，RS256 为 PKCS#1 DER；
+    /// 非对称密钥的校验使用 `public_jwk` 中的公钥。
+    fn from_material(
+        kid: String,
+        algorithm: JwtAlgorithm,
+        material: &[u8],
+        public_jwk: Option<PublicJwk>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Self> {
+        let (encoding_key, decoding_key, public_jwk) = match algorithm {
+            JwtAlgorithm::Hs256 => (
+                EncodingKey::from_secret(material),
+                DecodingKey::from_secret(material),
+                None,
+            ),
+            JwtAlgorithm::EdDsa => {
+                let jwk = public_jwk.ok_or_else(|| anyhow!("JWT 密钥 {kid} 缺少公钥"))?;
+                let x = jwk
+                    .x
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("JWT 密钥 {kid} 的公钥缺少 x"))?;
+                let decoding_key = DecodingKey::from_ed_components(x)
+                    .with_context(|| format!("解析 JWT 密钥 {kid} 的公钥失败"))?;
+                (EncodingKey::from_ed_der(material), decoding_key, Some(jwk))
+            }
+            JwtAlgorithm::Rs256 => {
+                let jwk = public_jwk.ok_or_else(|| anyhow!("JWT 密钥 {kid} 缺少公钥"))?;
+                let (Some(n), Some(e)) = (jwk.n.as_deref(), jwk.e.as_deref()) else {
+                    return Err(anyhow!("JWT 密钥 {kid} 的公钥缺少 n/e"));
+                };
+                let decoding_key = DecodingKey::from_rsa_components(n, e)
+                    .with_context(|| format!("解析 JWT 密钥 {kid} 的公钥失败"))?;
+                (EncodingKey::from_rsa_der(material), decoding_key, Some(jwk))
+            }
+        };
+
+        Ok(Self {
+            kid,
+            algorithm: algorithm.algorithm(),
+            encoding_key,
+            decoding_key,
+            public_jwk,
+            expires_at,
+        })
+    }
+
+    pub fn encoding_key(&self) -> &EncodingKey {
+        &self.encoding_key
+    }
+
+    pub fn decoding_key(&self) -> &DecodingKey {
+        &self.decoding_key
+    }
+
+    fn is_usable_at(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// JWT 密钥环：一个签名密钥 + 若干仍在宽限期内的校验密钥。
+#[derive(Debug, Clone)]
+pub struct JwtKeyring {
+    signing: JwtKey,
+    keys: Vec<JwtKey>,
+    loaded_at: Instant,
+}
+
+impl JwtKeyring {
+    pub fn signing_key(&self) -> &JwtKey {
+        &self.signing
+    }
+
+    /// 按 kid 查找未退役的校验密钥；未携带 kid 的旧 token 视为 legacy 密钥签发。
+    pub fn verification_key(&self, kid: Option<&str>) -> Option<&JwtKey> {
+        let kid = kid.unwrap_or(LEGACY_KID);
+        let now = Utc::now();
+        self.keys
+            .iter()
+            .find(|key| key.kid == kid && key.is_usable_at(now))
+    }
+
+    /// 仍可用于校验的非对称公钥；HS256 密钥不会公开。
+    pub fn public_jwks(&self) -> Vec<PublicJwk> {
+        let now = Utc::now();
+        self.keys
+            .iter()
+            .filter(|key| key.is_usable_at(now))
+            .filter_map(|key| key.public_jwk.clone())
+            .collect()
+    }
+
+    /// 距离上次从数据库加载已超过 `max_age`。
+    pub fn is_stale(&self, max_age: StdDuration) -> bool {
+        self.loaded_at.elapsed() >= max_age
+    }
+}
+
+/// 从 `jwt_signing_keys` 加载密钥环；表为空时回退到 `security.jwt_secret`。
+pub async fn load_keyring(
+    pool: &DbPool,
+    legacy_secret: &str,
+    encryption_key: &str,
+) -> Result<JwtKeyring> {
+    let rows = sqlx::query!(
+        r#"
+SELECT kid, algorithm, secret_ciphertext, public_jwk, is_active, expires_at
+FROM jwt_signing_keys
+WHERE is_active = TRUE
+   OR expires_at IS NULL
+   OR expires_at > NOW()
+ORDER BY created_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .context("查询 JWT 签名密钥失败")?;
+
+    if rows.is_empty() {
+        let legacy = JwtKey::from_material(
+            LEGACY_KID.to_string(),
+            JwtAlgorithm::Hs256,
+            legacy_secret.as_bytes(),
+            None,
+            None,
+        )?;
+        return Ok(JwtKeyring {
+            signing: legacy.clone(),
+            keys: vec![legacy],
+            loaded_at: Instant::now(),
+        });
+    }
+
+    let mut signing = None;
+    let mut keys = Vec::with_capacity(rows.len());
+    for row in rows {
+        let algorithm: JwtAlgorithm = row
+            .algorithm
+            .parse()
+            .with_context(|| format!("JWT 密钥 {} 的算法不受支持", row.kid))?;
+        let material = crate::crypto::decrypt_from_hex(encryption_key, &row.secret_ciphertext)
+            .with_context(|| format!("解密 JWT 密钥 {} 失败", row.kid))?;
+        let public_jwk = row
+            .public_jwk
+            .map(serde_json::from_value::<PublicJwk>)
+            .transpose()
+            .with_context(|| format!("解析 JWT 密钥 {} 的公钥失败", row.kid))?;
+        let key = JwtKey::from_material(
+            row.kid,
+            algorithm,
+            &material,
+            public_jwk,
+            if row.is_active { None } else { row.expires_at },
+        )?;
+        if row.is_active {
+            signing = Some(key.clone());
+        }
+        keys.push(key);
+    }
+
+    let signing = signing.ok_or_else(|| anyhow!("jwt_signing_keys 中缺少当前签名密钥"))?;
+    Ok(JwtKeyring {
+        signing,
+        keys,
+        loaded_at: Instant::now(),
+    })
+}
+
+/// 轮换签名密钥：按 `algorithm` 生成新的签名密钥，旧签名密钥在宽限期内仍可用于校验。
+///
+/// 首次轮换时会把 `security.jwt_secret` 以 legacy 密钥写入表中，同样享有宽限期。
+pub async fn rotate(
+    db: &DbPool,
+    legacy_secret: &str,
+    encryption_key: &str,
+    algorithm: JwtAlgorithm,
+    grace_period_secs: u64,
+) -> Result<String, AppError> {
+    let key = prepare(encryption_key, algorithm).await?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启密钥轮换事务失败: {e}")))?;
+    let kid = rotate_in_tx(
+        &mut tx,
+        legacy_secret,
+        encryption_key,
+        key,
+        grace_period_secs,
+    )
+    .await?;
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交密钥轮换事务失败: {e}")))?;
+
+    Ok(kid)
+}
+
+/// 已生成并加密、尚未写入数据库的签名密钥，由 [`rotate_in_tx`] 启用。
+pub struct PreparedKey {
+    kid: String,
+    algorithm: JwtAlgorithm,
+    secret_ciphertext: String,
+    public_jwk: Option<serde_json::Value>,
+}
+
+/// 按 `algorithm` 生成新的签名密钥；不访问数据库，可在开启事务前调用。
+pub async fn prepare(
+    encryption_key: &str,
+    algorithm: JwtAlgorithm,
+) -> Result<PreparedKey, AppError> {
+    let kid = generate_hex(8);
+    // RSA 密钥生成耗时较长，放到阻塞线程池中执行，且不持有表锁。
+    let generated = tokio::task::spawn_blocking({
+        let kid = kid.clone();
+        move || generate_key_material(&kid, algorithm)
+    })
+    .await
+    .map_err(|e| AppError::InternalError(format!("生成 JWT 签名密钥失败: {e}")))?
+    .map_err(|e| AppError::InternalError(format!("生成 JWT 签名密钥失败: {e}")))?;
+    let secret_ciphertext = crate::crypto::encrypt_to_hex(encryption_key, &generated.material)
+        .map_err(|e| AppError::InternalError(format!("加密 JWT 签名密钥失败: {e}")))?;
+    let public_jwk = generated
+        .public_jwk
+        .map(serde_json::to_value)
+        .transpose()
+        .map_err(|e| AppError::InternalError(format!("序列化 JWT 公钥失败: {e}")))?;
+
+    Ok(PreparedKey {
+        kid,
+        algorithm,
+        secret_ciphertext,
+        public_jwk,
+    })
+}
+
+/// 在调用方的事务中启用 [`prepare`] 生成的签名密钥，旧签名密钥在宽限期内仍可用于校验。
+pub async fn rotate_in_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    legacy_secret: &str,
+    encryption_key: &str,
+    key: PreparedKey,
+    grace_period_secs: u64,
+) -> Result<String, AppError> {
+    let PreparedKey {
+        kid,
+        algorithm,
+        secret_ciphertext,
+        public_jwk,
+    } = key;
+    let retire_at = Utc::now() + Duration::seconds(grace_period_secs as i64);
+
+    // 串行化并发轮换，保证任意时刻只有一个签名密钥。
+    sqlx::query!("LOCK TABLE jwt_signing_keys IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("锁定 JWT 密钥表失败: {e}")))?;
+
+    let has_keys =
+        sqlx::query_scalar!(r#"SELECT EXISTS (SELECT 1 FROM jwt_signing_keys) AS "exists!""#)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|e| AppError::InternalError(format!("查询 JWT 签名密钥失败: {e}")))?;
+
+    if has_keys {
+        sqlx::query!(
+            r#"
+UPDATE jwt_signing_keys
+SET is_active = FALSE,
+    expires_at = $1
+WHERE is_active = TRUE
+            "#,
+            retire_at,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("退役旧签名密钥失败: {e}")))?;
+    } else {
+        let legacy_ciphertext =
+            crate::crypto::encrypt_to_hex(encryption_key, legacy_secret.as_bytes())
+                .map_err(|e| AppError::InternalError(format!("加密 legacy 密钥失败: {e}")))?;
+        sqlx::query!(
+            r#"
+INSERT INTO jwt_signing_keys (kid, algorithm, secret_ciphertext, is_active, expires_at)
+VALUES ($1, 'HS256', $2, FALSE, $3)
+            "#,
+            LEGACY_KID,
+            legacy_ciphertext,
+            retire_at,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("写入 legacy 密钥失败: {e}")))?;
+    }
+
+    sqlx::query!(
+        r#"
+INSERT INTO jwt_signing_keys (kid, algorithm, secret_ciphertext, public_jwk, is_active)
+VALUES ($1, $2, $3, $4, TRUE)
+        "#,
+        kid,
+        algorithm.as_str(),
+        secret_ciphertext,
+        public_jwk,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("写入新签名密钥失败: {e}")))?;
+
+    Ok(kid)
+}
+
+struct GeneratedKey {
+    material: Vec<u8>,
+    public_jwk: Option<PublicJwk>,
+}
+
+fn generate_key_material(kid: &str, algorithm: JwtAlgorithm) -> Result<GeneratedKey> {
+    use argon2::password_hash::rand_core::OsRng;
+
+    let public_jwk = |kty: &str| PublicJwk {
+        kty: kty.to_string(),
+        kid: kid.to_string(),
+        alg: algorithm.as_str().to_string(),
+        key_use: "sig".to_string(),
+        crv: None,
+        x: None,
+        n: None,
+        e: None,
+    };
+
+    match algorithm {
+        JwtAlgorithm::Hs256 => Ok(GeneratedKey {
+            material: generate_bytes(32),
+            public_jwk: None,
+        }),
+        JwtAlgorithm::EdDsa => {
+            use ed25519_dalek::pkcs8::EncodePrivateKey;
+
+            let signing_key = ed25519_dalek::SigningKey::generate(&mut OsRng);
+            let der = signing_key
+                .to_pkcs8_der()
+                .map_err(|e| anyhow!("编码 Ed25519 私钥失败: {e}"))?;
+            Ok(GeneratedKey {
+                material: der.as_bytes().to_vec(),
+                public_jwk: Some(PublicJwk {
+                    crv: Some("Ed25519".to_string()),
+                    x: Some(URL_SAFE_NO_PAD.encode(signing_key.verifying_key().as_bytes())),
+                    ..public_jwk("OKP")
+                }),
+            })
+        }
+        JwtAlgorithm::Rs256 => {
+            use rsa::pkcs1::EncodeRsaPrivateKey;
+            use rsa::traits::PublicKeyParts;
+
+            let private_key =
+                rsa::RsaPrivateKey::new(&mut OsRng, RSA_KEY_BITS).context("生成 RSA 私钥失败")?;
+            let der = private_key
+                .to_pkcs1_der()
+                .map_err(|e| anyhow!("编码 RSA 私钥失败: {e}"))?;
+            Ok(GeneratedKey {
+                material: der.as_bytes().to_vec(),
+                public_jwk: Some(PublicJwk {
+                    n: Some(URL_SAFE_NO_PAD.encode(private_key.n().to_bytes_be())),
+                    e: Some(URL_SAFE_NO_PAD.encode(private_key.e().to_bytes_be())),
+                    ..public_jwk("RSA")
+                }),
+            })
+        }
+    }
+}
+
+fn generate_bytes(len: usize) -> Vec<u8> {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn generate_hex(len: usize) -> String {
+    crate::config::seed::hex_encode(&generate_bytes(len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(kid: &str, expires_at: Option<DateTime<Utc>>) -> JwtKey {
+        JwtKey::from_material(
+            kid.to_string(),
+            JwtAlgorithm::Hs256,
+            b"secret",
+            None,
+            expires_at,
+        )
+        .expect("构造测试密钥失败")
+    }
+
+    #[test]
+    fn keyring_should_skip_retired_keys_and_map_missing_kid_to_legacy() {
+        let signing = key("new", None);
+        let keyring = JwtKeyring {
+            signing: signing.clone(),
+            keys: vec![
+                signing,
+                key(LEGACY_KID, Some(Utc::now() + Duration::minutes(5))),
+                key("retired", Some(Utc::now() - Duration::minutes(5))),
+            ],
+            loaded_at: Instant::now(),
+        };
+
+        assert_eq!(
+            keyring
+                .verification_key(Some("new"))
+                .map(|k| k.kid.as_str()),
+            Some("new")
+        );
+        assert_eq!(
+            keyring.verification_key(None).map(|k| k.kid.as_str()),
+            Some(LEGACY_KID)
+        );
+        assert!(keyring.verification_key(Some("retired")).is_none());
+        assert!(keyring.verification_key(Some("unknown")).is_none());
+    }
+
+    #[test]
+    fn eddsa_key_should_sign_tokens_verifiable_with_published_jwk() {
+        let generated =
+            generate_key_material("ed-kid", JwtAlgorithm::EdDsa).expect("生成 Ed25519 密钥失败");
+        let signing = JwtKey::from_material(
+            "ed-kid".to_string(),
+            JwtAlgorithm::EdDsa,
+            &generated.material,
+            generated.public_jwk,
+            None,
+        )
+        .expect("构造 Ed25519 密钥失败");
+        let keyring = JwtKeyring {
+            signing: signing.clone(),
+            keys: vec![signing.clone(), key(LEGACY_KID, None)],
+            loaded_at: Instant::now(),
+        };
+
+        let jwks = keyring.public_jwks();
+        assert_eq!(jwks.len(), 1, "HS256 密钥不应公开");
+        let jwk = &jwks[0];
+        assert_eq!(
+            (jwk.kty.as_str(), jwk.alg.as_str(), jwk.kid.as_str()),
+            ("OKP", "EdDSA", "ed-kid")
+        );
+
+        let claims = serde_json::json!({ "sub": "user", "exp": Utc::now().timestamp() + 60 });
+        let token = jsonwebtoken::encode(&signing.header(), &claims, signing.encoding_key())
+            .expect("签发 EdDSA token 失败");
+        let decoding_key = DecodingKey::from_ed_components(jwk.x.as_deref().expect("缺少 x"))
+            .expect("解析公钥失败");
+        jsonwebtoken::decode::<serde_json::Value>(
+            &token,
+            &decoding_key,
+            &jsonwebtoken::Validation::new(Algorithm::EdDSA),
+        )
+        .expect("使用公开的 JWK 校验 token 失败");
+    }
+}
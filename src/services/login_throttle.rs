@@ -0,0 +1,353 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::config::runtime::LoginThrottleConfig;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::services::security_events::{self, SecurityEvent};
+
+const SCOPE_IDENTIFIER: &str = "identifier";
+const SCOPE_IP: &str = "ip";
+const THROTTLED_MESSAGE: &str = "登录失败次数过多，请稍后再试";
+
+/// 登录标识统一按去空白、小写后计数，避免大小写变体绕过限制。
+pub fn normalize_identifier(identifier: &str) -> String {
+    identifier.trim().to_lowercase()
+}
+
+/// 登录前检查：登录标识或客户端 IP 处于退避期时直接拒绝。
+pub async fn ensure_not_throttled(
+    db: &DbPool,
+    cfg: &LoginThrottleConfig,
+    identifier: &str,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let client_ip = client.trusted_ip(&cfg.trusted_proxies);
+    let blocked_until = sqlx::query_scalar!(
+        r#"
+SELECT MAX(blocked_until) AS "blocked_until"
+FROM auth_login_throttles
+WHERE blocked_until > NOW()
+  AND (
+    (scope = 'identifier' AND key = $1)
+    OR (scope = 'ip' AND key = $2)
+  )
+        "#,
+        identifier,
+        client_ip,
+    )
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询登录限流状态失败: {e}")))?;
+
+    match blocked_until {
+        Some(until) => Err(throttled_error(until)),
+        None => Ok(()),
+    }
+}
+
+/// 账号被临时锁定时返回 429。
+pub fn ensure_account_not_locked(locked_until: Option<DateTime<Utc>>) -> Result<(), AppError> {
+    match locked_until {
+        Some(until) if until > Utc::now() => Err(throttled_error(until)),
+        _ => Ok(()),
+    }
+}
+
+/// 记录一次登录失败：累加登录标识与 IP 的失败计数，按需设置退避与账号锁定。
+pub async fn record_failure(
+    db: &DbPool,
+    cfg: &LoginThrottleConfig,
+    identifier: &str,
+    user_id: Option<Uuid>,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let identifier_failures = bump_failure_count(db, cfg, SCOPE_IDENTIFIER, identifier).await?;
+    if let Some(delay) = backoff_delay_secs(
+        identifier_failures,
+        cfg.backoff_after_failures,
+        cfg.backoff_base_secs,
+        cfg.backoff_max_secs,
+    ) {
+        set_blocked_until(db, SCOPE_IDENTIFIER, identifier, delay).await?;
+    }
+
+    if let Some(ip) = client.trusted_ip(&cfg.trusted_proxies).as_deref() {
+        let ip_failures = bump_failure_count(db, cfg, SCOPE_IP, ip).await?;
+        if let Some(delay) = backoff_delay_secs(
+            ip_failures,
+            cfg.ip_failure_threshold,
+            cfg.backoff_base_secs,
+            cfg.backoff_max_secs,
+        ) {
+            set_blocked_until(db, SCOPE_IP, ip, delay).await?;
+        }
+    }
+
+    if let Some(user_id) = user_id {
+        if identifier_failures >= cfg.lockout_threshold {
+            lock_account(db, cfg, user_id, identifier_failures, client).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 登录成功后清除该登录标识的失败计数（IP 计数保留，避免被用于重置限流）。
+pub async fn clear_identifier(db: &DbPool, identifier: &str) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+DELETE FROM auth_login_throttles
+WHERE scope = 'identifier'
+  AND key = $1
+        "#,
+        identifier,
+    )
+    .execute(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理登录失败计数失败: {e}")))?;
+
+    Ok(())
+}
+
+/// 删除已失效的失败计数：距上次失败已超过计数窗口且不在退避期内的行，返回删除数量。
+///
+/// 未知登录标识与任意 IP 都会产生计数行，由后台任务定期调用以免表无限增长。
+pub async fn delete_expired(db: &DbPool, cfg: &LoginThrottleConfig) -> Result<u64, AppError> {
+    let deleted = sqlx::query!(
+        r#"
+DELETE FROM auth_login_throttles
+WHERE last_failure_at < NOW() - make_interval(secs => $1)
+  AND (blocked_until IS NULL OR blocked_until <= NOW())
+        "#,
+        cfg.failure_window_secs as f64,
+    )
+    .execute(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理过期登录失败计数失败: {e}")))?;
+
+    Ok(deleted.rows_affected())
+}
+
+/// 管理员解锁账号：清除锁定状态以及该用户各登录标识的失败计数。
+pub async fn unlock_account(
+    db: &DbPool,
+    user_id: Uuid,
+    unlocked_by: Uuid,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启解锁事务失败: {e}")))?;
+
+    let user = sqlx::query!(
+        r#"
+UPDATE users
+SET locked_until = NULL,
+    updated_at = NOW()
+WHERE id = $1
+  AND deleted_at IS NULL
+RETURNING username, email, phone
+        "#,
+        user_id,
+    )
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("解锁用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+
+    let identifiers = [user.username, Some(user.email), user.phone]
+        .into_iter()
+        .flatten()
+        .map(|v| normalize_identifier(&v))
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        r#"
+DELETE FROM auth_login_throttles
+WHERE scope = 'identifier'
+  AND key = ANY($1::text[])
+        "#,
+        &identifiers[..],
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理登录失败计数失败: {e}")))?;
+
+    security_events::record(
+        &mut *tx,
+        SecurityEvent {
+            event_type: security_events::ACCOUNT_UNLOCKED,
+            user_id: Some(user_id),
+            session_id: None,
+            client,
+            details: serde_json::json!({ "unlocked_by": unlocked_by }),
+        },
+    )
+    .await?;
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交解锁事务失败: {e}")))?;
+
+    Ok(())
+}
+
+async fn bump_failure_count(
+    db: &DbPool,
+    cfg: &LoginThrottleConfig,
+    scope: &str,
+    key: &str,
+) -> Result<u64, AppError> {
+    let count = sqlx::query_scalar!(
+        r#"
+INSERT INTO auth_login_throttles (scope, key, failure_count, first_failure_at, last_failure_at)
+VALUES ($1, $2, 1, NOW(), NOW())
+ON CONFLICT (scope, key) DO UPDATE
+SET failure_count = CASE
+        WHEN auth_login_throttles.last_failure_at < NOW() - make_interval(secs => $3)
+            THEN 1
+        ELSE auth_login_throttles.failure_count + 1
+    END,
+    first_failure_at = CASE
+        WHEN auth_login_throttles.last_failure_at < NOW() - make_interval(secs => $3)
+            THEN NOW()
+        ELSE auth_login_throttles.first_failure_at
+    END,
+    last_failure_at = NOW()
+RETURNING failure_count
+        "#,
+        scope,
+        key,
+        cfg.failure_window_secs as f64,
+    )
+    .fetch_one(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("记录登录失败次数失败: {e}")))?;
+
+    Ok(u64::try_from(count).unwrap_or(0))
+}
+
+async fn set_blocked_until(
+    db: &DbPool,
+    scope: &str,
+    key: &str,
+    delay_secs: u64,
+) -> Result<(), AppError> {
+    let blocked_until = now_plus_secs(delay_secs)?;
+    sqlx::query!(
+        r#"
+UPDATE auth_login_throttles
+SET blocked_until = $3
+WHERE scope = $1
+  AND key = $2
+        "#,
+        scope,
+        key,
+        blocked_until,
+    )
+    .execute(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("设置登录退避失败: {e}")))?;
+
+    Ok(())
+}
+
+async fn lock_account(
+    db: &DbPool,
+    cfg: &LoginThrottleConfig,
+    user_id: Uuid,
+    failures: u64,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let locked_until = now_plus_secs(cfg.lockout_secs)?;
+
+    let mut tx = db
+        .begin()
+        .await
+        .map_err(|e| AppError::InternalError(format!("开启账号锁定事务失败: {e}")))?;
+
+    let locked = sqlx::query!(
+        r#"
+UPDATE users
+SET locked_until = $2,
+    updated_at = NOW()
+WHERE id = $1
+  AND (locked_until IS NULL OR locked_until <= NOW())
+        "#,
+        user_id,
+        locked_until,
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("锁定账号失败: {e}")))?;
+
+    if locked.rows_affected() > 0 {
+        security_events::record(
+            &mut *tx,
+            SecurityEvent {
+                event_type: security_events::ACCOUNT_LOCKED,
+                user_id: Some(user_id),
+                session_id: None,
+                client,
+                details: serde_json::json!({
+                    "failures": failures,
+                    "locked_until": locked_until,
+                }),
+            },
+        )
+        .await?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| AppError::InternalError(format!("提交账号锁定事务失败: {e}")))?;
+
+    Ok(())
+}
+
+/// 失败次数达到 `threshold` 后开始退避：`base * 2^(failures - threshold)`，上限 `max`。
+fn backoff_delay_secs(failures: u64, threshold: u64, base: u64, max: u64) -> Option<u64> {
+    if threshold == 0 || failures < threshold {
+        return None;
+    }
+    let exponent = (failures - threshold).min(32) as u32;
+    Some(base.saturating_mul(1u64 << exponent).min(max))
+}
+
+/// 当前时间加上 `secs` 秒；配置异常导致溢出时返回错误而不是 panic。
+fn now_plus_secs(secs: u64) -> Result<DateTime<Utc>, AppError> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(Duration::try_seconds)
+        .and_then(|delay| Utc::now().checked_add_signed(delay))
+        .ok_or_else(|| AppError::InternalError(format!("登录限流时长超出范围: {secs} 秒")))
+}
+
+fn throttled_error(until: DateTime<Utc>) -> AppError {
+    let retry_after = (until - Utc::now()).num_seconds().max(1) as u64;
+    AppError::too_many_requests(THROTTLED_MESSAGE, retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_should_start_at_threshold_and_double_until_max() {
+        assert_eq!(backoff_delay_secs(2, 3, 1, 300), None);
+        assert_eq!(backoff_delay_secs(3, 3, 1, 300), Some(1));
+        assert_eq!(backoff_delay_secs(4, 3, 1, 300), Some(2));
+        assert_eq!(backoff_delay_secs(6, 3, 1, 300), Some(8));
+        assert_eq!(backoff_delay_secs(100, 3, 1, 300), Some(300));
+    }
+
+    #[test]
+    fn now_plus_secs_should_reject_overflowing_durations() {
+        assert!(now_plus_secs(900).is_ok());
+        assert!(now_plus_secs(u64::MAX).is_err());
+        assert!(now_plus_secs(i64::MAX as u64).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+use crate::config::runtime::MailRuntimeConfig;
+
+const SMTP_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// 邮件发送方式（`mail.transport`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTransportKind {
+    /// 只写日志，不真正发送（默认，适合开发环境；正文仅在 debug 级别输出）。
+    Log,
+    /// 以 `.eml` 文件写入 `mail.file_dir`（适合测试与排查）。
+    File,
+    /// 投递到 SMTP 中继（不做认证与 TLS，需部署本机或内网 MTA）。
+    Smtp,
+}
+
+impl std::str::FromStr for MailTransportKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "log" => Ok(Self::Log),
+            "file" => Ok(Self::File),
+            "smtp" => Ok(Self::Smtp),
+            other => Err(anyhow!("不支持的邮件发送方式: {other}")),
+        }
+    }
+}
+
+/// 一封纯文本邮件。
+#[derive(Debug, Clone)]
+pub struct MailMessage {
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl MailMessage {
+    /// 发件人与收件人会原样写入邮件头和 SMTP 命令，含换行等控制字符时拒绝发送，防止头部/命令注入。
+    fn ensure_addresses_safe(&self) -> Result<()> {
+        for address in [&self.from, &self.to] {
+            if address.chars().any(char::is_control) {
+                return Err(anyhow!("邮件地址包含非法字符: {}", address.escape_debug()));
+            }
+        }
+        Ok(())
+    }
+}
+
+type SendFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// 邮件发送通道；新增发送方式只需实现该 trait 并在 [`transport_for`] 中注册。
+pub trait MailTransport: Send + Sync {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> SendFuture<'a>;
+}
+
+pub struct LogTransport;
+
+impl MailTransport for LogTransport {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            // 正文可能含重置链接等一次性凭证，只在 debug 级别输出
+            tracing::info!(
+                target: "mail",
+                to = %message.to,
+                subject = %message.subject,
+                "邮件（log 方式，未实际发送，正文见 debug 日志）"
+            );
+            tracing::debug!(target: "mail", body = %message.body, "邮件正文");
+            Ok(())
+        })
+    }
+}
+
+pub struct FileTransport {
+    dir: PathBuf,
+}
+
+impl MailTransport for FileTransport {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            message.ensure_addresses_safe()?;
+            tokio::fs::create_dir_all(&self.dir)
+                .await
+                .with_context(|| format!("创建邮件目录失败: {}", self.dir.display()))?;
+            let file_name = format!(
+                "{}-{}.eml",
+                chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ"),
+                uuid::Uuid::new_v4().simple()
+            );
+            let path = self.dir.join(file_name);
+            tokio::fs::write(&path, render(message))
+                .await
+                .with_context(|| format!("写入邮件文件失败: {}", path.display()))?;
+            Ok(())
+        })
+    }
+}
+
+pub struct SmtpRelayTransport {
+    host: String,
+    port: u16,
+}
+
+impl MailTransport for SmtpRelayTransport {
+    fn send<'a>(&'a self, message: &'a MailMessage) -> SendFuture<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(SMTP_TIMEOUT, self.deliver(message))
+                .await
+                .map_err(|_| anyhow!("SMTP 投递超时"))?
+        })
+    }
+}
+
+impl SmtpRelayTransport {
+    async fn deliver(&self, message: &MailMessage) -> Result<()> {
+        message.ensure_addresses_safe()?;
+        let stream = tokio::net::TcpStream::connect((self.host.as_str(), self.port))
+            .await
+            .with_context(|| format!("连接 SMTP 中继失败: {}:{}", self.host, self.port))?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+
+        expect_reply(&mut reader, 220).await?;
+        let commands = [
+            ("EHLO localhost".to_string(), 250),
+            (
+                format!("MAIL FROM:<{}>", mailbox_address(&message.from)),
+                250,
+            ),
+            (format!("RCPT TO:<{}>", mailbox_address(&message.to)), 250),
+            ("DATA".to_string(), 354),
+        ];
+        for (command, code) in commands {
+            writer
+                .write_all(format!("{command}\r\n").as_bytes())
+                .await?;
+            expect_reply(&mut reader, code)
+                .await
+                .with_context(|| format!("SMTP 命令失败: {command}"))?;
+        }
+
+        // 正文按 base64 编码，行首不会出现 `.`，无需 dot-stuffing。
+        writer.write_all(render(message).as_bytes()).await?;
+        writer.write_all(b".\r\n").await?;
+        expect_reply(&mut reader, 250)
+            .await
+            .context("SMTP 投递失败")?;
+
+        writer.write_all(b"QUIT\r\n").await?;
+        Ok(())
+    }
+}
+
+/// 读取一条（可能多行的）SMTP 响应并校验状态码。
+async fn expect_reply<R>(reader: &mut R, expected: u16) -> Result<()>
+where
+    R: AsyncBufReadExt + Unpin,
+{
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Err(anyhow!("SMTP 连接被关闭"));
+        }
+        let code: u16 = line
+            .get(..3)
+            .and_then(|c| c.parse().ok())
+            .ok_or_else(|| anyhow!("SMTP 响应格式错误: {}", line.trim_end()))?;
+        if code != expected {
+            return Err(anyhow!("SMTP 响应异常: {}", line.trim_end()));
+        }
+        // `250-` 表示多行响应尚未结束。
+        if line.as_bytes().get(3) != Some(&b'-') {
+            return Ok(());
+        }
+    }
+}
+
+/// `Name <addr@example.com>` -> `addr@example.com`。
+fn mailbox_address(mailbox: &str) -> &str {
+    match (mailbox.rfind('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
+
+/// 渲染为 RFC 5322 报文（UTF-8 正文 base64 编码，CRLF 换行）。
+fn render(message: &MailMessage) -> String {
+    let subject = format!("=?UTF-8?B?{}?=", STANDARD.encode(&message.subject));
+    let encoded_body = STANDARD.encode(&message.body);
+    let body_lines = encoded_body
+        .as_bytes()
+        .chunks(76)
+        .map(|chunk| String::from_utf8_lossy(chunk).into_owned())
+        .collect::<Vec<_>>()
+        .join("\r\n");
+
+    [
+        format!("From: {}", message.from),
+        format!("To: {}", message.to),
+        format!("Subject: {subject}"),
+        format!("Date: {}", chrono::Utc::now().to_rfc2822()),
+        format!(
+            "Message-ID: <{}@{}>",
+            uuid::Uuid::new_v4().simple(),
+            mailbox_address(&message.from)
+                .rsplit('@')
+                .next()
+                .unwrap_or("localhost")
+        ),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=UTF-8".to_string(),
+        "Content-Transfer-Encoding: base64".to_string(),
+        String::new(),
+        body_lines,
+        String::new(),
+    ]
+    .join("\r\n")
+}
+
+pub fn transport_for(cfg: &MailRuntimeConfig) -> Box<dyn MailTransport> {
+    match cfg.transport {
+        MailTransportKind::Log => Box::new(LogTransport),
+        MailTransportKind::File => Box::new(FileTransport {
+            dir: PathBuf::from(&cfg.file_dir),
+        }),
+        MailTransportKind::Smtp => Box::new(SmtpRelayTransport {
+            host: cfg.smtp_host.clone(),
+            port: cfg.smtp_port,
+        }),
+    }
+}
+
+/// 按运行期配置选择发送方式并发送一封纯文本邮件。
+pub async fn send(cfg: &MailRuntimeConfig, to: &str, subject: &str, body: &str) -> Result<()> {
+    let message = MailMessage {
+        from: cfg.from.clone(),
+        to: to.to_string(),
+        subject: subject.to_string(),
+        body: body.to_string(),
+    };
+    transport_for(cfg).send(&message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn smtp_relay_should_deliver_message_through_dialogue() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("监听测试端口失败");
+        let port = listener.local_addr().expect("读取测试端口失败").port();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("接受连接失败");
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut received = Vec::new();
+
+            writer.write_all(b"220 test ESMTP\r\n").await.unwrap();
+            let mut in_data = false;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                received.push(line.clone());
+                let reply: &[u8] = if in_data {
+                    if line != "." {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-test\r\n250 8BITMIME\r\n"
+                } else if line == "DATA" {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line == "QUIT" {
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            received
+        });
+
+        let transport = SmtpRelayTransport {
+            host: "127.0.0.1".to_string(),
+            port,
+        };
+        let message = MailMessage {
+            from: "PROJECT_NAME <no-reply@example.com>".to_string(),
+            to: "user@example.com".to_string(),
+            subject: "重置密码".to_string(),
+            body: "点击链接重置密码".to_string(),
+        };
+        transport.send(&message).await.expect("SMTP 投递失败");
+
+        let received = server.await.expect("测试 SMTP 服务异常");
+        assert!(received.contains(&"MAIL FROM:<no-reply@example.com>".to_string()));
+        assert!(received.contains(&"RCPT TO:<user@example.com>".to_string()));
+        assert!(received.contains(&STANDARD.encode("点击链接重置密码")));
+    }
+
+    #[tokio::test]
+    async fn smtp_relay_should_reject_recipient_with_line_breaks() {
+        let transport = SmtpRelayTransport {
+            host: "127.0.0.1".to_string(),
+            // 校验先于连接，端口不会被访问
+            port: 9,
+        };
+        let message = MailMessage {
+            from: "no-reply@example.com".to_string(),
+            to: "user@example.com>\r\nRCPT TO:<victim@example.com".to_string(),
+            subject: "重置密码".to_string(),
+            body: "点击链接重置密码".to_string(),
+        };
+        let err = transport
+            .send(&message)
+            .await
+            .expect_err("收件人含换行时应拒绝发送");
+        assert!(err.to_string().contains("非法字符"));
+    }
+}
@@ -0,0 +1,239 @@
+use chrono::Utc;
+use sqlx::{PgConnection, PgExecutor};
+use uuid::Uuid;
+
+use crate::db::DbPool;
+use crate::error::AppError;
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_BYTES: usize = 5;
+
+/// 第二因素的校验方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondFactor {
+    Totp,
+    RecoveryCode,
+}
+
+impl SecondFactor {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SecondFactor::Totp => "totp",
+            SecondFactor::RecoveryCode => "recovery_code",
+        }
+    }
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct TotpRow {
+    secret_ciphertext: String,
+}
+
+/// 用户是否已完成 TOTP 绑定（确认后才算启用）。
+pub async fn is_totp_enabled<'e, E>(executor: E, user_id: Uuid) -> Result<bool, AppError>
+where
+    E: PgExecutor<'e>,
+{
+    let row = sqlx::query!(
+        r#"
+SELECT EXISTS (
+    SELECT 1
+    FROM user_totp
+    WHERE user_id = $1
+      AND confirmed_at IS NOT NULL
+) AS "exists!"
+        "#,
+        user_id,
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询两步验证状态失败: {e}")))?;
+
+    Ok(row.exists)
+}
+
+/// 校验已启用 TOTP 的用户提交的第二因素：6 位 TOTP 动态码或一次性恢复码。
+///
+/// - TOTP 通过 `last_used_step` 防止同一动态码被重复使用
+/// - 恢复码校验成功后立即标记为已使用
+pub async fn verify_second_factor(
+    db: &DbPool,
+    encryption_key: &str,
+    user_id: Uuid,
+    code: &str,
+) -> Result<Option<SecondFactor>, AppError> {
+    let code = code.trim();
+    if code.len() == crate::totp::DIGITS as usize && code.chars().all(|c| c.is_ascii_digit()) {
+        let mut conn = db
+            .acquire()
+            .await
+            .map_err(|e| AppError::InternalError(format!("获取数据库连接失败: {e}")))?;
+        let ok = verify_totp_code(&mut conn, encryption_key, user_id, code, true).await?;
+        return Ok(ok.then_some(SecondFactor::Totp));
+    }
+
+    let ok = consume_recovery_code(db, user_id, code).await?;
+    Ok(ok.then_some(SecondFactor::RecoveryCode))
+}
+
+/// 校验 TOTP 动态码；`confirmed` 指定校验已启用的密钥还是待确认的密钥。
+///
+/// 校验成功时会原子地推进 `last_used_step`，同一步长的动态码只能使用一次。
+pub async fn verify_totp_code(
+    conn: &mut PgConnection,
+    encryption_key: &str,
+    user_id: Uuid,
+    code: &str,
+    confirmed: bool,
+) -> Result<bool, AppError> {
+    let row = sqlx::query_as!(
+        TotpRow,
+        r#"
+SELECT secret_ciphertext
+FROM user_totp
+WHERE user_id = $1
+  AND (confirmed_at IS NOT NULL) = $2
+LIMIT 1
+        "#,
+        user_id,
+        confirmed,
+    )
+    .fetch_optional(&mut *conn)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询 TOTP 密钥失败: {e}")))?;
+
+    let Some(row) = row else {
+        return Ok(false);
+    };
+
+    let secret = crate::crypto::decrypt_from_hex(encryption_key, &row.secret_ciphertext)
+        .map_err(|e| AppError::InternalError(format!("解密 TOTP 密钥失败: {e}")))?;
+    let step = crate::totp::find_matching_step(&secret, code, Utc::now().timestamp())
+        .map_err(|e| AppError::InternalError(format!("计算 TOTP 失败: {e}")))?;
+    let Some(step) = step else {
+        return Ok(false);
+    };
+
+    let updated = sqlx::query!(
+        r#"
+UPDATE user_totp
+SET last_used_step = $2,
+    updated_at = NOW()
+WHERE user_id = $1
+  AND (last_used_step IS NULL OR last_used_step < $2)
+        "#,
+        user_id,
+        step,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::InternalError(format!("记录 TOTP 使用状态失败: {e}")))?;
+
+    Ok(updated.rows_affected() == 1)
+}
+
+async fn consume_recovery_code(db: &DbPool, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+    let normalized = normalize_recovery_code(code);
+    if normalized.is_empty() {
+        return Ok(false);
+    }
+
+    let candidates = sqlx::query!(
+        r#"
+SELECT id, code_hash
+FROM user_totp_recovery_codes
+WHERE user_id = $1
+  AND used_at IS NULL
+        "#,
+        user_id,
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询恢复码失败: {e}")))?;
+
+    for candidate in candidates {
+        let ok = crate::password::verify_password(&normalized, &candidate.code_hash)
+            .map_err(|e| AppError::InternalError(format!("恢复码校验失败: {e}")))?;
+        if !ok {
+            continue;
+        }
+
+        let updated = sqlx::query!(
+            r#"
+UPDATE user_totp_recovery_codes
+SET used_at = NOW()
+WHERE id = $1
+  AND used_at IS NULL
+            "#,
+            candidate.id,
+        )
+        .execute(db)
+        .await
+        .map_err(|e| AppError::InternalError(format!("标记恢复码已使用失败: {e}")))?;
+        return Ok(updated.rows_affected() == 1);
+    }
+
+    Ok(false)
+}
+
+/// 重新生成恢复码（旧恢复码全部作废），返回明文，仅此一次展示给用户。
+pub async fn regenerate_recovery_codes(
+    conn: &mut PgConnection,
+    user_id: Uuid,
+) -> Result<Vec<String>, AppError> {
+    sqlx::query!(
+        "DELETE FROM user_totp_recovery_codes WHERE user_id = $1",
+        user_id
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理旧恢复码失败: {e}")))?;
+
+    let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let raw = generate_recovery_code();
+        let hash = crate::password::hash_password_argon2id(&raw)
+            .map_err(|e| AppError::InternalError(format!("恢复码哈希失败: {e}")))?;
+
+        sqlx::query!(
+            r#"
+INSERT INTO user_totp_recovery_codes (user_id, code_hash)
+VALUES ($1, $2)
+            "#,
+            user_id,
+            hash,
+        )
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::InternalError(format!("写入恢复码失败: {e}")))?;
+
+        codes.push(format!("{}-{}", &raw[..5], &raw[5..]));
+    }
+
+    Ok(codes)
+}
+
+fn generate_recovery_code() -> String {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+    let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    crate::config::seed::hex_encode(&bytes)
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_normalize_recovery_code_input() {
+        assert_eq!(normalize_recovery_code(" AB12C-de34f "), "ab12cde34f");
+    }
+}
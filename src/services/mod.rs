@@ -1 +1,13 @@
+pub mod api_tokens;
+pub mod impersonation;
+pub mod jwt_keys;
+pub mod login_throttle;
+pub mod mail;
+pub mod mfa;
+pub mod organizations;
+pub mod password_reset;
+pub mod rbac;
+pub mod security_events;
 pub mod system_config;
+pub mod user_passwords;
+pub mod user_purge;
@@ -0,0 +1,80 @@
+use axum::http::HeaderMap;
+use uuid::Uuid;
+
+use crate::api::auth::CurrentUser;
+use crate::api::permissions;
+use crate::db::DbPool;
+use crate::error::AppError;
+
+/// 选择组织上下文的请求头；绑定组织的 API token 无需携带。
+pub const ORGANIZATION_HEADER: &str = "x-organization-id";
+
+/// 解析请求头中的组织 ID；未携带时为平台（全局）上下文。
+pub fn requested_organization(headers: &HeaderMap) -> Result<Option<Uuid>, AppError> {
+    let Some(value) = headers.get(ORGANIZATION_HEADER) else {
+        return Ok(None);
+    };
+    value
+        .to_str()
+        .ok()
+        .and_then(|v| Uuid::try_parse(v.trim()).ok())
+        .map(Some)
+        .ok_or_else(|| AppError::validation(format!("{ORGANIZATION_HEADER} 必须是组织 ID")))
+}
+
+/// 用户在组织中的成员角色，`permissions` 只保留组织级权限。
+pub struct Membership {
+    pub role: String,
+    pub permissions: Vec<String>,
+}
+
+pub async fn membership(
+    db: &DbPool,
+    organization_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<Membership>, AppError> {
+    let row = sqlx::query!(
+        r#"
+SELECT
+    m.role,
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = m.role
+          AND rp.permission = ANY($3::text[])
+        ORDER BY rp.permission
+    ) AS "permissions!"
+FROM organization_members m
+WHERE m.organization_id = $1
+  AND m.user_id = $2
+        "#,
+        organization_id,
+        user_id,
+        permissions::ORGANIZATION_SCOPED as &[&str],
+    )
+    .fetch_optional(db)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询组织成员失败: {e}")))?;
+
+    Ok(row.map(|row| Membership {
+        role: row.role,
+        permissions: row.permissions,
+    }))
+}
+
+/// 切换到组织上下文：权限改为成员角色在该组织内的权限。
+///
+/// 非成员（含组织不存在）一律返回 `403`，不区分两种情况。
+pub async fn enter(
+    db: &DbPool,
+    current_user: &mut CurrentUser,
+    organization_id: Uuid,
+) -> Result<(), AppError> {
+    let membership = membership(db, organization_id, current_user.user_id)
+        .await?
+        .ok_or_else(|| AppError::PermissionDenied("不是该组织的成员".to_string()))?;
+
+    current_user.organization_id = Some(organization_id);
+    current_user.permissions = membership.permissions;
+    Ok(())
+}
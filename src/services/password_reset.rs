@@ -0,0 +1,60 @@
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// 重置链接有效期。
+pub const TOKEN_TTL_MINUTES: i64 = 30;
+/// 同一用户两次签发之间的最短间隔，避免被用来轰炸邮箱。
+pub const REQUEST_COOLDOWN_SECS: i64 = 60;
+
+/// 新生成的重置令牌：`token` 为明文（`<id>.<secret>`），只出现在邮件链接中。
+pub struct GeneratedResetToken {
+    pub id: Uuid,
+    pub token: String,
+    pub secret_hash: String,
+}
+
+pub fn generate() -> Result<GeneratedResetToken, AppError> {
+    use argon2::password_hash::rand_core::{OsRng, RngCore};
+
+    let id = Uuid::new_v4();
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    let secret = crate::config::seed::hex_encode(&bytes);
+    let secret_hash = crate::password::hash_password_argon2id(&secret)
+        .map_err(|e| AppError::InternalError(format!("重置令牌哈希失败: {e}")))?;
+
+    Ok(GeneratedResetToken {
+        id,
+        token: format!("{}.{secret}", id.simple()),
+        secret_hash,
+    })
+}
+
+pub fn parse_token(token: &str) -> Option<(Uuid, &str)> {
+    let (id, secret) = token.trim().split_once('.')?;
+    let id = Uuid::try_parse(id).ok()?;
+    if secret.is_empty() {
+        return None;
+    }
+    Some((id, secret))
+}
+
+pub fn reset_link(public_base_url: &str, token: &str) -> String {
+    format!("{public_base_url}/reset-password?token={token}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_token_should_round_trip() {
+        let generated = generate().expect("生成重置令牌失败");
+        let (id, secret) = parse_token(&generated.token).expect("应能解析生成的令牌");
+        assert_eq!(id, generated.id);
+        assert!(crate::password::verify_password(secret, &generated.secret_hash).unwrap());
+        assert!(parse_token("not-a-token").is_none());
+        assert!(parse_token(&format!("{}.", generated.id.simple())).is_none());
+    }
+}
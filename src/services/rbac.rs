@@ -0,0 +1,180 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::api::auth::CurrentUser;
+use crate::error::AppError;
+
+/// 内置管理员角色：拥有全部权限，系统始终保留至少一个启用中的管理员。
+pub const ADMIN_ROLE: &str = "admin";
+/// 新建用户未指定角色时使用的默认角色。
+pub const DEFAULT_ROLE: &str = "user";
+
+/// 查询角色拥有的全部权限；角色不存在时返回 `None`。
+pub async fn role_permissions<'e, E>(
+    executor: E,
+    role: &str,
+) -> Result<Option<Vec<String>>, AppError>
+where
+    E: PgExecutor<'e>,
+{
+    sqlx::query_scalar!(
+        r#"
+SELECT ARRAY(
+    SELECT rp.permission
+    FROM role_permissions rp
+    WHERE rp.role = r.name
+    ORDER BY rp.permission
+) AS "permissions!"
+FROM roles r
+WHERE r.name = $1
+        "#,
+        role,
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询角色权限失败: {e}")))
+}
+
+/// 去除首尾空白、排序并去重，便于比较与落库。
+pub fn normalize_permissions(mut permissions: Vec<String>) -> Vec<String> {
+    for permission in &mut permissions {
+        *permission = permission.trim().to_string();
+    }
+    permissions.sort();
+    permissions.dedup();
+    permissions
+}
+
+/// 校验权限名均存在于 `permissions` 表，未知权限返回 `400`。
+pub async fn ensure_permissions_exist<'e, E>(
+    executor: E,
+    permissions: &[String],
+) -> Result<(), AppError>
+where
+    E: PgExecutor<'e>,
+{
+    let known = sqlx::query_scalar!(
+        "SELECT name FROM permissions WHERE name = ANY($1::text[])",
+        permissions,
+    )
+    .fetch_all(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询权限失败: {e}")))?;
+
+    if let Some(unknown) = permissions.iter().find(|p| !known.contains(p)) {
+        return Err(AppError::validation(format!("权限不存在: {unknown}")));
+    }
+    Ok(())
+}
+
+/// 授予的权限不得超出操作者自身的权限，避免借助自定义角色或角色分配提权。
+pub fn ensure_within_own_permissions(
+    current_user: &CurrentUser,
+    permissions: &[String],
+) -> Result<(), AppError> {
+    if let Some(missing) = permissions
+        .iter()
+        .find(|permission| !current_user.has_permission(permission))
+    {
+        return Err(AppError::PermissionDenied(format!(
+            "不能授予超出自身权限的权限: {missing}"
+        )));
+    }
+    Ok(())
+}
+
+/// 分配角色前校验：角色存在，且其权限不超出操作者自身的权限。
+///
+/// 组织上下文中分配的是组织内角色，只比较组织级权限。
+pub async fn ensure_role_assignable<'e, E>(
+    executor: E,
+    current_user: &CurrentUser,
+    role: &str,
+) -> Result<(), AppError>
+where
+    E: PgExecutor<'e>,
+{
+    let mut permissions = role_permissions(executor, role)
+        .await?
+        .ok_or_else(|| AppError::validation(format!("角色不存在: {role}")))?;
+    if current_user.organization_id.is_some() {
+        permissions.retain(|permission| {
+            crate::api::permissions::ORGANIZATION_SCOPED.contains(&permission.as_str())
+        });
+    }
+    ensure_within_own_permissions(current_user, &permissions)
+}
+
+/// 管理已有用户（修改、删除、设置密码等）前校验：目标用户的权限（全局角色、用户组与各组织内角色）
+/// 不超出操作者，避免只拥有 `users:write` 的自定义角色接管管理员账号。
+///
+/// 组织上下文中只能管理该组织的成员（否则返回 `404`）；同时属于其他组织的用户只能在平台上下文管理，
+/// 避免一个组织的管理员借重置密码接管其他组织的账号。
+///
+/// 目标用户不存在时放行，由后续操作返回 `404`。
+pub async fn ensure_user_manageable<'e, E>(
+    executor: E,
+    current_user: &CurrentUser,
+    user_id: Uuid,
+) -> Result<(), AppError>
+where
+    E: PgExecutor<'e>,
+{
+    let row = sqlx::query!(
+        r#"
+SELECT
+    ARRAY(
+        SELECT rp.permission
+        FROM role_permissions rp
+        WHERE rp.role = u.role
+        UNION
+        SELECT gp.permission
+        FROM user_group_members gm
+        INNER JOIN user_group_permissions gp ON gp.group_id = gm.group_id
+        WHERE gm.user_id = u.id
+        UNION
+        SELECT rp.permission
+        FROM organization_members m
+        INNER JOIN role_permissions rp ON rp.role = m.role
+        WHERE m.user_id = u.id
+          AND rp.permission = ANY($2::text[])
+    ) AS "permissions!",
+    ARRAY(
+        SELECT m.organization_id
+        FROM organization_members m
+        WHERE m.user_id = u.id
+    ) AS "organization_ids!"
+FROM users u
+WHERE u.id = $1
+        "#,
+        user_id,
+        crate::api::permissions::ORGANIZATION_SCOPED as &[&str],
+    )
+    .fetch_optional(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户权限失败: {e}")))?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+    if let Some(organization_id) = current_user.organization_id {
+        if !row.organization_ids.contains(&organization_id) {
+            return Err(AppError::NotFound(format!("用户不存在: {user_id}")));
+        }
+        if row.organization_ids.len() > 1 {
+            return Err(AppError::PermissionDenied(
+                "该用户同时属于其他组织，只能在平台上下文管理".to_string(),
+            ));
+        }
+    }
+    if row
+        .permissions
+        .iter()
+        .any(|permission| !current_user.has_permission(permission))
+    {
+        return Err(AppError::PermissionDenied(
+            "不能管理权限高于自身的用户".to_string(),
+        ));
+    }
+    Ok(())
+}
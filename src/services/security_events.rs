@@ -0,0 +1,90 @@
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::error::AppError;
+
+pub const REFRESH_REUSE_DETECTED: &str = "refresh_reuse_detected";
+pub const MFA_ENABLED: &str = "mfa_enabled";
+pub const MFA_DISABLED: &str = "mfa_disabled";
+pub const MFA_RECOVERY_CODES_REGENERATED: &str = "mfa_recovery_codes_regenerated";
+pub const MFA_RECOVERY_CODE_USED: &str = "mfa_recovery_code_used";
+pub const ACCOUNT_LOCKED: &str = "account_locked";
+pub const ACCOUNT_UNLOCKED: &str = "account_unlocked";
+pub const JWT_KEY_ROTATED: &str = "jwt_key_rotated";
+pub const API_TOKEN_CREATED: &str = "api_token_created";
+pub const API_TOKEN_REVOKED: &str = "api_token_revoked";
+pub const PASSWORD_RESET_REQUESTED: &str = "password_reset_requested";
+pub const PASSWORD_RESET_COMPLETED: &str = "password_reset_completed";
+pub const PASSWORD_SET_BY_ADMIN: &str = "password_set_by_admin";
+pub const USER_ROLE_CHANGED: &str = "user_role_changed";
+pub const USERS_EXPORTED: &str = "users_exported";
+pub const USER_PURGED: &str = "user_purged";
+pub const ACCOUNT_DELETION_REQUESTED: &str = "account_deletion_requested";
+pub const PERSONAL_DATA_EXPORTED: &str = "personal_data_exported";
+pub const IMPERSONATION_STARTED: &str = "impersonation_started";
+pub const IMPERSONATION_ENDED: &str = "impersonation_ended";
+pub const ROLE_CREATED: &str = "role_created";
+pub const ROLE_UPDATED: &str = "role_updated";
+pub const ROLE_DELETED: &str = "role_deleted";
+pub const GROUP_CREATED: &str = "group_created";
+pub const GROUP_UPDATED: &str = "group_updated";
+pub const GROUP_DELETED: &str = "group_deleted";
+pub const GROUP_MEMBER_ADDED: &str = "group_member_added";
+pub const GROUP_MEMBER_REMOVED: &str = "group_member_removed";
+pub const ORGANIZATION_CREATED: &str = "organization_created";
+pub const ORGANIZATION_DELETED: &str = "organization_deleted";
+pub const ORGANIZATION_MEMBER_UPDATED: &str = "organization_member_updated";
+pub const ORGANIZATION_MEMBER_REMOVED: &str = "organization_member_removed";
+
+/// 一条待写入 `security_events` 的安全事件。
+pub struct SecurityEvent<'a> {
+    pub event_type: &'a str,
+    pub user_id: Option<Uuid>,
+    pub session_id: Option<Uuid>,
+    pub client: &'a ClientInfo,
+    pub details: serde_json::Value,
+}
+
+/// 写入安全事件，并同步输出一条 `security` target 的告警日志。
+///
+/// 接受任意 executor，便于在调用方事务内与业务写操作一起提交。
+pub async fn record<'e, E>(executor: E, event: SecurityEvent<'_>) -> Result<(), AppError>
+where
+    E: PgExecutor<'e>,
+{
+    tracing::warn!(
+        target: "security",
+        event_type = event.event_type,
+        user_id = ?event.user_id,
+        session_id = ?event.session_id,
+        client_ip = event.client.ip.as_deref().unwrap_or("-"),
+        details = %event.details,
+        "安全事件"
+    );
+
+    sqlx::query!(
+        r#"
+INSERT INTO security_events (
+    event_type,
+    user_id,
+    session_id,
+    client_ip,
+    user_agent,
+    details
+)
+VALUES ($1, $2, $3, $4, $5, $6)
+        "#,
+        event.event_type,
+        event.user_id,
+        event.session_id,
+        event.client.ip,
+        event.client.user_agent,
+        event.details,
+    )
+    .execute(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("写入安全事件失败: {e}")))?;
+
+    Ok(())
+}
@@ -1,38 +1,108 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgExecutor;
+use uuid::Uuid;
+
+use crate::api::etag::IfMatch;
 use crate::db::DbPool;
 use crate::error::AppError;
 
+/// 配置版本：全局配置（组织上下文再叠加该组织覆盖项）最近一次写入的时间，用于生成 ETag。
+pub async fn version<'e, E>(
+    executor: E,
+    organization_id: Option<Uuid>,
+) -> Result<DateTime<Utc>, AppError>
+where
+    E: PgExecutor<'e>,
+{
+    let version = sqlx::query_scalar!(
+        r#"
+SELECT GREATEST(
+    (SELECT MAX(updated_at) FROM system_config),
+    (SELECT MAX(updated_at) FROM organization_config WHERE organization_id = $1)
+)
+        "#,
+        organization_id,
+    )
+    .fetch_one(executor)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询配置版本失败: {e}")))?;
+    Ok(version.unwrap_or(DateTime::UNIX_EPOCH))
+}
+
 /// 批量 upsert system_config。
 ///
 /// - 仅更新 key/value/updated_at，不改动 description
-/// - 以事务包裹，保证同一次 PATCH 要么全部成功要么全部失败
+/// - 写入调用方的事务，由调用方提交，保证同一次 PATCH（含随之进行的密钥轮换）要么全部成功要么全部失败
+/// - 锁表后校验 `If-Match`，并发 PATCH 依次执行，后到者看到新版本
 pub async fn upsert_many(
-    db: &DbPool,
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
     changes: Vec<(String, serde_json::Value)>,
+    if_match: &IfMatch,
 ) -> Result<(), AppError> {
-    if changes.is_empty() {
-        return Ok(());
+    sqlx::query!("LOCK TABLE system_config IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("锁定 system_config 失败: {e}")))?;
+    if_match.ensure(version(&mut **tx, None).await?, "配置")?;
+
+    for (key, value) in changes {
+        sqlx::query!(
+            r#"
+INSERT INTO system_config (key, value)
+VALUES ($1, $2)
+ON CONFLICT (key) DO UPDATE
+SET value = EXCLUDED.value,
+    updated_at = NOW()
+            "#,
+            key,
+            value,
+        )
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("写入 system_config 失败: {e}")))?;
     }
 
+    Ok(())
+}
+
+/// 批量 upsert 组织级配置覆盖（`organization_config`），语义与 [`upsert_many`] 一致。
+pub async fn upsert_organization_many(
+    db: &DbPool,
+    organization_id: Uuid,
+    changes: Vec<(String, serde_json::Value)>,
+    if_match: &IfMatch,
+) -> Result<(), AppError> {
     let mut tx = db
         .begin()
         .await
         .map_err(|e| AppError::InternalError(format!("开启事务失败: {e}")))?;
 
+    sqlx::query!("LOCK TABLE organization_config IN SHARE ROW EXCLUSIVE MODE")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("锁定 organization_config 失败: {e}")))?;
+    sqlx::query!("LOCK TABLE system_config IN SHARE MODE")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("锁定 system_config 失败: {e}")))?;
+    if_match.ensure(version(&mut *tx, Some(organization_id)).await?, "配置")?;
+
     for (key, value) in changes {
         sqlx::query!(
             r#"
-INSERT INTO system_config (key, value)
-VALUES ($1, $2)
-ON CONFLICT (key) DO UPDATE
+INSERT INTO organization_config (organization_id, key, value)
+VALUES ($1, $2, $3)
+ON CONFLICT (organization_id, key) DO UPDATE
 SET value = EXCLUDED.value,
     updated_at = NOW()
             "#,
+            organization_id,
             key,
             value,
         )
         .execute(&mut *tx)
         .await
-        .map_err(|e| AppError::InternalError(format!("写入 system_config 失败: {e}")))?;
+        .map_err(|e| AppError::InternalError(format!("写入 organization_config 失败: {e}")))?;
     }
 
     tx.commit()
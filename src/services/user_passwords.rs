@@ -0,0 +1,82 @@
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::error::AppError;
+use crate::services::impersonation::{self, RevokedSession};
+
+/// 写入新密码哈希并撤销该用户全部会话，其中的模拟登录会话记录结束事件。
+///
+/// 同时递增 `auth_version`，使已签发的 access token 立即失效；`must_change` 为 `true` 时
+/// 用户下次登录只能获得受限会话，直到自行修改密码。
+///
+/// 密码只能用于用户名登录，未设置用户名的用户返回参数错误。
+pub async fn set_password_and_revoke_sessions(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    user_id: Uuid,
+    password_hash: &str,
+    must_change: bool,
+    revoked_reason: &str,
+    client: &ClientInfo,
+) -> Result<(), AppError> {
+    let has_username = sqlx::query_scalar!(
+        r#"
+SELECT username IS NOT NULL AS "has_username!"
+FROM users
+WHERE id = $1
+  AND deleted_at IS NULL
+FOR UPDATE
+        "#,
+        user_id,
+    )
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("查询用户失败: {e}")))?
+    .ok_or_else(|| AppError::NotFound(format!("用户不存在: {user_id}")))?;
+    if !has_username {
+        return Err(AppError::validation(
+            "用户未设置用户名，请先设置用户名再设置密码",
+        ));
+    }
+
+    let updated = sqlx::query!(
+        r#"
+UPDATE users
+SET password_hash = $2,
+    password_must_change = $3,
+    auth_version = auth_version + 1,
+    updated_at = NOW()
+WHERE id = $1
+  AND deleted_at IS NULL
+        "#,
+        user_id,
+        password_hash,
+        must_change,
+    )
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("更新用户密码失败: {e}")))?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::NotFound(format!("用户不存在: {user_id}")));
+    }
+
+    let revoked = sqlx::query_as!(
+        RevokedSession,
+        r#"
+UPDATE auth_sessions
+SET revoked_at = NOW(),
+    revoked_reason = $2,
+    updated_at = NOW()
+WHERE user_id = $1
+  AND revoked_at IS NULL
+RETURNING id, user_id, impersonator_id
+        "#,
+        user_id,
+        revoked_reason,
+    )
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| AppError::InternalError(format!("撤销用户会话失败: {e}")))?;
+    impersonation::record_ended(tx, &revoked, client, revoked_reason).await?;
+
+    Ok(())
+}
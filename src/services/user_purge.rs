@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use sqlx::PgConnection;
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+
+use crate::api::client_info::ClientInfo;
+use crate::db::DbPool;
+use crate::error::AppError;
+use crate::http::router::AppState;
+use crate::services::impersonation;
+use crate::services::login_throttle;
+use crate::services::security_events::{self, SecurityEvent};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// 每个事务最多清除的用户数，避免一次删除过多行长时间持锁。
+const PURGE_BATCH_SIZE: i64 = 500;
+
+/// 启动后台任务：按 `users.purge_after_days` 定期物理删除超过保留期的已删除用户，
+/// 清理已失效的登录失败计数（`auth_login_throttles`），并为到期的模拟登录会话补记结束事件。
+///
+/// 首次执行推迟一个间隔，启动后不会立即删除数据；多实例同时运行时以 `SKIP LOCKED` 错开，不会重复清除。
+pub fn spawn(state: AppState) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval_at(tokio::time::Instant::now() + PURGE_INTERVAL, PURGE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let cfg = state.config.load_full();
+            match login_throttle::delete_expired(&state.db, &cfg.security.login_throttle).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "已清理过期的登录失败计数"),
+                Err(e) => tracing::error!(error = %e, "清理登录失败计数失败"),
+            }
+            match impersonation::end_expired(&state.db).await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "已结束到期的模拟登录会话"),
+                Err(e) => tracing::error!(error = %e, "结束到期模拟登录会话失败"),
+            }
+
+            let purge_after_days = cfg.users.purge_after_days;
+            if purge_after_days == 0 {
+                continue;
+            }
+            match purge_expired(&state.db, purge_after_days).await {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::info!(count, purge_after_days, "已清除超过保留期的已删除用户")
+                }
+                Err(e) => tracing::error!(error = %e, "清除已删除用户失败"),
+            }
+        }
+    })
+}
+
+/// 物理删除 `deleted_at` 早于保留期的用户，返回清除数量。
+///
+/// 清除方式见 [`delete_users`]。
+pub async fn purge_expired(db: &DbPool, purge_after_days: u64) -> Result<u64, AppError> {
+    let purge_after_days = i32::try_from(purge_after_days)
+        .map_err(|_| AppError::InternalError(format!("保留天数超出范围: {purge_after_days}")))?;
+    let client = ClientInfo::default();
+    let mut purged = 0;
+    loop {
+        let mut tx = db
+            .begin()
+            .await
+            .map_err(|e| AppError::InternalError(format!("开启清除用户事务失败: {e}")))?;
+
+        let user_ids = sqlx::query_scalar!(
+            r#"
+SELECT id
+FROM users
+WHERE deleted_at < NOW() - make_interval(days => $1)
+ORDER BY deleted_at
+LIMIT $2
+FOR UPDATE SKIP LOCKED
+            "#,
+            purge_after_days,
+            PURGE_BATCH_SIZE,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| AppError::InternalError(format!("查询待清除用户失败: {e}")))?;
+        delete_users(&mut tx, &user_ids).await?;
+
+        for user_id in &user_ids {
+            security_events::record(
+                &mut *tx,
+                SecurityEvent {
+                    event_type: security_events::USER_PURGED,
+                    user_id: None,
+                    session_id: None,
+                    client: &client,
+                    details: serde_json::json!({
+                        "purged_user_id": user_id,
+                        "reason": "retention",
+                        "purge_after_days": purge_after_days,
+                    }),
+                },
+            )
+            .await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| AppError::InternalError(format!("提交清除用户事务失败: {e}")))?;
+
+        purged += user_ids.len() as u64;
+        if (user_ids.len() as i64) < PURGE_BATCH_SIZE {
+            return Ok(purged);
+        }
+    }
+}
+
+/// 物理删除用户并抹去其在审计表中的可识别信息。
+///
+/// 会话、令牌、MFA 与成员关系随外键级联删除；`security_events` 只保留事件类型与时间，
+/// 客户端 IP、User-Agent 与 `details` 一并清空，`user_id` 随外键置空；
+/// 以其用户名、邮箱或手机号为键的登录失败计数同时删除。
+pub async fn delete_users(conn: &mut PgConnection, user_ids: &[Uuid]) -> Result<(), AppError> {
+    if user_ids.is_empty() {
+        return Ok(());
+    }
+
+    sqlx::query!(
+        r#"
+UPDATE security_events
+SET client_ip = NULL,
+    user_agent = NULL,
+    details = '{}'::jsonb
+WHERE user_id = ANY($1)
+        "#,
+        user_ids,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理已清除用户的安全事件失败: {e}")))?;
+
+    sqlx::query!(
+        r#"
+DELETE FROM auth_login_throttles
+WHERE scope = 'identifier'
+  AND key IN (
+      SELECT lower(btrim(identifier))
+      FROM users u
+      CROSS JOIN LATERAL unnest(ARRAY[u.username, u.email, u.phone]) AS identifier
+      WHERE u.id = ANY($1)
+        AND identifier IS NOT NULL
+  )
+        "#,
+        user_ids,
+    )
+    .execute(&mut *conn)
+    .await
+    .map_err(|e| AppError::InternalError(format!("清理已清除用户的登录失败计数失败: {e}")))?;
+
+    sqlx::query!("DELETE FROM users WHERE id = ANY($1)", user_ids)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::InternalError(format!("清除用户失败: {e}")))?;
+
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use anyhow::{anyhow, Result};
+use argon2::password_hash::rand_core::{OsRng, RngCore};
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+
+use crate::crypto::constant_time_eq;
+
+/// TOTP（RFC 6238）参数：HMAC-SHA1、30 秒步长、6 位数字，与主流验证器 App 默认值一致。
+pub const STEP_SECS: i64 = 30;
+pub const DIGITS: u32 = 6;
+/// 校验时允许的前后步长偏差，用于容忍客户端时钟漂移。
+pub const ALLOWED_SKEW_STEPS: i64 = 1;
+
+const SECRET_BYTES: usize = 20;
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+pub fn generate_secret() -> Vec<u8> {
+    let mut bytes = vec![0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+pub fn step_at(unix_secs: i64) -> i64 {
+    unix_secs.div_euclid(STEP_SECS)
+}
+
+pub fn code_at_step(secret: &[u8], step: i64) -> Result<String> {
+    let mut mac =
+        Hmac::<Sha1>::new_from_slice(secret).map_err(|e| anyhow!("初始化 HMAC 失败: {e}"))?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    let code = binary % 10u32.pow(DIGITS);
+    Ok(format!("{code:0width$}", width = DIGITS as usize))
+}
+
+/// 在允许的时钟偏差内查找与 `code` 匹配的步长；返回匹配到的步长，供调用方做防重放记录。
+pub fn find_matching_step(secret: &[u8], code: &str, unix_secs: i64) -> Result<Option<i64>> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let current = step_at(unix_secs);
+    for step in (current - ALLOWED_SKEW_STEPS)..=(current + ALLOWED_SKEW_STEPS) {
+        if constant_time_eq(code_at_step(secret, step)?.as_bytes(), code.as_bytes()) {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+/// 生成验证器 App 可识别的 `otpauth://` URI。
+pub fn otpauth_uri(issuer: &str, account: &str, secret: &[u8]) -> String {
+    let issuer = percent_encode(issuer);
+    let account = percent_encode(account);
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={}&issuer={issuer}&algorithm=SHA1&digits={DIGITS}&period={STEP_SECS}",
+        base32_encode(secret)
+    )
+}
+
+pub fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &b in bytes {
+        buffer = (buffer << 8) | b as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+pub fn base32_decode(input: &str) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let c = c.to_ascii_uppercase();
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or_else(|| anyhow!("base32 字符不合法: {c}"))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push(((buffer >> bits) & 0xff) as u8);
+        }
+    }
+    Ok(out)
+}
+
+fn percent_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for b in input.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{b:02X}"));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 附录 B 的 SHA1 测试向量（取后 6 位）。
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn should_match_rfc6238_test_vectors() {
+        assert_eq!(code_at_step(RFC_SECRET, step_at(59)).unwrap(), "287082");
+        assert_eq!(
+            code_at_step(RFC_SECRET, step_at(1_111_111_109)).unwrap(),
+            "081804"
+        );
+        assert_eq!(
+            code_at_step(RFC_SECRET, step_at(2_000_000_000)).unwrap(),
+            "279037"
+        );
+    }
+
+    #[test]
+    fn should_accept_adjacent_step_and_reject_far_step() {
+        let now = 1_111_111_109;
+        let next = code_at_step(RFC_SECRET, step_at(now) + 1).unwrap();
+        let far = code_at_step(RFC_SECRET, step_at(now) + 3).unwrap();
+
+        assert_eq!(
+            find_matching_step(RFC_SECRET, &next, now).unwrap(),
+            Some(step_at(now) + 1)
+        );
+        assert_eq!(find_matching_step(RFC_SECRET, &far, now).unwrap(), None);
+    }
+
+    #[test]
+    fn should_roundtrip_base32() {
+        let encoded = base32_encode(RFC_SECRET);
+        assert_eq!(encoded, "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(base32_decode(&encoded).unwrap(), RFC_SECRET);
+    }
+}